@@ -0,0 +1,25 @@
+/*
+The Coulomb3D physics core. Everything in here is plain number crunching with no graphics
+dependency, so it can be used from the GUI, from scripts, or on a headless CI machine.
+Vector algebra lives in math/algebra.rs and all physical calculations are in math/physics.rs;
+they are gathered in math/mod.rs and re-exported from the crate root. Scene files (charge
+configurations saved on disk) are handled in scene.rs, and export to VTK for ParaView in vtk.rs.
+*/
+pub mod math;
+pub mod scene;
+pub mod vtk;
+
+pub use math::*;
+
+/// A point charge `charge` [C] located at `position` [m].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Charge {
+    pub charge: f64,
+    pub position: (f32, f32, f32),
+}
+
+impl Charge {
+    pub fn new(charge: f64, position: (f32, f32, f32)) -> Self {
+        Self { charge, position }
+    }
+}
@@ -0,0 +1,104 @@
+pub mod vector {
+    use std::ops::{Add, AddAssign, Sub};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Vector3D {
+        x: f64,
+        y: f64,
+        z: f64,
+    }
+
+    impl Vector3D {
+        pub fn new(x: f64, y: f64, z: f64) -> Self {
+            Self { x, y, z }
+        }
+        pub fn dot_product(&self, vec2: &Vector3D) -> f64 {
+            self.x * vec2.x + self.y * vec2.y + self.z * vec2.z
+        }
+        pub fn scalar_product(&self, s: &f64) -> Self {
+            Self {
+                x: s * self.x,
+                y: s * self.y,
+                z: s * self.z,
+            }
+        }
+        pub fn cross_product(&self, vec2: &Vector3D) -> Self {
+            Self {
+                x: self.y * vec2.z - self.z * vec2.y,
+                y: self.z * vec2.x - self.x * vec2.z,
+                z: self.x * vec2.y - self.y * vec2.x,
+            }
+        }
+        pub fn magnitude(&self) -> f64 {
+            self.dot_product(self).powf(0.5)
+        }
+        /// Unit vector along `self`; the zero vector stays zero.
+        pub fn normalized(&self) -> Self {
+            let m = self.magnitude();
+            if m == 0.0 { *self } else { self.scalar_product(&(1.0 / m)) }
+        }
+        /// Return the (x, y, z) components as a plain tuple.
+        pub fn components(&self) -> (f64, f64, f64) {
+            (self.x, self.y, self.z)
+        }
+    }
+
+    impl Sub for Vector3D {
+        type Output = Vector3D;
+        fn sub(self, rhs: Self) -> Self::Output {
+            Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+        }
+    }
+
+    impl Add for Vector3D {
+        type Output = Vector3D;
+        fn add(self, rhs: Self) -> Self::Output {
+            Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+        }
+    }
+
+    impl AddAssign for Vector3D {
+        fn add_assign(&mut self, rhs: Self) {
+            self.x += rhs.x;
+            self.y += rhs.y;
+            self.z += rhs.z;
+        }
+    }
+
+    impl std::fmt::Display for Vector3D {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "( {}, \n{}, \n{})", self.x, self.y, self.z)
+        }
+    }
+}
+
+pub mod quadrature {
+    /// Gauss–Legendre nodes and weights on [−1, 1], found by Newton iteration on Pₙ.
+    /// Exact for polynomials up to degree 2n − 1.
+    pub fn gauss_legendre(n: usize) -> Vec<(f64, f64)> {
+        let mut nodes = Vec::with_capacity(n);
+        for i in 0..n {
+            // Chebyshev-like initial guess for the i-th root
+            let mut x = (std::f64::consts::PI * (i as f64 + 0.75) / (n as f64 + 0.5)).cos();
+            let mut dp = 1.0;
+            for _ in 0..100 {
+                // Recurrence: (k+1) P_{k+1} = (2k+1) x P_k − k P_{k−1}
+                let (mut p0, mut p1) = (1.0, x);
+                for k in 1..n {
+                    let p2 = ((2 * k + 1) as f64 * x * p1 - k as f64 * p0) / (k + 1) as f64;
+                    p0 = p1;
+                    p1 = p2;
+                }
+                // p1 = Pₙ(x), p0 = Pₙ₋₁(x)
+                dp = n as f64 * (x * p1 - p0) / (x * x - 1.0);
+                let dx = p1 / dp;
+                x -= dx;
+                if dx.abs() < 1e-15 {
+                    break;
+                }
+            }
+            nodes.push((x, 2.0 / ((1.0 - x * x) * dp * dp)));
+        }
+        nodes
+    }
+}
@@ -0,0 +1,60 @@
+/*
+How the field of the point charges is evaluated. Direct summation is exact and costs O(N) per
+query; the tree-based backends trade a controlled error for speed on large charge clouds. A
+`PointCharges` built for the chosen backend is a `FieldSource` like any other, so it drops into
+a `Superposition` in place of the bare `Vec<Charge>`.
+*/
+use crate::math::{BarnesHut, FieldSource, Fmm, Vector3D};
+use crate::Charge;
+
+/// Field evaluation method for point charges.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FieldBackend {
+    /// Sum over every charge
+    #[default]
+    Direct,
+    /// Barnes–Hut octree with opening angle `theta`
+    BarnesHut { theta: f64 },
+    /// Fast multipole method with expansion order `order`
+    Fmm { order: usize },
+}
+
+/// A set of point charges prepared for a particular `FieldBackend`.
+pub enum PointCharges {
+    Direct(Vec<Charge>),
+    BarnesHut(BarnesHut),
+    Fmm(Fmm),
+}
+
+impl PointCharges {
+    pub fn new(charges: &[Charge], backend: FieldBackend) -> Self {
+        match backend {
+            FieldBackend::Direct => PointCharges::Direct(charges.to_vec()),
+            FieldBackend::BarnesHut { theta } => PointCharges::BarnesHut(BarnesHut::new(charges, theta)),
+            FieldBackend::Fmm { order } => PointCharges::Fmm(Fmm::new(charges, order)),
+        }
+    }
+
+    fn source(&self) -> &dyn FieldSource {
+        match self {
+            PointCharges::Direct(charges) => charges,
+            PointCharges::BarnesHut(tree) => tree,
+            PointCharges::Fmm(fmm) => fmm,
+        }
+    }
+}
+
+impl FieldSource for PointCharges {
+    fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+        self.source().potential(s)
+    }
+    fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+        self.source().field(s)
+    }
+    fn absorbs(&self, s: &(f32, f32, f32)) -> bool {
+        self.source().absorbs(s)
+    }
+    fn extent(&self) -> f32 {
+        self.source().extent()
+    }
+}
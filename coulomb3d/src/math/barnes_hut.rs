@@ -0,0 +1,241 @@
+/*
+Barnes–Hut evaluation of the potential and field of many point charges. The charges are sorted
+into an octree; each cell stores its total charge and its dipole moment about its "centre of
+charge" (the |q|-weighted mean position, so cells with mixed signs still get a sensible centre).
+A query walks the tree and treats a cell of side s at distance d as a single multipole whenever
+s / d < θ, opening it otherwise. θ = 0 opens every cell and reproduces direct summation; θ ≈ 0.5
+is a good default. Cost per query drops from O(N) to O(log N) for well-separated clouds.
+
+d is measured to the nearest point of the cell, not to its centre of charge. A query point
+inside a cell (or just outside it, next to its charges) can be far from a lopsided cell's centre
+of charge, and with θ above about 0.58 it would otherwise be handed an expansion that diverges
+there. Measured to the cell every accepted expansion is evaluated at least s/θ away from all of
+its charges, so θ up to 1 stays safe; larger values are clamped to 1.
+
+The dipole term is kept because the charges can have either sign: a cell that is nearly neutral
+overall is dominated by its dipole, which a monopole-only tree would miss entirely.
+*/
+use crate::math::{FieldSource, Vector3D, ABSORB_RADIUS, K};
+use crate::Charge;
+
+/// Cells holding this many charges or fewer are not split further.
+const LEAF_SIZE: usize = 8;
+/// Guard against endless splitting when many charges share one position.
+const MAX_DEPTH: usize = 32;
+
+struct Cell {
+    /// Geometric centre and half-size of the cubic cell
+    center: Vector3D,
+    half: f64,
+    /// Total charge, expansion centre, and dipole moment about that centre
+    charge: f64,
+    expansion: Vector3D,
+    dipole: Vector3D,
+    /// Range of `BarnesHut::bodies` inside this cell
+    first: usize,
+    last: usize,
+    /// Indices into `BarnesHut::cells`; empty for leaves
+    children: Vec<usize>,
+}
+
+/// An octree over a set of point charges, usable wherever a `FieldSource` is.
+pub struct BarnesHut {
+    /// Opening angle θ ∈ [0, 1]: cells with side / distance to the cell < θ are approximated
+    pub theta: f64,
+    bodies: Vec<(Vector3D, f64)>,
+    cells: Vec<Cell>,
+}
+
+impl BarnesHut {
+    /// Build the tree for `charges` with opening angle `theta`.
+    pub fn new(charges: &[Charge], theta: f64) -> Self {
+        let mut bodies: Vec<(Vector3D, f64)> = charges
+            .iter()
+            .map(|c| {
+                let (x, y, z) = c.position;
+                (Vector3D::new(x as f64, y as f64, z as f64), c.charge)
+            })
+            .collect();
+        let mut tree = Self { theta: theta.clamp(0.0, 1.0), bodies: Vec::new(), cells: Vec::new() };
+        if bodies.is_empty() {
+            return tree;
+        }
+
+        // Root cube: the bounding box of all charges, made cubic
+        let (mut lo, mut hi) = ([f64::INFINITY; 3], [f64::NEG_INFINITY; 3]);
+        for (p, _) in &bodies {
+            let (x, y, z) = p.components();
+            for (a, v) in [x, y, z].into_iter().enumerate() {
+                lo[a] = lo[a].min(v);
+                hi[a] = hi[a].max(v);
+            }
+        }
+        let center = Vector3D::new((lo[0] + hi[0]) / 2.0, (lo[1] + hi[1]) / 2.0, (lo[2] + hi[2]) / 2.0);
+        let half = (0..3).fold(0.0f64, |acc, a| acc.max((hi[a] - lo[a]) / 2.0)).max(1e-9);
+
+        let n = bodies.len();
+        tree.build(&mut bodies, 0, n, center, half, 0);
+        tree.bodies = bodies;
+        tree
+    }
+
+    /// Number of charges in the tree.
+    pub fn len(&self) -> usize {
+        self.bodies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bodies.is_empty()
+    }
+
+    /// Create the cell for `bodies[first..last]` and, unless it is small enough to be
+    /// a leaf, its children. Returns the index of the new cell.
+    fn build(
+        &mut self,
+        bodies: &mut [(Vector3D, f64)],
+        first: usize,
+        last: usize,
+        center: Vector3D,
+        half: f64,
+        depth: usize,
+    ) -> usize {
+        let slice = &bodies[first..last];
+        let charge: f64 = slice.iter().map(|(_, q)| q).sum();
+        let weight: f64 = slice.iter().map(|(_, q)| q.abs()).sum();
+        let expansion = if weight > 0.0 {
+            let mut c = Vector3D::new(0.0, 0.0, 0.0);
+            for (p, q) in slice {
+                c += p.scalar_product(&(q.abs() / weight));
+            }
+            c
+        } else {
+            center
+        };
+        let mut dipole = Vector3D::new(0.0, 0.0, 0.0);
+        for (p, q) in slice {
+            dipole += (*p - expansion).scalar_product(q);
+        }
+
+        let index = self.cells.len();
+        self.cells.push(Cell { center, half, charge, expansion, dipole, first, last, children: Vec::new() });
+        if last - first <= LEAF_SIZE || depth >= MAX_DEPTH {
+            return index;
+        }
+
+        // Group the bodies by octant, then recurse into each non-empty group
+        let (cx, cy, cz) = center.components();
+        let octant = |p: &Vector3D| {
+            let (x, y, z) = p.components();
+            (x >= cx) as usize | ((y >= cy) as usize) << 1 | ((z >= cz) as usize) << 2
+        };
+        bodies[first..last].sort_by_key(|(p, _)| octant(p));
+        let mut children = Vec::new();
+        let mut start = first;
+        while start < last {
+            let o = octant(&bodies[start].0);
+            let mut end = start;
+            while end < last && octant(&bodies[end].0) == o {
+                end += 1;
+            }
+            let h = half / 2.0;
+            let sign = |bit: usize| if o & bit != 0 { h } else { -h };
+            let child_center = center + Vector3D::new(sign(1), sign(2), sign(4));
+            children.push(self.build(bodies, start, end, child_center, h, depth + 1));
+            start = end;
+        }
+        self.cells[index].children = children;
+        index
+    }
+
+    /// Walk the tree from the root and call `visit(d, q, p)` for every term of the
+    /// sum: each cell accepted as a multipole (charge q, dipole p) and each body in
+    /// the leaves that had to be opened (p = 0). `d` points from the term to `s`.
+    fn walk(&self, s: Vector3D, mut visit: impl FnMut(Vector3D, f64, Vector3D)) {
+        if self.cells.is_empty() {
+            return;
+        }
+        let zero = Vector3D::new(0.0, 0.0, 0.0);
+        let mut stack = vec![0];
+        while let Some(i) = stack.pop() {
+            let cell = &self.cells[i];
+            if 2.0 * cell.half < self.theta * distance_to_cell(cell, s) {
+                visit(s - cell.expansion, cell.charge, cell.dipole);
+            } else if cell.children.is_empty() {
+                for &(p, q) in &self.bodies[cell.first..cell.last] {
+                    visit(s - p, q, zero);
+                }
+            } else {
+                stack.extend(&cell.children);
+            }
+        }
+    }
+}
+
+/// Distance from `s` to the nearest point of the cell's cube, 0 inside it.
+fn distance_to_cell(cell: &Cell, s: Vector3D) -> f64 {
+    let (dx, dy, dz) = (s - cell.center).components();
+    let outside = |d: f64| (d.abs() - cell.half).max(0.0);
+    (outside(dx).powi(2) + outside(dy).powi(2) + outside(dz).powi(2)).sqrt()
+}
+
+fn to_vector(s: &(f32, f32, f32)) -> Vector3D {
+    Vector3D::new(s.0 as f64, s.1 as f64, s.2 as f64)
+}
+
+impl FieldSource for BarnesHut {
+    fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+        // ϕ = K ∑ (q/r + p·d/r³)
+        let mut phi = 0.0;
+        let zero = Vector3D::new(0.0, 0.0, 0.0);
+        self.walk(to_vector(s), |d, q, p| {
+            let r = d.magnitude();
+            phi += q / r;
+            // bodies carry no dipole; skipping the term keeps ϕ = ±∞ (not NaN) on top of one
+            if p != zero {
+                phi += p.dot_product(&d) / r.powi(3);
+            }
+        });
+        K * phi
+    }
+
+    fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+        // E = K ∑ (q d/r³ + 3(p·d) d/r⁵ − p/r³)
+        let mut e = Vector3D::new(0.0, 0.0, 0.0);
+        self.walk(to_vector(s), |d, q, p| {
+            let r = d.magnitude();
+            let r3 = r.powi(3);
+            e += d.scalar_product(&(q / r3 + 3.0 * p.dot_product(&d) / (r3 * r * r)));
+            e += p.scalar_product(&(-1.0 / r3));
+        });
+        e.scalar_product(&K)
+    }
+
+    fn absorbs(&self, s: &(f32, f32, f32)) -> bool {
+        // Only cells whose box comes within the absorb radius can hold a nearby charge
+        let s = to_vector(s);
+        let radius = ABSORB_RADIUS as f64;
+        let mut stack = if self.cells.is_empty() { vec![] } else { vec![0] };
+        while let Some(i) = stack.pop() {
+            let cell = &self.cells[i];
+            let (dx, dy, dz) = (s - cell.center).components();
+            if dx.abs().max(dy.abs()).max(dz.abs()) > cell.half + radius {
+                continue;
+            }
+            if cell.children.is_empty() {
+                if self.bodies[cell.first..cell.last].iter().any(|(p, _)| (s - *p).magnitude() < radius) {
+                    return true;
+                }
+            } else {
+                stack.extend(&cell.children);
+            }
+        }
+        false
+    }
+
+    fn extent(&self) -> f32 {
+        self.bodies.iter().fold(0.0f32, |acc, (p, _)| {
+            let (x, y, z) = p.components();
+            acc.max(x.abs().max(y.abs()).max(z.abs()) as f32)
+        })
+    }
+}
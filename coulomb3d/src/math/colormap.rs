@@ -0,0 +1,141 @@
+/*
+Colour maps for scalar data (slice heatmaps, coloured field lines, colour bars). A `ColorScale` turns a value into a
+colour in two steps: the value is normalised to t ∈ [0, 1] on a linear or logarithmic scale, then
+looked up in a `Colormap`. Colours are RGBA in [0, 1], the layout a scene file uses for charge
+colours; the GUI converts them to its own colour type.
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colormap {
+    /// Perceptually uniform dark-blue → green → yellow
+    Viridis,
+    /// Perceptually uniform dark-blue → magenta → yellow
+    Plasma,
+    /// Blue → white → red, centred on zero
+    Diverging,
+}
+
+/// Anchor colours sampled evenly along each map; intermediate values are interpolated.
+const VIRIDIS: [(f32, f32, f32); 9] = [
+    (0.267, 0.005, 0.329), (0.283, 0.141, 0.458), (0.254, 0.265, 0.530),
+    (0.207, 0.372, 0.553), (0.164, 0.471, 0.558), (0.128, 0.567, 0.551),
+    (0.135, 0.659, 0.518), (0.478, 0.821, 0.319), (0.993, 0.906, 0.144),
+];
+const PLASMA: [(f32, f32, f32); 11] = [
+    (0.051, 0.031, 0.529), (0.255, 0.016, 0.616), (0.416, 0.000, 0.659),
+    (0.561, 0.051, 0.643), (0.694, 0.165, 0.565), (0.800, 0.278, 0.471),
+    (0.882, 0.392, 0.384), (0.949, 0.518, 0.294), (0.988, 0.651, 0.212),
+    (0.988, 0.808, 0.145), (0.941, 0.976, 0.129),
+];
+const DIVERGING: [(f32, f32, f32); 5] = [
+    (0.230, 0.299, 0.754), (0.552, 0.690, 0.996), (0.865, 0.865, 0.865),
+    (0.958, 0.604, 0.482), (0.706, 0.016, 0.150),
+];
+
+impl Colormap {
+    /// Opaque RGBA colour at t ∈ [0, 1] (clamped).
+    pub fn color(&self, t: f32) -> [f32; 4] {
+        let anchors: &[(f32, f32, f32)] = match self {
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Plasma => &PLASMA,
+            Colormap::Diverging => &DIVERGING,
+        };
+        let x = t.clamp(0.0, 1.0) * (anchors.len() - 1) as f32;
+        let i = (x.floor() as usize).min(anchors.len() - 2);
+        let f = x - i as f32;
+        let (a, b) = (anchors[i], anchors[i + 1]);
+        [a.0 + (b.0 - a.0) * f, a.1 + (b.1 - a.1) * f, a.2 + (b.2 - a.2) * f, 1.0]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scale {
+    Linear,
+    /// log₁₀ for positive data; a signed log (sign · log(1 + |v|/floor)) when
+    /// the range straddles zero
+    Log,
+}
+
+/// Maps values in `lo..=hi` onto a colour map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorScale {
+    pub colormap: Colormap,
+    pub scale: Scale,
+    pub lo: f64,
+    pub hi: f64,
+}
+
+/// Dynamic range shown by logarithmic scales, in decades.
+const LOG_DECADES: f64 = 4.0;
+
+impl ColorScale {
+    /// A diverging map is always made symmetric about zero so white means zero.
+    pub fn new(colormap: Colormap, scale: Scale, lo: f64, hi: f64) -> Self {
+        let (lo, hi) = if colormap == Colormap::Diverging {
+            let m = lo.abs().max(hi.abs());
+            (-m, m)
+        } else {
+            (lo, hi)
+        };
+        Self { colormap, scale, lo, hi }
+    }
+
+    fn signed(&self) -> bool {
+        self.lo < 0.0
+    }
+
+    /// Normalised position of `v` in [0, 1].
+    pub fn normalize(&self, v: f64) -> f32 {
+        if !v.is_finite() {
+            return if v > 0.0 { 1.0 } else { 0.0 };
+        }
+        let t = match self.scale {
+            Scale::Linear => {
+                if self.hi > self.lo { (v - self.lo) / (self.hi - self.lo) } else { 0.5 }
+            }
+            Scale::Log if self.signed() => {
+                let m = self.lo.abs().max(self.hi.abs());
+                let floor = m * 10f64.powf(-LOG_DECADES);
+                let s = |x: f64| x.signum() * (1.0 + x.abs() / floor).log10();
+                let (a, b) = (s(self.lo), s(self.hi));
+                if b > a { (s(v) - a) / (b - a) } else { 0.5 }
+            }
+            Scale::Log => {
+                let hi = self.hi.max(f64::MIN_POSITIVE);
+                let lo = self.lo.max(hi * 10f64.powf(-LOG_DECADES));
+                if hi > lo { (v.max(lo).log10() - lo.log10()) / (hi.log10() - lo.log10()) } else { 0.5 }
+            }
+        };
+        t.clamp(0.0, 1.0) as f32
+    }
+
+    /// The value that normalises to `t`, used to label colour bars.
+    pub fn value_at(&self, t: f32) -> f64 {
+        let t = t as f64;
+        match self.scale {
+            Scale::Linear => self.lo + t * (self.hi - self.lo),
+            Scale::Log if self.signed() => {
+                let m = self.lo.abs().max(self.hi.abs());
+                let floor = m * 10f64.powf(-LOG_DECADES);
+                let s = |x: f64| x.signum() * (1.0 + x.abs() / floor).log10();
+                let y = s(self.lo) + t * (s(self.hi) - s(self.lo));
+                y.signum() * (10f64.powf(y.abs()) - 1.0) * floor
+            }
+            Scale::Log => {
+                let hi = self.hi.max(f64::MIN_POSITIVE);
+                let lo = self.lo.max(hi * 10f64.powf(-LOG_DECADES));
+                10f64.powf(lo.log10() + t * (hi.log10() - lo.log10()))
+            }
+        }
+    }
+
+    pub fn color(&self, v: f64) -> [f32; 4] {
+        self.colormap.color(self.normalize(v))
+    }
+}
+
+/// Fixed scale for the relative error of the multipole approximation: four decades
+/// from 1e-4 (dark, expansion valid) to 1 (yellow, expansion useless).
+pub fn multipole_error_scale() -> ColorScale {
+    ColorScale::new(Colormap::Viridis, Scale::Log, 1e-4, 1.0)
+}
@@ -0,0 +1,280 @@
+/*
+Conductors by the method of images: grounded infinite planes and grounded or isolated
+conducting spheres. Each conductor replaces its induced surface charge by point charges hidden
+behind (or inside) its surface:
+
+    plane through a, normal n̂:   q at x   →  −q at x − 2((x − a)·n̂) n̂
+    sphere of radius R at c:      q at d   →  −qR/|d| at c + R² d/|d|²     (d = x − c)
+
+An isolated sphere must keep its total charge Q, so it also carries Q at its centre and every
+image it creates is balanced by the opposite charge at the centre; both leave the surface an
+equipotential. With several conductors the images are themselves reflected in the other
+conductors, generation after generation, until they become negligible.
+
+Only point charges are imaged: continuous distributions are not reflected. Values inside a
+conductor are not physical; field lines are absorbed there.
+*/
+use crate::math::distributions::plane_basis;
+use crate::math::{electric_field, electric_potential, FieldSource, Vector3D, EPSILON, PI};
+use crate::Charge;
+use serde::{Deserialize, Serialize};
+
+/// Image generations computed at most.
+const MAX_GENERATIONS: usize = 40;
+/// Images smaller than this fraction of the largest source charge are dropped.
+const IMAGE_CUTOFF: f64 = 1e-6;
+/// Upper bound on the number of images, which grows geometrically with the conductor count.
+const MAX_IMAGES: usize = 50_000;
+
+/// A conductor. Points and directions are (x, y, z) in metres; normals need not be unit length.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Conductor {
+    /// Grounded infinite plane through `point`; `normal` points into the region with the charges.
+    Plane { point: [f64; 3], normal: [f64; 3] },
+    /// Conducting sphere of `radius` [m], held at zero potential when `grounded`, otherwise
+    /// isolated with total `charge` [C].
+    Sphere { center: [f64; 3], radius: f64, grounded: bool, charge: f64 },
+}
+
+fn vector(a: [f64; 3]) -> Vector3D {
+    Vector3D::new(a[0], a[1], a[2])
+}
+
+fn query(s: &(f32, f32, f32)) -> Vector3D {
+    Vector3D::new(s.0 as f64, s.1 as f64, s.2 as f64)
+}
+
+fn point(v: Vector3D) -> (f32, f32, f32) {
+    let (x, y, z) = v.components();
+    (x as f32, y as f32, z as f32)
+}
+
+impl Conductor {
+    /// Signed distance from the surface, positive on the side where charges live.
+    pub fn distance(&self, p: Vector3D) -> f64 {
+        match *self {
+            Conductor::Plane { point, normal } => (p - vector(point)).dot_product(&vector(normal).normalized()),
+            Conductor::Sphere { center, radius, .. } => (p - vector(center)).magnitude() - radius,
+        }
+    }
+
+    /// Whether `p` is inside the conductor (behind a plane, inside a sphere).
+    pub fn contains(&self, p: Vector3D) -> bool {
+        self.distance(p) <= 0.0
+    }
+
+    /// Unit surface normal pointing out of the conductor, at the surface point nearest `p`.
+    pub fn normal_at(&self, p: Vector3D) -> Vector3D {
+        match *self {
+            Conductor::Plane { normal, .. } => vector(normal).normalized(),
+            Conductor::Sphere { center, .. } => {
+                let d = p - vector(center);
+                if d.magnitude() > 0.0 { d.normalized() } else { Vector3D::new(0.0, 0.0, 1.0) }
+            }
+        }
+    }
+
+    /// The surface point nearest `p`.
+    pub fn project(&self, p: Vector3D) -> Vector3D {
+        p - self.normal_at(p).scalar_product(&self.distance(p))
+    }
+
+    /// Largest |coordinate| of the conductor; a plane only contributes its anchor point.
+    pub fn extent(&self) -> f64 {
+        let reach = |c: [f64; 3], r: f64| c.iter().fold(0.0f64, |acc, x| acc.max(x.abs())) + r;
+        match *self {
+            Conductor::Plane { point, .. } => reach(point, 0.0),
+            Conductor::Sphere { center, radius, .. } => reach(center, radius),
+        }
+    }
+
+    /// Images of `q` needed to keep the surface an equipotential, or none when `q` is not
+    /// in front of the surface.
+    fn reflect(&self, q: &Charge) -> Vec<Charge> {
+        let x = query(&q.position);
+        if self.contains(x) {
+            return Vec::new();
+        }
+        match *self {
+            Conductor::Plane { normal, .. } => {
+                let n = vector(normal).normalized();
+                vec![Charge::new(-q.charge, point(x - n.scalar_product(&(2.0 * self.distance(x)))))]
+            }
+            Conductor::Sphere { center, radius, grounded, .. } => {
+                let c = vector(center);
+                let d = x - c;
+                let r2 = d.dot_product(&d);
+                let image = Charge::new(-q.charge * radius / r2.sqrt(), point(c + d.scalar_product(&(radius * radius / r2))));
+                if grounded {
+                    vec![image]
+                } else {
+                    let balance = Charge::new(-image.charge, point(c));
+                    vec![image, balance]
+                }
+            }
+        }
+    }
+}
+
+/// The image charges induced by a set of conductors on a set of point charges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageSystem {
+    pub conductors: Vec<Conductor>,
+    images: Vec<Charge>,
+}
+
+impl ImageSystem {
+    /// Build the images of `charges` in `conductors`. Charges inside a conductor are ignored.
+    pub fn new(charges: &[Charge], conductors: &[Conductor]) -> Self {
+        let inside = |c: &Charge| conductors.iter().any(|k| k.contains(query(&c.position)));
+
+        // Each generation remembers which conductor made it, which does not reflect it again
+        let mut generation: Vec<(Charge, Option<usize>)> =
+            charges.iter().filter(|c| !inside(c)).map(|c| (c.clone(), None)).collect();
+        let mut images = Vec::new();
+        for (i, k) in conductors.iter().enumerate() {
+            if let Conductor::Sphere { center, grounded: false, charge, .. } = *k {
+                if charge != 0.0 {
+                    let q = Charge::new(charge, point(vector(center)));
+                    images.push(q.clone());
+                    generation.push((q, Some(i)));
+                }
+            }
+        }
+
+        let largest = generation.iter().fold(0.0f64, |m, (c, _)| m.max(c.charge.abs()));
+        let cutoff = IMAGE_CUTOFF * largest;
+        for _ in 0..MAX_GENERATIONS {
+            let mut next = Vec::new();
+            for (q, made_by) in &generation {
+                for (i, k) in conductors.iter().enumerate() {
+                    if *made_by != Some(i) {
+                        next.extend(k.reflect(q).into_iter().map(|c| (c, Some(i))));
+                    }
+                }
+            }
+            next.retain(|(c, _)| c.charge.abs() > cutoff);
+            if next.is_empty() || images.len() + next.len() > MAX_IMAGES {
+                break;
+            }
+            images.extend(next.iter().map(|(c, _)| c.clone()));
+            generation = next;
+        }
+        Self { conductors: conductors.to_vec(), images }
+    }
+
+    /// The image charges, including the net charge at the centre of isolated spheres.
+    pub fn images(&self) -> &[Charge] {
+        &self.images
+    }
+}
+
+impl FieldSource for ImageSystem {
+    fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+        electric_potential(&self.images, s)
+    }
+    fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+        electric_field(&self.images, s)
+    }
+    fn absorbs(&self, s: &(f32, f32, f32)) -> bool {
+        self.conductors.iter().any(|k| k.contains(query(s)))
+    }
+    fn extent(&self) -> f32 {
+        self.conductors.iter().fold(0.0f64, |acc, k| acc.max(k.extent())) as f32
+    }
+}
+
+/// Induced surface charge density σ = ε₀ E·n̂ [C/m²] at the surface point nearest `s`.
+/// `field` must be the total field: real charges, other sources and the images.
+pub fn surface_charge_density(field: &dyn FieldSource, conductor: &Conductor, s: &(f32, f32, f32)) -> f64 {
+    let p = conductor.project(query(s));
+    EPSILON * field.field(&point(p)).dot_product(&conductor.normal_at(p))
+}
+
+/// A surface charge density sampled on a `resolution` × `resolution` grid of surface points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurfaceCharge {
+    pub resolution: usize,
+    /// Row-major surface points
+    pub points: Vec<(f32, f32, f32)>,
+    /// σ [C/m²] at each point
+    pub sigma: Vec<f64>,
+}
+
+/// An `n` × `n` grid on a sphere: polar angle along the rows (pole to pole), azimuth along
+/// the columns (the last one repeating the first).
+pub(crate) fn sphere_grid(center: Vector3D, radius: f64, n: usize) -> Vec<(f32, f32, f32)> {
+    let t = |i: usize| i as f64 / (n - 1) as f64;
+    (0..n * n)
+        .map(|at| {
+            let (th, ph) = (PI * t(at / n), 2.0 * PI * t(at % n));
+            let dir = Vector3D::new(th.sin() * ph.cos(), th.sin() * ph.sin(), th.cos());
+            point(center + dir.scalar_product(&radius))
+        })
+        .collect()
+}
+
+/// An `n` × `n` grid on the parallelogram c ± u ± v.
+pub(crate) fn rect_grid(c: Vector3D, u: Vector3D, v: Vector3D, n: usize) -> Vec<(f32, f32, f32)> {
+    let t = |i: usize| 2.0 * i as f64 / (n - 1) as f64 - 1.0;
+    (0..n * n)
+        .map(|at| point(c + u.scalar_product(&t(at / n)) + v.scalar_product(&t(at % n))))
+        .collect()
+}
+
+/// Sample the induced surface charge on `conductor`: a sphere all over, a plane over a
+/// square of `half_size` around its point nearest the origin.
+pub fn sample_surface_charge(
+    field: &dyn FieldSource,
+    conductor: &Conductor,
+    resolution: usize,
+    half_size: f64,
+) -> SurfaceCharge {
+    let n = resolution.max(2);
+    let points = match *conductor {
+        Conductor::Sphere { center, radius, .. } => sphere_grid(vector(center), radius, n),
+        Conductor::Plane { point, normal } => {
+            let nn = vector(normal).normalized();
+            let (u, v) = plane_basis(nn);
+            let foot = nn.scalar_product(&vector(point).dot_product(&nn));
+            rect_grid(foot, u.scalar_product(&half_size), v.scalar_product(&half_size), n)
+        }
+    };
+    SurfaceCharge::sample(points, n, |p| surface_charge_density(field, conductor, p))
+}
+
+impl SurfaceCharge {
+    /// Evaluate `sigma` at every point of an `n` × `n` surface grid.
+    pub fn sample(points: Vec<(f32, f32, f32)>, n: usize, sigma: impl Fn(&(f32, f32, f32)) -> f64) -> Self {
+        let sigma = points.iter().map(sigma).collect();
+        Self { resolution: n, points, sigma }
+    }
+
+    /// Range (min, max) of σ over the samples.
+    pub fn sigma_range(&self) -> (f64, f64) {
+        self.sigma
+            .iter()
+            .filter(|s| s.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &s| (lo.min(s), hi.max(s)))
+    }
+
+    /// Charge on the sampled surface [C]: each grid cell contributes its mean σ times its area.
+    pub fn total_charge(&self) -> f64 {
+        let n = self.resolution;
+        let at = |i: usize, j: usize| (query(&self.points[i * n + j]), self.sigma[i * n + j]);
+        let mut total = 0.0;
+        for i in 0..n - 1 {
+            for j in 0..n - 1 {
+                let (a, sa) = at(i, j);
+                let (b, sb) = at(i + 1, j);
+                let (c, sc) = at(i + 1, j + 1);
+                let (d, sd) = at(i, j + 1);
+                // Area of a (possibly degenerate) quad from its diagonals
+                let area = 0.5 * (c - a).cross_product(&(d - b)).magnitude();
+                total += 0.25 * (sa + sb + sc + sd) * area;
+            }
+        }
+        total
+    }
+}
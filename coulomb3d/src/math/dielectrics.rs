@@ -0,0 +1,385 @@
+/*
+Linear dielectrics: spheres, boxes and half-spaces with a relative permittivity εᵣ, embedded in
+vacuum. A point charge polarises them, and the bound charge reshapes its field. Two geometries
+have exact solutions (Jackson §4.3–4.4):
+
+Half-space. For a charge q in the medium with ε₁ facing the medium with ε₂:
+
+    on the charge's side:   ϕ = K/ε₁ (q/R + q′/R′),   q′ = −q (ε₂ − ε₁)/(ε₂ + ε₁) at the mirror point
+    on the far side:        ϕ = K q″/R,               q″ = 2q/(ε₁ + ε₂) at the charge
+
+Sphere of radius a. With the axis through the charge at distance d from the centre,
+x = cos γ the angle to it and ε = εᵣ, the Legendre series
+
+    d > a:   r ≥ a:  ϕ = Kq [1/R − ∑ₗ (ε − 1) l a²ˡ⁺¹ / ((εl + l + 1) dˡ⁺¹ rˡ⁺¹) Pₗ(x)]
+             r < a:  ϕ = Kq ∑ₗ (2l + 1) rˡ / ((εl + l + 1) dˡ⁺¹) Pₗ(x)
+    d < a:   r < a:  ϕ = Kq/ε [1/R + ∑ₗ (l + 1)(ε − 1) dˡ rˡ / ((εl + l + 1) a²ˡ⁺¹) Pₗ(x)]
+             r ≥ a:  ϕ = Kq ∑ₗ (2l + 1) dˡ / ((εl + l + 1) rˡ⁺¹) Pₗ(x)
+
+satisfies continuity of ϕ and of the normal component of D at r = a. It is summed until the terms
+fall below double precision. Any other arrangement (boxes, several dielectrics) is handed to the
+grid solver of poisson.rs.
+
+From E follow D = ε₀εᵣE and the polarisation P = ε₀(εᵣ − 1)E. The bound surface charge σ_b = P·n̂
+equals ε₀ times the jump of the normal component of E across the surface.
+*/
+use crate::math::conductors::{rect_grid, sphere_grid};
+use crate::math::distributions::plane_basis;
+use crate::math::{FieldSource, GridSolver, PoissonSolution, SurfaceCharge, Vector3D, EPSILON, K};
+use crate::Charge;
+use serde::{Deserialize, Serialize};
+
+/// Distance either side of a dielectric surface at which the field jump is measured [m].
+const SURFACE_OFFSET: f64 = 1e-3;
+/// Most Legendre terms summed for the sphere.
+const MAX_TERMS: usize = 400;
+
+/// A dielectric region. Points and directions are (x, y, z) in metres; normals need not be
+/// unit length.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Dielectric {
+    /// Ball of `radius` [m]
+    Sphere { center: [f64; 3], radius: f64, epsilon_r: f64 },
+    /// Axis-aligned box between the corners `min` and `max`
+    Box { min: [f64; 3], max: [f64; 3], epsilon_r: f64 },
+    /// Everything behind the plane through `point`; `normal` points out of the dielectric.
+    HalfSpace { point: [f64; 3], normal: [f64; 3], epsilon_r: f64 },
+}
+
+fn vector(a: [f64; 3]) -> Vector3D {
+    Vector3D::new(a[0], a[1], a[2])
+}
+
+fn query(s: &(f32, f32, f32)) -> Vector3D {
+    Vector3D::new(s.0 as f64, s.1 as f64, s.2 as f64)
+}
+
+fn point(v: Vector3D) -> (f32, f32, f32) {
+    let (x, y, z) = v.components();
+    (x as f32, y as f32, z as f32)
+}
+
+impl Dielectric {
+    pub fn epsilon_r(&self) -> f64 {
+        match *self {
+            Dielectric::Sphere { epsilon_r, .. }
+            | Dielectric::Box { epsilon_r, .. }
+            | Dielectric::HalfSpace { epsilon_r, .. } => epsilon_r,
+        }
+    }
+
+    /// Signed distance from the surface, negative inside. For boxes it is exact on the faces
+    /// and only approximate off them near edges and corners.
+    pub fn distance(&self, p: Vector3D) -> f64 {
+        match *self {
+            Dielectric::Sphere { center, radius, .. } => (p - vector(center)).magnitude() - radius,
+            Dielectric::HalfSpace { point, normal, .. } => (p - vector(point)).dot_product(&vector(normal).normalized()),
+            Dielectric::Box { min, max, .. } => {
+                let q = [p.components().0, p.components().1, p.components().2];
+                (0..3)
+                    .map(|a| (min[a] - q[a]).max(q[a] - max[a]))
+                    .fold(f64::NEG_INFINITY, f64::max)
+            }
+        }
+    }
+
+    /// Whether `p` is inside the dielectric.
+    pub fn contains(&self, p: Vector3D) -> bool {
+        self.distance(p) < 0.0
+    }
+
+    /// Unit normal pointing out of the dielectric, at the surface point nearest `p`.
+    pub fn normal_at(&self, p: Vector3D) -> Vector3D {
+        match *self {
+            Dielectric::Sphere { center, .. } => {
+                let d = p - vector(center);
+                if d.magnitude() > 0.0 { d.normalized() } else { Vector3D::new(0.0, 0.0, 1.0) }
+            }
+            Dielectric::HalfSpace { normal, .. } => vector(normal).normalized(),
+            Dielectric::Box { min, max, .. } => {
+                // The face whose plane is farthest out (or least far in)
+                let (x, y, z) = p.components();
+                let q = [x, y, z];
+                let mut best = (f64::NEG_INFINITY, 0, 1.0);
+                for a in 0..3 {
+                    for (d, sign) in [(min[a] - q[a], -1.0), (q[a] - max[a], 1.0)] {
+                        if d > best.0 {
+                            best = (d, a, sign);
+                        }
+                    }
+                }
+                let mut n = [0.0; 3];
+                n[best.1] = best.2;
+                vector(n)
+            }
+        }
+    }
+
+    /// The surface point nearest `p` (for boxes: on the plane of the nearest face).
+    pub fn project(&self, p: Vector3D) -> Vector3D {
+        p - self.normal_at(p).scalar_product(&self.distance(p))
+    }
+
+    /// Largest |coordinate| of the dielectric; a half-space only contributes its anchor point.
+    pub fn extent(&self) -> f64 {
+        let reach = |c: [f64; 3]| c.iter().fold(0.0f64, |acc, x| acc.max(x.abs()));
+        match *self {
+            Dielectric::Sphere { center, radius, .. } => reach(center) + radius,
+            Dielectric::Box { min, max, .. } => reach(min).max(reach(max)),
+            Dielectric::HalfSpace { point, .. } => reach(point),
+        }
+    }
+
+    /// Grids of points covering the surface, each `n` × `n`: one for a sphere, one square
+    /// of `half_size` for a half-space and one per face for a box.
+    pub fn surface_grids(&self, n: usize, half_size: f64) -> Vec<Vec<(f32, f32, f32)>> {
+        match *self {
+            Dielectric::Sphere { center, radius, .. } => vec![sphere_grid(vector(center), radius, n)],
+            Dielectric::HalfSpace { point, normal, .. } => {
+                let nn = vector(normal).normalized();
+                let (u, v) = plane_basis(nn);
+                let foot = nn.scalar_product(&vector(point).dot_product(&nn));
+                vec![rect_grid(foot, u.scalar_product(&half_size), v.scalar_product(&half_size), n)]
+            }
+            Dielectric::Box { min, max, .. } => {
+                let c = (vector(min) + vector(max)).scalar_product(&0.5);
+                let half = (vector(max) - vector(min)).scalar_product(&0.5);
+                let (hx, hy, hz) = half.components();
+                let axes = [Vector3D::new(hx, 0.0, 0.0), Vector3D::new(0.0, hy, 0.0), Vector3D::new(0.0, 0.0, hz)];
+                let mut grids = Vec::with_capacity(6);
+                for a in 0..3 {
+                    let (u, v) = (axes[(a + 1) % 3], axes[(a + 2) % 3]);
+                    for sign in [-1.0, 1.0] {
+                        grids.push(rect_grid(c + axes[a].scalar_product(&sign), u, v, n));
+                    }
+                }
+                grids
+            }
+        }
+    }
+}
+
+/// Relative permittivity at `p`: that of the first dielectric containing it, 1 in vacuum.
+pub fn relative_permittivity(dielectrics: &[Dielectric], p: [f64; 3]) -> f64 {
+    let p = vector(p);
+    dielectrics.iter().find(|d| d.contains(p)).map_or(1.0, |d| d.epsilon_r())
+}
+
+/// Field of the point charges in the presence of dielectrics. A single sphere or half-space is
+/// solved exactly; anything else on a grid.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScreenedCharges {
+    HalfSpace { charges: Vec<Charge>, dielectric: Dielectric },
+    Sphere { charges: Vec<Charge>, dielectric: Dielectric },
+    Grid(PoissonSolution),
+}
+
+impl ScreenedCharges {
+    /// Solve exactly when possible, unless `grid` asks for the grid solver (whose electrodes
+    /// and boundaries are then included); otherwise fall back to a default grid.
+    pub fn new(charges: &[Charge], dielectrics: &[Dielectric], grid: Option<&GridSolver>) -> Self {
+        match (dielectrics, grid) {
+            ([d @ Dielectric::HalfSpace { .. }], None) => {
+                ScreenedCharges::HalfSpace { charges: charges.to_vec(), dielectric: d.clone() }
+            }
+            ([d @ Dielectric::Sphere { .. }], None) => {
+                ScreenedCharges::Sphere { charges: charges.to_vec(), dielectric: d.clone() }
+            }
+            (_, grid) => ScreenedCharges::Grid(grid.cloned().unwrap_or_default().solve_in(charges, dielectrics)),
+        }
+    }
+
+    /// Whether the exact solution is used.
+    pub fn is_exact(&self) -> bool {
+        !matches!(self, ScreenedCharges::Grid(_))
+    }
+
+    fn charges(&self) -> &[Charge] {
+        match self {
+            ScreenedCharges::HalfSpace { charges, .. } | ScreenedCharges::Sphere { charges, .. } => charges,
+            ScreenedCharges::Grid(_) => &[],
+        }
+    }
+
+    /// Potential [V] and field [N/C] at `p` of the charges and the polarisation they induce.
+    fn potential_and_field(&self, p: Vector3D) -> (f64, Vector3D) {
+        let mut phi = 0.0;
+        let mut e = Vector3D::new(0.0, 0.0, 0.0);
+        for c in self.charges() {
+            let (dphi, de) = match self {
+                ScreenedCharges::HalfSpace { dielectric, .. } => half_space(dielectric, c, p),
+                ScreenedCharges::Sphere { dielectric, .. } => sphere(dielectric, c, p),
+                ScreenedCharges::Grid(_) => unreachable!(),
+            };
+            phi += dphi;
+            e += de;
+        }
+        (phi, e)
+    }
+}
+
+/// ϕ/K and E/K of a unit charge at `x`, seen from `p`.
+fn coulomb(x: Vector3D, p: Vector3D) -> (f64, Vector3D) {
+    let r = p - x;
+    let d = r.magnitude();
+    if d == 0.0 {
+        return (0.0, Vector3D::new(0.0, 0.0, 0.0));
+    }
+    (1.0 / d, r.scalar_product(&(1.0 / (d * d * d))))
+}
+
+fn half_space(d: &Dielectric, c: &Charge, p: Vector3D) -> (f64, Vector3D) {
+    let Dielectric::HalfSpace { point, normal, epsilon_r } = *d else { unreachable!() };
+    let n = vector(normal).normalized();
+    let x = query(&c.position);
+    let side = |v: Vector3D| (v - vector(point)).dot_product(&n) < 0.0;
+    // ε₁ on the charge's side, ε₂ across the interface
+    let (e1, e2) = if side(x) { (epsilon_r, 1.0) } else { (1.0, epsilon_r) };
+    let (phi, e) = if side(x) == side(p) {
+        let mirror = x - n.scalar_product(&(2.0 * (x - vector(point)).dot_product(&n)));
+        let image = -(e2 - e1) / (e2 + e1);
+        let (p1, f1) = coulomb(x, p);
+        let (p2, f2) = coulomb(mirror, p);
+        ((p1 + image * p2) / e1, (f1 + f2.scalar_product(&image)).scalar_product(&(1.0 / e1)))
+    } else {
+        let (p1, f1) = coulomb(x, p);
+        let t = 2.0 / (e1 + e2);
+        (t * p1, f1.scalar_product(&t))
+    };
+    (K * c.charge * phi, e.scalar_product(&(K * c.charge)))
+}
+
+fn sphere(d: &Dielectric, c: &Charge, p: Vector3D) -> (f64, Vector3D) {
+    let Dielectric::Sphere { center, radius: a, epsilon_r: eps } = *d else { unreachable!() };
+    let o = vector(center);
+    let xq = query(&c.position) - o;
+    let mut rp = p - o;
+    let dist = xq.magnitude();
+    let axis = if dist > 0.0 { xq.scalar_product(&(1.0 / dist)) } else { Vector3D::new(0.0, 0.0, 1.0) };
+    if rp.magnitude() < 1e-9 {
+        rp = axis.scalar_product(&1e-9);
+    }
+    let r = rp.magnitude();
+    let rhat = rp.scalar_product(&(1.0 / r));
+    let x = rhat.dot_product(&axis).clamp(-1.0, 1.0);
+
+    // Each series term is (c₀ + c₁l)/(εl + l + 1) · pref · ratioˡ · Pₗ(x), growing like rˡ
+    // (rising) or falling like r^−(l+1); the direct 1/R term is added separately
+    let (direct, pref, ratio, rising, [c0, c1]) = match (dist > a, r >= a) {
+        (true, true) => (1.0, a / (dist * r), a * a / (dist * r), false, [0.0, 1.0 - eps]),
+        (true, false) => (0.0, 1.0 / dist, r / dist, true, [1.0, 2.0]),
+        (false, false) => (1.0 / eps, 1.0 / (eps * a), dist * r / (a * a), true, [eps - 1.0, eps - 1.0]),
+        (false, true) => (0.0, 1.0 / r, dist / r, false, [1.0, 2.0]),
+    };
+    let l_f = |l: usize| l as f64;
+    let coef = |l: usize| (c0 + c1 * l_f(l)) / (eps * l_f(l) + l_f(l) + 1.0);
+
+    let (mut phi, mut e) = {
+        let (p1, f1) = coulomb(xq, rp);
+        (direct * p1, f1.scalar_product(&direct))
+    };
+    // Pₗ and Pₗ′ by the usual recurrences
+    let (mut p_prev, mut p_cur) = (0.0, 1.0);
+    let (mut dp_prev, mut dp_cur) = (0.0, 0.0);
+    let mut power = 1.0;
+    let (mut d_radial, mut d_angular) = (0.0, 0.0);
+    for l in 0..MAX_TERMS {
+        let t = coef(l) * pref * power;
+        phi += t * p_cur;
+        // ∂/∂r of the radial factor, and ∂/∂x for the angular part
+        let radial = if rising { l_f(l) / r } else { -(l_f(l) + 1.0) / r };
+        d_radial += t * radial * p_cur;
+        d_angular += t * dp_cur;
+
+        let next = ((2.0 * l_f(l) + 1.0) * x * p_cur - l_f(l) * p_prev) / (l_f(l) + 1.0);
+        let dnext = if l == 0 { 1.0 } else { dp_prev + (2.0 * l_f(l) + 1.0) * p_cur };
+        (p_prev, p_cur) = (p_cur, next);
+        (dp_prev, dp_cur) = (dp_cur, dnext);
+        power *= ratio;
+        if power < 1e-17 {
+            break;
+        }
+    }
+    // E = −∇ϕ with ∇x = (axis − x r̂)/r
+    e += rhat.scalar_product(&-d_radial) + (axis - rhat.scalar_product(&x)).scalar_product(&(-d_angular / r));
+    (K * c.charge * phi, e.scalar_product(&(K * c.charge)))
+}
+
+impl FieldSource for ScreenedCharges {
+    fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+        match self {
+            ScreenedCharges::Grid(solution) => solution.potential(s),
+            _ => self.potential_and_field(query(s)).0,
+        }
+    }
+    fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+        match self {
+            ScreenedCharges::Grid(solution) => solution.field(s),
+            _ => self.potential_and_field(query(s)).1,
+        }
+    }
+    fn absorbs(&self, s: &(f32, f32, f32)) -> bool {
+        match self {
+            ScreenedCharges::Grid(solution) => solution.absorbs(s),
+            _ => self.charges().absorbs(s),
+        }
+    }
+    fn extent(&self) -> f32 {
+        match self {
+            ScreenedCharges::Grid(solution) => solution.extent(),
+            ScreenedCharges::HalfSpace { charges, dielectric } | ScreenedCharges::Sphere { charges, dielectric } => {
+                charges.extent().max(dielectric.extent() as f32)
+            }
+        }
+    }
+}
+
+/// E, D and P at one point, with the permittivity there.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaterialFields {
+    pub epsilon_r: f64,
+    /// Electric field [N/C]
+    pub e: Vector3D,
+    /// Displacement field ε₀εᵣE [C/m²]
+    pub d: Vector3D,
+    /// Polarisation ε₀(εᵣ − 1)E [C/m²]
+    pub p: Vector3D,
+}
+
+/// E, D and P of `field` at `s`.
+pub fn material_fields(field: &dyn FieldSource, dielectrics: &[Dielectric], s: &(f32, f32, f32)) -> MaterialFields {
+    let epsilon_r = relative_permittivity(dielectrics, [s.0 as f64, s.1 as f64, s.2 as f64]);
+    let e = field.field(s);
+    MaterialFields {
+        epsilon_r,
+        e,
+        d: e.scalar_product(&(EPSILON * epsilon_r)),
+        p: e.scalar_product(&(EPSILON * (epsilon_r - 1.0))),
+    }
+}
+
+/// Bound surface charge σ_b [C/m²] at the surface point nearest `s`: ε₀ times the jump of
+/// E·n̂ across the surface. Grid solutions resolve it only to within a lattice cell.
+pub fn bound_surface_charge(field: &dyn FieldSource, dielectric: &Dielectric, s: &(f32, f32, f32)) -> f64 {
+    let p = dielectric.project(query(s));
+    let n = dielectric.normal_at(p);
+    let outside = field.field(&point(p + n.scalar_product(&SURFACE_OFFSET)));
+    let inside = field.field(&point(p - n.scalar_product(&SURFACE_OFFSET)));
+    EPSILON * (outside - inside).dot_product(&n)
+}
+
+/// σ_b sampled on every surface grid of `dielectric`; see `Dielectric::surface_grids`.
+pub fn sample_bound_charge(
+    field: &dyn FieldSource,
+    dielectric: &Dielectric,
+    resolution: usize,
+    half_size: f64,
+) -> Vec<SurfaceCharge> {
+    let n = resolution.max(2);
+    dielectric
+        .surface_grids(n, half_size)
+        .into_iter()
+        .map(|points| SurfaceCharge::sample(points, n, |p| bound_surface_charge(field, dielectric, p)))
+        .collect()
+}
@@ -0,0 +1,397 @@
+/*
+Continuous charge distributions: finite lines, rings, disks, spherical shells and balls, and
+infinite planes and slabs. Each one is a `FieldSource`, so it mixes freely with point charges
+through `Superposition`.
+
+Lines, spheres, planes and slabs use closed-form expressions. Rings and disks have no
+elementary off-axis solution (it needs elliptic integrals), so they are integrated numerically:
+the ring with the midpoint rule in angle, the disk with Gauss–Legendre in radius times the
+midpoint rule in angle.
+
+The infinite sources carry infinite total charge, so their potential cannot vanish at infinity;
+it is taken to be zero on the plane (or the slab's mid-plane) instead.
+*/
+use crate::math::algebra::quadrature::gauss_legendre;
+use crate::math::{FieldSource, Seed, Vector3D, K, PI};
+use serde::{Deserialize, Serialize};
+
+/// Angular samples used for ring and disk quadrature.
+const ANGULAR_SAMPLES: usize = 96;
+/// Radial Gauss–Legendre nodes used for disk quadrature.
+const RADIAL_NODES: usize = 16;
+/// Distance from a line, ring or surface inside which field lines are absorbed.
+const ABSORB_DISTANCE: f64 = 0.05;
+
+/// A continuous charge distribution. Points and directions are (x, y, z) in metres;
+/// normals need not be unit length.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Distribution {
+    /// Straight segment from `start` to `end` with linear density `lambda` [C/m].
+    Line { start: [f64; 3], end: [f64; 3], lambda: f64 },
+    /// Circular ring of `radius` [m] carrying total `charge` [C].
+    Ring { center: [f64; 3], normal: [f64; 3], radius: f64, charge: f64 },
+    /// Flat disk of `radius` [m] with uniform surface density `sigma` [C/m²].
+    Disk { center: [f64; 3], normal: [f64; 3], radius: f64, sigma: f64 },
+    /// Sphere of `radius` [m] with total `charge` [C], spread over the surface
+    /// (a shell) or, when `solid`, uniformly through the volume (a ball).
+    Sphere { center: [f64; 3], radius: f64, charge: f64, solid: bool },
+    /// Infinite plane through `point` with uniform surface density `sigma` [C/m²].
+    Plane { point: [f64; 3], normal: [f64; 3], sigma: f64 },
+    /// Infinite slab of `thickness` [m] centred on the plane through `point`,
+    /// with uniform volume density `rho` [C/m³].
+    Slab { point: [f64; 3], normal: [f64; 3], thickness: f64, rho: f64 },
+}
+
+fn vector(a: [f64; 3]) -> Vector3D {
+    Vector3D::new(a[0], a[1], a[2])
+}
+
+fn query(s: &(f32, f32, f32)) -> Vector3D {
+    Vector3D::new(s.0 as f64, s.1 as f64, s.2 as f64)
+}
+
+/// Two unit vectors spanning the plane perpendicular to the unit vector `n`.
+pub(crate) fn plane_basis(n: Vector3D) -> (Vector3D, Vector3D) {
+    let (nx, _, _) = n.components();
+    let helper = if nx.abs() < 0.9 { Vector3D::new(1.0, 0.0, 0.0) } else { Vector3D::new(0.0, 1.0, 0.0) };
+    let u = n.cross_product(&helper).normalized();
+    let v = n.cross_product(&u);
+    (u, v)
+}
+
+/// Potential and field of a point-like element `q` at `x`, seen from `p`.
+fn element(q: f64, x: Vector3D, p: Vector3D) -> (f64, Vector3D) {
+    let r = p - x;
+    let d = r.magnitude();
+    if d == 0.0 {
+        return (0.0, Vector3D::new(0.0, 0.0, 0.0));
+    }
+    (q / d, r.scalar_product(&(q / (d * d * d))))
+}
+
+impl Distribution {
+    /// Total charge [C], or `None` for the infinite plane and slab.
+    pub fn total_charge(&self) -> Option<f64> {
+        match *self {
+            Distribution::Line { start, end, lambda } => Some(lambda * (vector(end) - vector(start)).magnitude()),
+            Distribution::Ring { charge, .. } => Some(charge),
+            Distribution::Disk { radius, sigma, .. } => Some(sigma * PI * radius * radius),
+            Distribution::Sphere { charge, .. } => Some(charge),
+            Distribution::Plane { .. } | Distribution::Slab { .. } => None,
+        }
+    }
+
+    /// Sign of the charge density, used to decide which way seeded lines are traced.
+    fn sign(&self) -> f64 {
+        let density = match *self {
+            Distribution::Line { lambda, .. } => lambda,
+            Distribution::Ring { charge, .. } | Distribution::Sphere { charge, .. } => charge,
+            Distribution::Disk { sigma, .. } | Distribution::Plane { sigma, .. } => sigma,
+            Distribution::Slab { rho, .. } => rho,
+        };
+        if density >= 0.0 { 1.0 } else { -1.0 }
+    }
+
+    /// Potential ϕ [V] and field E [N/C] at `p` in one pass.
+    pub fn potential_and_field(&self, p: Vector3D) -> (f64, Vector3D) {
+        match *self {
+            Distribution::Line { start, end, lambda } => {
+                /*
+                Finite line, local coordinates: z along the segment from A, ρ across it.
+                ϕ   = Kλ ln[(r_A + r_B + L) / (r_A + r_B − L)]
+                E_z = Kλ (1/r_B − 1/r_A)
+                E_ρ = Kλ/ρ (z/r_A − (z − L)/r_B)
+                */
+                let a = vector(start);
+                let axis = vector(end) - a;
+                let len = axis.magnitude();
+                if len == 0.0 {
+                    return (0.0, Vector3D::new(0.0, 0.0, 0.0));
+                }
+                let u = axis.scalar_product(&(1.0 / len));
+                let d = p - a;
+                let z = d.dot_product(&u);
+                let radial = d - u.scalar_product(&z);
+                let rho = radial.magnitude();
+                let ra = d.magnitude();
+                let rb = (p - vector(end)).magnitude();
+
+                let phi = K * lambda * ((ra + rb + len) / (ra + rb - len)).ln();
+                let ez = K * lambda * (1.0 / rb - 1.0 / ra);
+                let er = if rho > 1e-12 { K * lambda / rho * (z / ra - (z - len) / rb) } else { 0.0 };
+                let e = u.scalar_product(&ez) + radial.normalized().scalar_product(&er);
+                (phi, e)
+            }
+            Distribution::Ring { center, normal, radius, charge } => {
+                // Midpoint rule over the angle, each element carrying Q/N
+                let c = vector(center);
+                let (u, v) = plane_basis(vector(normal).normalized());
+                let dq = charge / ANGULAR_SAMPLES as f64;
+                let mut phi = 0.0;
+                let mut e = Vector3D::new(0.0, 0.0, 0.0);
+                for k in 0..ANGULAR_SAMPLES {
+                    let th = 2.0 * PI * (k as f64 + 0.5) / ANGULAR_SAMPLES as f64;
+                    let x = c + u.scalar_product(&(radius * th.cos())) + v.scalar_product(&(radius * th.sin()));
+                    let (dphi, de) = element(dq, x, p);
+                    phi += dphi;
+                    e += de;
+                }
+                (K * phi, e.scalar_product(&K))
+            }
+            Distribution::Disk { center, normal, radius, sigma } => {
+                // Gauss–Legendre in r ∈ [0, R] times midpoint rule in θ; dq = σ r dr dθ
+                let c = vector(center);
+                let (u, v) = plane_basis(vector(normal).normalized());
+                let dth = 2.0 * PI / ANGULAR_SAMPLES as f64;
+                let mut phi = 0.0;
+                let mut e = Vector3D::new(0.0, 0.0, 0.0);
+                for (xi, w) in gauss_legendre(RADIAL_NODES) {
+                    let r = 0.5 * radius * (xi + 1.0);
+                    let dq = sigma * r * 0.5 * radius * w * dth;
+                    for k in 0..ANGULAR_SAMPLES {
+                        let th = dth * (k as f64 + 0.5);
+                        let x = c + u.scalar_product(&(r * th.cos())) + v.scalar_product(&(r * th.sin()));
+                        let (dphi, de) = element(dq, x, p);
+                        phi += dphi;
+                        e += de;
+                    }
+                }
+                (K * phi, e.scalar_product(&K))
+            }
+            Distribution::Sphere { center, radius, charge, solid } => {
+                /*
+                Outside: ϕ = KQ/r,  E = KQ r̂/r²  (both shell and ball)
+                Shell inside: ϕ = KQ/R,  E = 0
+                Ball inside:  ϕ = KQ(3R² − r²)/(2R³),  E = KQ r/R³
+                */
+                let d = p - vector(center);
+                let r = d.magnitude();
+                if r >= radius {
+                    return (K * charge / r, d.scalar_product(&(K * charge / (r * r * r))));
+                }
+                if solid {
+                    let r3 = radius * radius * radius;
+                    (
+                        K * charge * (3.0 * radius * radius - r * r) / (2.0 * r3),
+                        d.scalar_product(&(K * charge / r3)),
+                    )
+                } else {
+                    (K * charge / radius, Vector3D::new(0.0, 0.0, 0.0))
+                }
+            }
+            Distribution::Plane { point, normal, sigma } => {
+                // E = σ/(2ε₀) sgn(d) n̂ = 2πKσ sgn(d) n̂,  ϕ = −2πKσ |d|
+                let n = vector(normal).normalized();
+                let dist = (p - vector(point)).dot_product(&n);
+                let e0 = 2.0 * PI * K * sigma;
+                (-e0 * dist.abs(), n.scalar_product(&(e0 * dist.signum())))
+            }
+            Distribution::Slab { point, normal, thickness, rho } => {
+                /*
+                d measured from the mid-plane, h = t/2:
+                inside:  E = ρd/ε₀ n̂,            ϕ = −ρd²/(2ε₀)
+                outside: E = ρh/ε₀ sgn(d) n̂,     ϕ = −ρh(|d| − h/2)/ε₀
+                with 1/ε₀ = 4πK
+                */
+                let n = vector(normal).normalized();
+                let dist = (p - vector(point)).dot_product(&n);
+                let h = 0.5 * thickness;
+                let inv_eps = 4.0 * PI * K;
+                if dist.abs() <= h {
+                    (-0.5 * rho * dist * dist * inv_eps, n.scalar_product(&(rho * dist * inv_eps)))
+                } else {
+                    (
+                        -rho * h * (dist.abs() - 0.5 * h) * inv_eps,
+                        n.scalar_product(&(rho * h * dist.signum() * inv_eps)),
+                    )
+                }
+            }
+        }
+    }
+
+    /// Whether `p` is on (or, for solids, inside) the distribution.
+    pub fn contains(&self, p: Vector3D) -> bool {
+        match *self {
+            Distribution::Line { start, end, .. } => {
+                let a = vector(start);
+                let axis = vector(end) - a;
+                let len2 = axis.dot_product(&axis);
+                let t = if len2 > 0.0 { ((p - a).dot_product(&axis) / len2).clamp(0.0, 1.0) } else { 0.0 };
+                (p - (a + axis.scalar_product(&t))).magnitude() < ABSORB_DISTANCE
+            }
+            Distribution::Ring { center, normal, radius, .. } => {
+                let n = vector(normal).normalized();
+                let d = p - vector(center);
+                let z = d.dot_product(&n);
+                let rho = (d - n.scalar_product(&z)).magnitude();
+                ((rho - radius).powi(2) + z * z).sqrt() < ABSORB_DISTANCE
+            }
+            Distribution::Disk { center, normal, radius, .. } => {
+                let n = vector(normal).normalized();
+                let d = p - vector(center);
+                let z = d.dot_product(&n);
+                let rho = (d - n.scalar_product(&z)).magnitude();
+                z.abs() < ABSORB_DISTANCE && rho <= radius
+            }
+            Distribution::Sphere { center, radius, solid, .. } => {
+                let r = (p - vector(center)).magnitude();
+                if solid { r < radius + ABSORB_DISTANCE } else { (r - radius).abs() < ABSORB_DISTANCE }
+            }
+            Distribution::Plane { point, normal, .. } => {
+                (p - vector(point)).dot_product(&vector(normal).normalized()).abs() < ABSORB_DISTANCE
+            }
+            Distribution::Slab { point, normal, thickness, .. } => {
+                (p - vector(point)).dot_product(&vector(normal).normalized()).abs() < 0.5 * thickness + ABSORB_DISTANCE
+            }
+        }
+    }
+
+    /// Largest |coordinate| of the finite part of the distribution. Infinite planes and
+    /// slabs only contribute their anchor point.
+    pub fn extent(&self) -> f64 {
+        let reach = |c: [f64; 3], r: f64| c.iter().fold(0.0f64, |acc, x| acc.max(x.abs())) + r;
+        match *self {
+            Distribution::Line { start, end, .. } => reach(start, 0.0).max(reach(end, 0.0)),
+            Distribution::Ring { center, radius, .. }
+            | Distribution::Disk { center, radius, .. }
+            | Distribution::Sphere { center, radius, .. } => reach(center, radius),
+            Distribution::Plane { point, .. } => reach(point, 0.0),
+            Distribution::Slab { point, thickness, .. } => reach(point, 0.5 * thickness),
+        }
+    }
+
+    /// Roughly `n` field line seeds just off the distribution, traced away from it
+    /// for positive densities and towards it for negative ones.
+    pub fn seeds(&self, n: usize) -> Vec<Seed> {
+        let dir = self.sign() as f32;
+        let off = 2.0 * ABSORB_DISTANCE;
+        let seed = |p: Vector3D| {
+            let (x, y, z) = p.components();
+            Seed { position: (x as f32, y as f32, z as f32), direction: dir }
+        };
+        let n = n.max(1);
+        match *self {
+            Distribution::Line { start, end, .. } => {
+                // n seeds spiralling around the segment
+                let a = vector(start);
+                let axis = vector(end) - a;
+                let (u, v) = plane_basis(axis.normalized());
+                (0..n)
+                    .map(|k| {
+                        let t = (k as f64 + 0.5) / n as f64;
+                        let th = 2.0 * PI * k as f64 * 0.381966;
+                        let ring = u.scalar_product(&th.cos()) + v.scalar_product(&th.sin());
+                        seed(a + axis.scalar_product(&t) + ring.scalar_product(&off))
+                    })
+                    .collect()
+            }
+            Distribution::Ring { center, normal, radius, .. } => {
+                // Alternate above/below the ring plane, spread around its circumference
+                let c = vector(center);
+                let nn = vector(normal).normalized();
+                let (u, v) = plane_basis(nn);
+                (0..n)
+                    .map(|k| {
+                        let th = 2.0 * PI * k as f64 / n as f64;
+                        let radial = u.scalar_product(&th.cos()) + v.scalar_product(&th.sin());
+                        let side = if k % 2 == 0 { 1.0 } else { -1.0 };
+                        seed(c + radial.scalar_product(&radius) + nn.scalar_product(&(side * off)))
+                    })
+                    .collect()
+            }
+            Distribution::Disk { center, normal, radius, .. } => {
+                // Sunflower pattern on both faces
+                let c = vector(center);
+                let nn = vector(normal).normalized();
+                let (u, v) = plane_basis(nn);
+                (0..n)
+                    .map(|k| {
+                        let r = radius * ((k / 2) as f64 + 0.5).sqrt() / ((n / 2).max(1) as f64).sqrt();
+                        let th = 2.0 * PI * k as f64 * 0.381966;
+                        let radial = u.scalar_product(&(r * th.cos())) + v.scalar_product(&(r * th.sin()));
+                        let side = if k % 2 == 0 { 1.0 } else { -1.0 };
+                        seed(c + radial + nn.scalar_product(&(side * off)))
+                    })
+                    .collect()
+            }
+            Distribution::Sphere { center, radius, .. } => {
+                // Fibonacci sphere just outside the surface
+                let c = vector(center);
+                let ga = PI * (3.0 - 5.0f64.sqrt());
+                (0..n)
+                    .map(|k| {
+                        let y = if n > 1 { 1.0 - 2.0 * k as f64 / (n - 1) as f64 } else { 0.0 };
+                        let r = (1.0 - y * y).sqrt();
+                        let th = ga * k as f64;
+                        let dir = Vector3D::new(r * th.cos(), y, r * th.sin());
+                        seed(c + dir.scalar_product(&(radius + off)))
+                    })
+                    .collect()
+            }
+            Distribution::Plane { point, normal, .. } | Distribution::Slab { point, normal, .. } => {
+                // A square grid on both faces, 3 m across, centred on the point nearest the origin
+                let nn = vector(normal).normalized();
+                let p0 = vector(point);
+                let foot = nn.scalar_product(&p0.dot_product(&nn));
+                let half = match *self {
+                    Distribution::Slab { thickness, .. } => 0.5 * thickness,
+                    _ => 0.0,
+                };
+                let (u, v) = plane_basis(nn);
+                let side = ((n as f64 / 2.0).sqrt().ceil() as usize).max(1);
+                let mut out = Vec::with_capacity(2 * side * side);
+                for i in 0..side {
+                    for j in 0..side {
+                        let a = if side > 1 { -1.5 + 3.0 * i as f64 / (side - 1) as f64 } else { 0.0 };
+                        let b = if side > 1 { -1.5 + 3.0 * j as f64 / (side - 1) as f64 } else { 0.0 };
+                        let q = foot + u.scalar_product(&a) + v.scalar_product(&b);
+                        for s in [1.0, -1.0] {
+                            out.push(seed(q + nn.scalar_product(&(s * (half + off)))));
+                        }
+                    }
+                }
+                out
+            }
+        }
+    }
+}
+
+impl FieldSource for Distribution {
+    fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+        self.potential_and_field(query(s)).0
+    }
+    fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+        self.potential_and_field(query(s)).1
+    }
+    fn absorbs(&self, s: &(f32, f32, f32)) -> bool {
+        self.contains(query(s))
+    }
+    fn extent(&self) -> f32 {
+        Distribution::extent(self) as f32
+    }
+}
+
+impl FieldSource for [Distribution] {
+    fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+        self.iter().map(|d| d.potential(s)).sum()
+    }
+    fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+        let mut e = Vector3D::new(0.0, 0.0, 0.0);
+        for d in self {
+            e += FieldSource::field(d, s);
+        }
+        e
+    }
+    fn absorbs(&self, s: &(f32, f32, f32)) -> bool {
+        self.iter().any(|d| d.absorbs(s))
+    }
+    fn extent(&self) -> f32 {
+        self.iter().fold(0.0f32, |acc, d| acc.max(FieldSource::extent(d)))
+    }
+}
+
+/// Field line seeds for every distribution, `n` per distribution.
+pub fn distribution_seeds(distributions: &[Distribution], n: usize) -> Vec<Seed> {
+    distributions.iter().flat_map(|d| d.seeds(n)).collect()
+}
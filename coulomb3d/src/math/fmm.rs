@@ -0,0 +1,424 @@
+/*
+Fast multipole method for the potential and field of many point charges. This carries the idea
+behind `multipole_moments` to arbitrary order: every cell of an octree gets the multipole
+expansion of its charges up to degree ℓ (its ℓ ≤ 2 coefficients are exactly the monopole, dipole
+and quadrupole moments), and those expansions are translated down the tree into local expansions.
+
+    upward pass     P2M at the leaves, M2M from children to parents
+    downward pass   M2L from each cell's interaction list (children of the parent's neighbours
+                    that are not neighbours themselves), L2L from parent to children
+    query           L2P from the leaf containing the point, plus direct sums over its
+                    27 neighbouring leaves
+
+The tree is a full octree over the charges' bounding cube, deep enough that a leaf holds about
+`LEAF_TARGET` charges, so building it costs O(N) and every query inside the cube is O(1).
+Points outside the cube are evaluated by walking the multipole expansions instead, opening cells
+until they are well separated. The error falls geometrically with the order ℓ; see
+tests/fmm.rs for the measured values.
+
+Expansions use the solid harmonics of harmonics.rs; the translation operators below are the
+textbook ones written out in that normalisation. Each operator only computes the orders m ≥ 0
+and fills in the rest by symmetry, since all expansions here describe real potentials.
+*/
+use crate::math::harmonics::{expansion_len, index, irregular, mirror, regular, Complex};
+use crate::math::{FieldSource, Vector3D, ABSORB_RADIUS, K};
+use crate::Charge;
+
+/// Aim for this many charges per leaf when choosing the tree depth.
+const LEAF_TARGET: usize = 32;
+/// Deepest leaf level (8⁴ = 4096 leaves); keeps memory bounded for high orders.
+const MAX_LEVEL: usize = 4;
+/// Outside the root cube a cell is used as a multipole once its radius is below
+/// this fraction of its distance.
+const OUTSIDE_MAC: f64 = 0.5;
+/// Highest supported expansion order. The lowest is 1: E comes from the gradient of the
+/// local expansions, and at order 0 they have none.
+pub const MAX_FMM_ORDER: usize = 20;
+
+/// A multipole or local expansion up to some degree.
+type Expansion = Vec<Complex>;
+
+/// Multipole-to-multipole: add `child` (about a centre displaced by `d` from the
+/// parent's) to the parent expansion.
+fn m2m(child: &[Complex], d: Vector3D, p: usize, parent: &mut [Complex]) {
+    let u = regular(d, p);
+    for n in 0..=p {
+        for m in 0..=n as i64 {
+            let mut acc = Complex::ZERO;
+            for k in 0..=n {
+                for l in -(k as i64)..=k as i64 {
+                    if (m - l).unsigned_abs() as usize <= n - k {
+                        acc += u[index(k, l)] * child[index(n - k, m - l)];
+                    }
+                }
+            }
+            parent[index(n, m)] += acc;
+        }
+    }
+    mirror(parent, p);
+}
+
+/// Multipole-to-local: add the degree-`q` local expansion, about a centre at `x`
+/// relative to the multipole's centre, of a degree-`p` multipole expansion.
+fn m2l(multipole: &[Complex], p: usize, x: Vector3D, q: usize, local: &mut [Complex]) {
+    let t = irregular(x, p + q);
+    for k in 0..=q {
+        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+        for l in 0..=k as i64 {
+            let mut acc = Complex::ZERO;
+            for n in 0..=p {
+                for m in -(n as i64)..=n as i64 {
+                    acc += multipole[index(n, m)].conj() * t[index(n + k, m + l)];
+                }
+            }
+            local[index(k, l)] += acc.scale(sign);
+        }
+    }
+    mirror(local, q);
+}
+
+/// Local-to-local: add the degree-`q` local expansion about a centre displaced by
+/// `d` of a degree-`p` local expansion.
+fn l2l(local: &[Complex], p: usize, d: Vector3D, q: usize, out: &mut [Complex]) {
+    let u = regular(d, p);
+    for j in 0..=q {
+        for s in 0..=j as i64 {
+            let mut acc = Complex::ZERO;
+            for k in j..=p {
+                for l in -(k as i64)..=k as i64 {
+                    if (l - s).unsigned_abs() as usize <= k - j {
+                        acc += local[index(k, l)] * u[index(k - j, l - s)].conj();
+                    }
+                }
+            }
+            out[index(j, s)] += acc;
+        }
+    }
+    mirror(out, q);
+}
+
+/// ∑ q/r and its gradient from a local expansion (degree ≥ 1) about the point itself.
+fn value_and_gradient(f: &[Complex]) -> (f64, Vector3D) {
+    let (f10, f11, f1m) = (f[index(1, 0)], f[index(1, 1)], f[index(1, -1)]);
+    let gradient = Vector3D::new(0.5 * (f11 - f1m).re, 0.5 * (f11 + f1m).im, f10.re);
+    (f[0].re, gradient)
+}
+
+/// Point charges prepared for fast multipole evaluation at expansion order ℓ.
+pub struct Fmm {
+    /// Expansion order ℓ ∈ [1, `MAX_FMM_ORDER`]
+    pub order: usize,
+    /// Leaf level of the octree (the root is level 0)
+    levels: usize,
+    /// Centre and half-size of the root cube
+    center: Vector3D,
+    half: f64,
+    /// Positions and charges, sorted by leaf
+    bodies: Vec<(Vector3D, f64)>,
+    /// Bodies of leaf `c` are `bodies[leaf_start[c]..leaf_start[c + 1]]`
+    leaf_start: Vec<usize>,
+    /// Charges below each cell, per level
+    counts: Vec<Vec<usize>>,
+    /// Multipole expansion of each cell about its centre, per level
+    multipoles: Vec<Vec<Expansion>>,
+    /// Local expansion of each leaf about its centre
+    locals: Vec<Expansion>,
+}
+
+impl Fmm {
+    /// Build the tree and run the upward and downward passes for expansion order `order`,
+    /// clamped to [1, `MAX_FMM_ORDER`].
+    pub fn new(charges: &[Charge], order: usize) -> Self {
+        let p = order.clamp(1, MAX_FMM_ORDER);
+        let mut bodies: Vec<(Vector3D, f64)> = charges
+            .iter()
+            .map(|c| {
+                let (x, y, z) = c.position;
+                (Vector3D::new(x as f64, y as f64, z as f64), c.charge)
+            })
+            .collect();
+
+        let (mut lo, mut hi) = ([f64::INFINITY; 3], [f64::NEG_INFINITY; 3]);
+        for (r, _) in &bodies {
+            let (x, y, z) = r.components();
+            for (a, v) in [x, y, z].into_iter().enumerate() {
+                lo[a] = lo[a].min(v);
+                hi[a] = hi[a].max(v);
+            }
+        }
+        let (center, half) = if bodies.is_empty() {
+            (Vector3D::new(0.0, 0.0, 0.0), 1.0)
+        } else {
+            let c = Vector3D::new((lo[0] + hi[0]) / 2.0, (lo[1] + hi[1]) / 2.0, (lo[2] + hi[2]) / 2.0);
+            // slightly enlarged so every charge lies strictly inside
+            let h = (0..3).fold(0.0f64, |acc, a| acc.max((hi[a] - lo[a]) / 2.0));
+            (c, h * 1.001 + 1e-6)
+        };
+
+        let mut levels = 2;
+        while levels < MAX_LEVEL && bodies.len() > LEAF_TARGET << (3 * levels) {
+            levels += 1;
+        }
+
+        let mut fmm = Self {
+            order: p,
+            levels,
+            center,
+            half,
+            bodies: Vec::new(),
+            leaf_start: Vec::new(),
+            counts: Vec::new(),
+            multipoles: Vec::new(),
+            locals: Vec::new(),
+        };
+
+        // Sort the bodies into the leaves
+        let n_leaves = 1 << (3 * levels);
+        bodies.sort_by_key(|(r, _)| fmm.leaf_of(*r));
+        let mut leaf_start = vec![0; n_leaves + 1];
+        for (r, _) in &bodies {
+            leaf_start[fmm.leaf_of(*r) + 1] += 1;
+        }
+        for c in 0..n_leaves {
+            leaf_start[c + 1] += leaf_start[c];
+        }
+        fmm.bodies = bodies;
+        fmm.leaf_start = leaf_start;
+
+        fmm.upward_pass();
+        fmm.downward_pass();
+        fmm
+    }
+
+    /// Number of charges.
+    pub fn len(&self) -> usize {
+        self.bodies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bodies.is_empty()
+    }
+
+    fn cells_per_side(level: usize) -> usize {
+        1 << level
+    }
+
+    fn flat(level: usize, i: usize, j: usize, k: usize) -> usize {
+        let n = Self::cells_per_side(level);
+        (k * n + j) * n + i
+    }
+
+    /// Integer coordinates of the cell at `level` holding `r`, clamped to the cube.
+    fn cell_coords(&self, level: usize, r: Vector3D) -> [usize; 3] {
+        let n = Self::cells_per_side(level);
+        let (x, y, z) = (r - self.center).components();
+        [x, y, z].map(|v| (((v + self.half) / (2.0 * self.half) * n as f64).floor().max(0.0) as usize).min(n - 1))
+    }
+
+    fn leaf_of(&self, r: Vector3D) -> usize {
+        let [i, j, k] = self.cell_coords(self.levels, r);
+        Self::flat(self.levels, i, j, k)
+    }
+
+    fn cell_center(&self, level: usize, i: usize, j: usize, k: usize) -> Vector3D {
+        let h = 2.0 * self.half / Self::cells_per_side(level) as f64;
+        let c = |i: usize| -self.half + (i as f64 + 0.5) * h;
+        self.center + Vector3D::new(c(i), c(j), c(k))
+    }
+
+    /// P2M at the leaves, then M2M up to the root.
+    fn upward_pass(&mut self) {
+        let p = self.order;
+        let len = expansion_len(p);
+        let n_leaves = 1 << (3 * self.levels);
+        let mut counts = vec![vec![0; n_leaves]];
+        let mut multipoles = vec![vec![vec![Complex::ZERO; len]; n_leaves]];
+
+        let n = Self::cells_per_side(self.levels);
+        for (k, j, i) in (0..n).flat_map(|k| (0..n).flat_map(move |j| (0..n).map(move |i| (k, j, i)))) {
+            let c = Self::flat(self.levels, i, j, k);
+            let center = self.cell_center(self.levels, i, j, k);
+            counts[0][c] = self.leaf_start[c + 1] - self.leaf_start[c];
+            for &(r, q) in &self.bodies[self.leaf_start[c]..self.leaf_start[c + 1]] {
+                for (m, u) in multipoles[0][c].iter_mut().zip(regular(r - center, p)) {
+                    *m += u.scale(q);
+                }
+            }
+        }
+
+        for level in (0..self.levels).rev() {
+            let n = Self::cells_per_side(level);
+            let mut level_counts = vec![0; n * n * n];
+            let mut level_multipoles = vec![vec![Complex::ZERO; len]; n * n * n];
+            let (child_counts, child_multipoles) = (counts.last().unwrap(), multipoles.last().unwrap());
+            for (k, j, i) in (0..n).flat_map(|k| (0..n).flat_map(move |j| (0..n).map(move |i| (k, j, i)))) {
+                let c = Self::flat(level, i, j, k);
+                let center = self.cell_center(level, i, j, k);
+                for o in 0..8 {
+                    let (ci, cj, ck) = (2 * i + (o & 1), 2 * j + (o >> 1 & 1), 2 * k + (o >> 2));
+                    let child = Self::flat(level + 1, ci, cj, ck);
+                    if child_counts[child] == 0 {
+                        continue;
+                    }
+                    level_counts[c] += child_counts[child];
+                    let d = self.cell_center(level + 1, ci, cj, ck) - center;
+                    m2m(&child_multipoles[child], d, p, &mut level_multipoles[c]);
+                }
+            }
+            counts.push(level_counts);
+            multipoles.push(level_multipoles);
+        }
+
+        // Collected leaf-first; store root-first so `[level]` indexes them
+        counts.reverse();
+        multipoles.reverse();
+        self.counts = counts;
+        self.multipoles = multipoles;
+    }
+
+    /// M2L from the interaction lists and L2L down to the leaves.
+    fn downward_pass(&mut self) {
+        let p = self.order;
+        let len = expansion_len(p);
+        // Levels 0 and 1 have no well-separated cells, so their locals are zero
+        let mut parent_locals: Vec<Expansion> = vec![vec![Complex::ZERO; len]; 8];
+
+        for level in 2..=self.levels {
+            let n = Self::cells_per_side(level);
+            let mut locals = vec![vec![Complex::ZERO; len]; n * n * n];
+            for (k, j, i) in (0..n).flat_map(|k| (0..n).flat_map(move |j| (0..n).map(move |i| (k, j, i)))) {
+                let c = Self::flat(level, i, j, k);
+                let center = self.cell_center(level, i, j, k);
+                let (pi, pj, pk) = (i / 2, j / 2, k / 2);
+
+                let parent_center = self.cell_center(level - 1, pi, pj, pk);
+                l2l(&parent_locals[Self::flat(level - 1, pi, pj, pk)], p, center - parent_center, p, &mut locals[c]);
+
+                // Children of the parent's neighbours that are not our neighbours
+                let range = |v: usize| (2 * v.saturating_sub(1))..(2 * (v + 2)).min(n);
+                for sk in range(pk) {
+                    for sj in range(pj) {
+                        for si in range(pi) {
+                            let near = si.abs_diff(i) <= 1 && sj.abs_diff(j) <= 1 && sk.abs_diff(k) <= 1;
+                            let source = Self::flat(level, si, sj, sk);
+                            if near || self.counts[level][source] == 0 {
+                                continue;
+                            }
+                            let x = center - self.cell_center(level, si, sj, sk);
+                            m2l(&self.multipoles[level][source], p, x, p, &mut locals[c]);
+                        }
+                    }
+                }
+            }
+            parent_locals = locals;
+        }
+        self.locals = parent_locals;
+    }
+
+    fn inside(&self, r: Vector3D, margin: f64) -> bool {
+        let (x, y, z) = (r - self.center).components();
+        x.abs().max(y.abs()).max(z.abs()) <= self.half + margin
+    }
+
+    /// Direct sum over the bodies of the leaves within `reach` cells of the leaf at
+    /// `coords`, calling `visit(d, q)` with `d` pointing from the body to `s`.
+    fn near(&self, s: Vector3D, coords: [usize; 3], reach: usize, mut visit: impl FnMut(Vector3D, f64)) {
+        let n = Self::cells_per_side(self.levels);
+        let range = |v: usize| v.saturating_sub(reach)..(v + reach + 1).min(n);
+        for k in range(coords[2]) {
+            for j in range(coords[1]) {
+                for i in range(coords[0]) {
+                    let c = Self::flat(self.levels, i, j, k);
+                    for &(r, q) in &self.bodies[self.leaf_start[c]..self.leaf_start[c + 1]] {
+                        visit(s - r, q);
+                    }
+                }
+            }
+        }
+    }
+
+    /// ∑ qᵢ/|s − rᵢ| and its gradient with respect to s, to local degree `q`
+    /// (0 for the value only, 1 to include the gradient).
+    fn evaluate(&self, s: Vector3D, q: usize) -> (f64, Vector3D) {
+        let mut f = vec![Complex::ZERO; expansion_len(q.max(1))];
+        let mut direct = (0.0, Vector3D::new(0.0, 0.0, 0.0));
+        let mut add = |d: Vector3D, charge: f64| {
+            let r = d.magnitude();
+            direct.0 += charge / r;
+            if q > 0 {
+                direct.1 += d.scalar_product(&(-charge / r.powi(3)));
+            }
+        };
+
+        if self.bodies.is_empty() {
+            return direct;
+        }
+        if self.inside(s, 0.0) {
+            // L2P from our leaf, direct sums over the neighbouring leaves
+            let coords = self.cell_coords(self.levels, s);
+            let [i, j, k] = coords;
+            let leaf = &self.locals[Self::flat(self.levels, i, j, k)];
+            l2l(leaf, self.order, s - self.cell_center(self.levels, i, j, k), q, &mut f);
+            self.near(s, coords, 1, &mut add);
+        } else {
+            // Outside the cube: use whole cells once they are far enough away
+            let mut stack = vec![(0, [0, 0, 0])];
+            while let Some((level, [i, j, k])) = stack.pop() {
+                let c = Self::flat(level, i, j, k);
+                if self.counts[level][c] == 0 {
+                    continue;
+                }
+                let x = s - self.cell_center(level, i, j, k);
+                let radius = 3f64.sqrt() * self.half / Self::cells_per_side(level) as f64;
+                if radius < OUTSIDE_MAC * x.magnitude() {
+                    m2l(&self.multipoles[level][c], self.order, x, q, &mut f);
+                } else if level == self.levels {
+                    self.near(s, [i, j, k], 0, &mut add);
+                } else {
+                    for o in 0..8 {
+                        stack.push((level + 1, [2 * i + (o & 1), 2 * j + (o >> 1 & 1), 2 * k + (o >> 2)]));
+                    }
+                }
+            }
+        }
+
+        let (value, gradient) = if q > 0 { value_and_gradient(&f) } else { (f[0].re, Vector3D::new(0.0, 0.0, 0.0)) };
+        (value + direct.0, gradient + direct.1)
+    }
+}
+
+fn to_vector(s: &(f32, f32, f32)) -> Vector3D {
+    Vector3D::new(s.0 as f64, s.1 as f64, s.2 as f64)
+}
+
+impl FieldSource for Fmm {
+    fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+        K * self.evaluate(to_vector(s), 0).0
+    }
+
+    fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+        // E = −∇ϕ
+        self.evaluate(to_vector(s), 1).1.scalar_product(&-K)
+    }
+
+    fn absorbs(&self, s: &(f32, f32, f32)) -> bool {
+        let s = to_vector(s);
+        let radius = ABSORB_RADIUS as f64;
+        if self.bodies.is_empty() || !self.inside(s, radius) {
+            return false;
+        }
+        // Enough neighbouring leaves to cover the absorb radius
+        let leaf = 2.0 * self.half / Self::cells_per_side(self.levels) as f64;
+        let reach = (radius / leaf).ceil() as usize;
+        let mut hit = false;
+        self.near(s, self.cell_coords(self.levels, s), reach, |d, _| hit |= d.magnitude() < radius);
+        hit
+    }
+
+    fn extent(&self) -> f32 {
+        self.bodies.iter().fold(0.0f32, |acc, (r, _)| {
+            let (x, y, z) = r.components();
+            acc.max(x.abs().max(y.abs()).max(z.abs()) as f32)
+        })
+    }
+}
@@ -0,0 +1,76 @@
+/*
+Vector-field glyphs (a quiver plot): E sampled on a 3-D lattice or over a slice plane and drawn
+as arrows. An arrow's length is a linear or logarithmic function of |E| relative to a reference
+strength, capped at the longest arrow allowed. The reference is a high percentile of the sampled
+magnitudes rather than the maximum, so the few samples next to a point charge are clipped
+instead of shrinking every other arrow to nothing. Samples inside a source, or where E is not
+finite, get no glyph at all.
+*/
+use crate::math::{percentile_range, FieldSource, SlicePlane, Vector3D};
+
+/// Decades of |E| below the reference that a logarithmic scale still shows.
+const LOG_DECADES: f64 = 3.0;
+
+/// E at one lattice point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glyph {
+    pub position: (f32, f32, f32),
+    pub field: Vector3D,
+}
+
+/// How arrow length follows |E|.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GlyphScaling {
+    /// Proportional to |E|
+    #[default]
+    Linear,
+    /// Proportional to log₁₀ |E| over `LOG_DECADES` decades
+    Log,
+}
+
+fn glyph<F: FieldSource + ?Sized>(field: &F, p: (f32, f32, f32)) -> Option<Glyph> {
+    if field.absorbs(&p) {
+        return None;
+    }
+    let e = field.field(&p);
+    e.magnitude().is_finite().then_some(Glyph { position: p, field: e })
+}
+
+/// E on a cube of half-size `half_size` centred at the origin, `resolution` points per edge.
+pub fn sample_glyphs_cube<F: FieldSource + ?Sized>(field: &F, half_size: f64, resolution: usize) -> Vec<Glyph> {
+    let n = resolution.max(2);
+    let coord = |i: usize| (-half_size + 2.0 * half_size * i as f64 / (n - 1) as f64) as f32;
+    (0..n)
+        .flat_map(|k| (0..n).flat_map(move |j| (0..n).map(move |i| (coord(i), coord(j), coord(k)))))
+        .filter_map(|p| glyph(field, p))
+        .collect()
+}
+
+/// E on a `resolution` × `resolution` grid over `plane`.
+pub fn sample_glyphs_slice<F: FieldSource + ?Sized>(field: &F, plane: &SlicePlane, resolution: usize) -> Vec<Glyph> {
+    plane.grid_points(resolution).filter_map(|p| glyph(field, p)).collect()
+}
+
+/// |E| from the 2nd to the 98th percentile of the glyphs, (0, 0) if there are none.
+pub fn glyph_magnitude_range(glyphs: &[Glyph]) -> (f64, f64) {
+    let mut m: Vec<f64> = glyphs.iter().map(|g| g.field.magnitude()).collect();
+    percentile_range(&mut m)
+}
+
+/// Arrow length for every glyph: `max_length` at the top of [`glyph_magnitude_range`] and above,
+/// shorter below it on the chosen scale (down to 0 at zero field, or `LOG_DECADES` under it).
+pub fn glyph_lengths(glyphs: &[Glyph], scaling: GlyphScaling, max_length: f64) -> Vec<f64> {
+    let (_, reference) = glyph_magnitude_range(glyphs);
+    glyphs
+        .iter()
+        .map(|g| {
+            let ratio = if reference > 0.0 { g.field.magnitude() / reference } else { 0.0 };
+            let t = match scaling {
+                GlyphScaling::Linear => ratio,
+                GlyphScaling::Log if ratio > 0.0 => 1.0 + ratio.log10() / LOG_DECADES,
+                GlyphScaling::Log => 0.0,
+            };
+            max_length * t.clamp(0.0, 1.0)
+        })
+        .collect()
+}
@@ -0,0 +1,67 @@
+/*
+Regular 3-D sampling grids. A `ScalarGrid` holds one value per lattice point of an axis-aligned
+box; it is what the isosurface extraction works on, and what sampled fields are exported as.
+*/
+
+/// Scalar values sampled on a regular lattice, x fastest, then y, then z.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScalarGrid {
+    /// Position of lattice point (0, 0, 0) [m]
+    pub origin: [f64; 3],
+    /// Distance between neighbouring lattice points [m]
+    pub spacing: f64,
+    /// Number of lattice points along x, y, z
+    pub dims: [usize; 3],
+    pub values: Vec<f64>,
+}
+
+impl ScalarGrid {
+    /// Sample `f` on a lattice with `dims` points per axis starting at `origin`.
+    pub fn sample(
+        origin: [f64; 3],
+        spacing: f64,
+        dims: [usize; 3],
+        f: impl Fn(&(f32, f32, f32)) -> f64,
+    ) -> Self {
+        let mut values = Vec::with_capacity(dims[0] * dims[1] * dims[2]);
+        for k in 0..dims[2] {
+            for j in 0..dims[1] {
+                for i in 0..dims[0] {
+                    let p = Self::point_of(origin, spacing, [i, j, k]);
+                    values.push(f(&(p[0] as f32, p[1] as f32, p[2] as f32)));
+                }
+            }
+        }
+        Self { origin, spacing, dims, values }
+    }
+
+    /// Sample `f` on a cube of half-size `bounds` centred at the origin,
+    /// with `resolution` points along each edge.
+    pub fn sample_cube(bounds: f64, resolution: usize, f: impl Fn(&(f32, f32, f32)) -> f64) -> Self {
+        let n = resolution.max(2);
+        let spacing = 2.0 * bounds / (n - 1) as f64;
+        Self::sample([-bounds; 3], spacing, [n; 3], f)
+    }
+
+    /// Flat index of lattice point (i, j, k).
+    pub fn index(&self, i: usize, j: usize, k: usize) -> usize {
+        i + self.dims[0] * (j + self.dims[1] * k)
+    }
+
+    pub fn value(&self, i: usize, j: usize, k: usize) -> f64 {
+        self.values[self.index(i, j, k)]
+    }
+
+    /// Position of lattice point (i, j, k) [m].
+    pub fn point(&self, i: usize, j: usize, k: usize) -> [f64; 3] {
+        Self::point_of(self.origin, self.spacing, [i, j, k])
+    }
+
+    fn point_of(origin: [f64; 3], spacing: f64, ijk: [usize; 3]) -> [f64; 3] {
+        [
+            origin[0] + spacing * ijk[0] as f64,
+            origin[1] + spacing * ijk[1] as f64,
+            origin[2] + spacing * ijk[2] as f64,
+        ]
+    }
+}
@@ -0,0 +1,133 @@
+/*
+Solid harmonics for multipole expansions of arbitrary order. With the normalisation
+
+    Υₙᵐ(r) = (−1)ᵐ rⁿ Pₙᵐ(cos θ) e^{imφ} / (n + m)!        regular
+    Θₙᵐ(r) = (−1)ᵐ (n − m)! Pₙᵐ(cos θ) e^{imφ} / rⁿ⁺¹      irregular
+
+the Coulomb kernel and the translation theorems take their simplest form:
+
+    1/|x − y|    = ∑ₙₘ Υₙᵐ(y)* Θₙᵐ(x)                      (|y| < |x|)
+    Υₙᵐ(a + b)   = ∑ₖₗ Υₖˡ(b) Υₙ₋ₖᵐ⁻ˡ(a)
+    Θₙᵐ(x − y)   = ∑ₖₗ Υₖˡ(y)* Θₙ₊ₖᵐ⁺ˡ(x)                  (|y| < |x|)
+
+Both families are generated by Cartesian recurrences (no angles, no Legendre polynomials), and
+negative orders follow from Xₙ⁻ᵐ = (−1)ᵐ (Xₙᵐ)*. An expansion up to degree p is stored as a flat
+`Vec<Complex>` of length (p + 1)², entry `index(n, m)` holding degree n, order m ∈ [−n, n].
+*/
+use crate::math::Vector3D;
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+/// A complex number, just enough arithmetic for the expansions.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub const ZERO: Complex = Complex { re: 0.0, im: 0.0 };
+
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+    pub fn conj(&self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+    pub fn scale(&self, s: f64) -> Self {
+        Self::new(self.re * s, self.im * s)
+    }
+    pub fn norm(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, rhs: Self) -> Self::Output {
+        Complex::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Complex::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Complex::new(self.re * rhs.re - self.im * rhs.im, self.re * rhs.im + self.im * rhs.re)
+    }
+}
+
+impl AddAssign for Complex {
+    fn add_assign(&mut self, rhs: Self) {
+        self.re += rhs.re;
+        self.im += rhs.im;
+    }
+}
+
+/// Number of coefficients in an expansion up to degree `p`.
+pub fn expansion_len(p: usize) -> usize {
+    (p + 1) * (p + 1)
+}
+
+/// Position of degree `n`, order `m` (|m| ≤ n) in an expansion.
+pub fn index(n: usize, m: i64) -> usize {
+    ((n * n + n) as i64 + m) as usize
+}
+
+/// Fill the negative orders from Xₙ⁻ᵐ = (−1)ᵐ (Xₙᵐ)*.
+pub(crate) fn mirror(x: &mut [Complex], p: usize) {
+    for n in 1..=p {
+        for m in 1..=n as i64 {
+            let v = x[index(n, m)].conj();
+            x[index(n, -m)] = if m % 2 == 0 { v } else { v.scale(-1.0) };
+        }
+    }
+}
+
+/// Regular solid harmonics Υₙᵐ(r) for n ≤ `p`.
+pub fn regular(r: Vector3D, p: usize) -> Vec<Complex> {
+    let (x, y, z) = r.components();
+    let r2 = x * x + y * y + z * z;
+    let w = Complex::new(x, y);
+    let mut u = vec![Complex::ZERO; expansion_len(p)];
+    u[0] = Complex::new(1.0, 0.0);
+    for n in 1..=p {
+        // Υₙⁿ = (x + iy)/(2n) Υₙ₋₁ⁿ⁻¹
+        u[index(n, n as i64)] = w * u[index(n - 1, n as i64 - 1)].scale(1.0 / (2 * n) as f64);
+        // Υₙᵐ = ((2n − 1) z Υₙ₋₁ᵐ − r² Υₙ₋₂ᵐ) / ((n + m)(n − m))
+        for m in 0..n {
+            let older = if n >= m + 2 { u[index(n - 2, m as i64)] } else { Complex::ZERO };
+            let v = u[index(n - 1, m as i64)].scale((2 * n - 1) as f64 * z) - older.scale(r2);
+            u[index(n, m as i64)] = v.scale(1.0 / ((n + m) * (n - m)) as f64);
+        }
+    }
+    mirror(&mut u, p);
+    u
+}
+
+/// Irregular solid harmonics Θₙᵐ(r) for n ≤ `p`; `r` must not be zero.
+pub fn irregular(r: Vector3D, p: usize) -> Vec<Complex> {
+    let (x, y, z) = r.components();
+    let r2 = x * x + y * y + z * z;
+    let w = Complex::new(x, y);
+    let mut t = vec![Complex::ZERO; expansion_len(p)];
+    t[0] = Complex::new(1.0 / r2.sqrt(), 0.0);
+    for n in 1..=p {
+        // Θₙⁿ = (2n − 1)(x + iy)/r² Θₙ₋₁ⁿ⁻¹
+        t[index(n, n as i64)] = w * t[index(n - 1, n as i64 - 1)].scale((2 * n - 1) as f64 / r2);
+        // Θₙᵐ = ((2n − 1) z Θₙ₋₁ᵐ − ((n − 1)² − m²) Θₙ₋₂ᵐ) / r²
+        for m in 0..n {
+            let older = if n >= m + 2 { t[index(n - 2, m as i64)] } else { Complex::ZERO };
+            let k = ((n - 1) * (n - 1)) as f64 - (m * m) as f64;
+            let v = t[index(n - 1, m as i64)].scale((2 * n - 1) as f64 * z) - older.scale(k);
+            t[index(n, m as i64)] = v.scale(1.0 / r2);
+        }
+    }
+    mirror(&mut t, p);
+    t
+}
@@ -0,0 +1,29 @@
+use crate::Charge;
+
+pub fn string_to_tuple(input: &str) -> Result<(f32, f32, f32), &'static str> {
+    /*
+    This function converts input strings (of tuples) to actual tuples.
+    */
+    let trimmed = input.trim_matches(|c| c == '(' || c == ')');
+    let parts: Vec<&str> = trimmed.split(',').collect();
+
+    if parts.len() != 3 {
+        return Err("Input string must have exactly 3 components");
+    }
+
+    let x = parts[0].trim().parse::<f32>().map_err(|_| "Failed to parse x")?;
+    let y = parts[1].trim().parse::<f32>().map_err(|_| "Failed to parse y")?;
+    let z = parts[2].trim().parse::<f32>().map_err(|_| "Failed to parse z")?;
+
+    Ok((x, y, z))
+}
+
+pub fn is_position_unique(position: &str, charge: &[Charge]) -> bool {
+    let p = string_to_tuple(position).unwrap();
+    for c in charge {
+        if c.position == p {
+            return false;
+        }
+    }
+    true
+}
\ No newline at end of file
@@ -0,0 +1,118 @@
+/*
+Isosurface extraction by marching cubes. Every grid cell is split into six tetrahedra around
+its main diagonal and each tetrahedron is polygonised on its own; this needs no 256-entry
+lookup table and, unlike the classic cube cases, never leaves cracks at ambiguous faces.
+
+The output is a plain triangle soup so it can be tested and exported without any renderer.
+Triangles are wound so their normal (right-hand rule) points towards increasing values,
+i.e. along −E for a potential grid.
+*/
+use crate::math::ScalarGrid;
+
+/// A triangle given by its three corners (x, y, z) [m].
+pub type Triangle = [(f32, f32, f32); 3];
+
+/// Corner offsets of a cell, indexed by bits x + 2y + 4z.
+const CORNERS: [[usize; 3]; 8] = [
+    [0, 0, 0], [1, 0, 0], [0, 1, 0], [1, 1, 0],
+    [0, 0, 1], [1, 0, 1], [0, 1, 1], [1, 1, 1],
+];
+
+/// Six tetrahedra sharing the diagonal 0–7 that tile a cell.
+const TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 1, 3, 7], [0, 3, 2, 7], [0, 2, 6, 7],
+    [0, 6, 4, 7], [0, 4, 5, 7], [0, 5, 1, 7],
+];
+
+/// Extract the surface where the sampled values equal `level`.
+pub fn marching_cubes(grid: &ScalarGrid, level: f64) -> Vec<Triangle> {
+    let mut tris = Vec::new();
+    let [nx, ny, nz] = grid.dims;
+    if nx < 2 || ny < 2 || nz < 2 {
+        return tris;
+    }
+
+    for k in 0..nz - 1 {
+        for j in 0..ny - 1 {
+            for i in 0..nx - 1 {
+                let mut pos = [[0.0f64; 3]; 8];
+                let mut val = [0.0f64; 8];
+                for (c, off) in CORNERS.iter().enumerate() {
+                    let (ci, cj, ck) = (i + off[0], j + off[1], k + off[2]);
+                    pos[c] = grid.point(ci, cj, ck);
+                    val[c] = grid.value(ci, cj, ck);
+                }
+                // Skip cells the surface cannot cross (also skips non-finite cells)
+                let above = val.iter().filter(|v| **v >= level).count();
+                let below = val.iter().filter(|v| **v < level).count();
+                if above == 0 || below == 0 {
+                    continue;
+                }
+                for tet in TETRAHEDRA {
+                    polygonise_tetrahedron(&tet.map(|c| pos[c]), &tet.map(|c| val[c]), level, &mut tris);
+                }
+            }
+        }
+    }
+    tris
+}
+
+fn polygonise_tetrahedron(pos: &[[f64; 3]; 4], val: &[f64; 4], level: f64, out: &mut Vec<Triangle>) {
+    let (inside, outside): (Vec<usize>, Vec<usize>) = (0..4).partition(|&v| val[v] < level);
+    let crossing = |a: usize, b: usize| -> [f64; 3] {
+        let t = (level - val[a]) / (val[b] - val[a]);
+        let t = if t.is_finite() { t.clamp(0.0, 1.0) } else { 0.5 };
+        [
+            pos[a][0] + t * (pos[b][0] - pos[a][0]),
+            pos[a][1] + t * (pos[b][1] - pos[a][1]),
+            pos[a][2] + t * (pos[b][2] - pos[a][2]),
+        ]
+    };
+
+    // Direction of increasing value across this tetrahedron, used to orient the output
+    let centroid = |set: &[usize]| -> [f64; 3] {
+        let n = set.len() as f64;
+        let mut c = [0.0; 3];
+        for &v in set {
+            for (ci, pi) in c.iter_mut().zip(pos[v]) {
+                *ci += pi / n;
+            }
+        }
+        c
+    };
+    let (ci, co) = (centroid(&inside), centroid(&outside));
+    let uphill = [co[0] - ci[0], co[1] - ci[1], co[2] - ci[2]];
+
+    let mut emit = |a: [f64; 3], b: [f64; 3], c: [f64; 3]| {
+        let ab = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let ac = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+        let n = [
+            ab[1] * ac[2] - ab[2] * ac[1],
+            ab[2] * ac[0] - ab[0] * ac[2],
+            ab[0] * ac[1] - ab[1] * ac[0],
+        ];
+        let f = |p: [f64; 3]| (p[0] as f32, p[1] as f32, p[2] as f32);
+        if n[0] * uphill[0] + n[1] * uphill[1] + n[2] * uphill[2] >= 0.0 {
+            out.push([f(a), f(b), f(c)]);
+        } else {
+            out.push([f(a), f(c), f(b)]);
+        }
+    };
+
+    match (inside.len(), outside.len()) {
+        // One vertex cut off from the other three: a single triangle
+        (1, 3) | (3, 1) => {
+            let (lone, rest) = if inside.len() == 1 { (inside[0], &outside) } else { (outside[0], &inside) };
+            emit(crossing(lone, rest[0]), crossing(lone, rest[1]), crossing(lone, rest[2]));
+        }
+        // Two against two: a quadrilateral, split into two triangles
+        (2, 2) => {
+            let (a, b) = (inside[0], inside[1]);
+            let (c, d) = (outside[0], outside[1]);
+            let (p0, p1, p2, p3) = (crossing(a, c), crossing(a, d), crossing(b, d), crossing(b, c));
+            emit(p0, p1, p2);
+            emit(p0, p2, p3);
+        }
+        _ => {}
+    }
+}
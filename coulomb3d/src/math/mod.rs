@@ -0,0 +1,70 @@
+/*
+MATH MODULE, CONTAINS MATH/PHYSICS-RELATED FUNCTIONALITY:
+* VECTOR ALGEBRA
+* ELECTRODYNAMICS
+* MAGNETOSTATICS (Biot–Savart: segments, loops, solenoids)
+* SPHERICAL MULTIPOLE EXPANSIONS (arbitrary order)
+* FAST FIELD EVALUATION (Barnes–Hut octree, fast multipole method)
+* CHARGE DYNAMICS (time stepping)
+* CHARGED PARTICLE TRAJECTORIES (Lorentz force, adaptive Dormand–Prince)
+* CONTINUOUS CHARGE DISTRIBUTIONS
+* CONDUCTORS (method of images)
+* DIELECTRICS (exact half-space and sphere solutions, polarisation)
+* SAMPLING GRIDS AND ISOSURFACES (marching cubes)
+* FINITE-DIFFERENCE POISSON SOLVER (SOR, electrodes)
+* SLICE PLANES
+* VECTOR-FIELD GLYPHS (quiver plots on a lattice or a slice plane)
+* COLOUR MAPS (linear and logarithmic scales for heatmaps and coloured lines)
+* USER-PLACED FIELD LINE SEEDS (points, grids, circles)
++ also a `helpers` module
+*/
+
+// DEFINING CONSTANTS
+const PI: f64 = std::f64::consts::PI; // π
+const EPSILON: f64 = 8.8541878128E-12; // ε₀ vacuum permittivity
+pub const K: f64 = 1f64 / (4f64 * PI * EPSILON); // Coulomb's Constant 1/4πε₀
+pub const MU_0: f64 = 1.25663706212E-6; // μ₀ vacuum permeability
+
+// IMPORTING MODS
+pub mod algebra;
+pub mod backend;
+pub mod barnes_hut;
+pub mod colormap;
+pub mod conductors;
+pub mod dielectrics;
+pub mod distributions;
+pub mod fmm;
+pub mod glyphs;
+pub mod grid;
+pub mod harmonics;
+pub mod helpers;
+pub mod isosurface;
+mod ode;
+pub mod physics;
+pub mod poisson;
+pub mod seeding;
+pub mod slice;
+pub mod spherical;
+pub mod trajectories;
+
+// EXPORTING FUNCTIONS
+pub use algebra::vector::*;
+pub use backend::*;
+pub use barnes_hut::*;
+pub use colormap::*;
+pub use conductors::*;
+pub use dielectrics::*;
+pub use distributions::*;
+pub use fmm::*;
+pub use glyphs::*;
+pub use grid::*;
+pub use helpers::*;
+pub use isosurface::*;
+pub use physics::electrostatics::*; // includes generate_field_lines
+pub use physics::dynamics::*;
+pub use physics::magnetostatics::*;
+pub use poisson::*;
+pub use seeding::*;
+pub use slice::*;
+pub use spherical::*;
+pub use trajectories::*;
@@ -0,0 +1,67 @@
+/*
+Embedded Runge–Kutta integration for autonomous systems y′ = f(y). The Dormand–Prince 5(4)
+pair advances with the fifth-order solution and uses its difference to the fourth-order one as
+the local error estimate, from which the next step size follows.
+*/
+
+// Butcher tableau (Dormand & Prince 1980)
+const A: [[f64; 6]; 6] = [
+    [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+    [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+    [19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0, 0.0, 0.0],
+    [9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0, 0.0],
+    [35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0],
+];
+/// Fifth-order weights (the last row of A; the seventh stage only enters the error)
+const B5: [f64; 7] = [35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0, 0.0];
+/// Fourth-order weights
+const B4: [f64; 7] = [
+    5179.0 / 57600.0,
+    0.0,
+    7571.0 / 16695.0,
+    393.0 / 640.0,
+    -92097.0 / 339200.0,
+    187.0 / 2100.0,
+    1.0 / 40.0,
+];
+
+/// Safety factor and bounds on how much the step may change at once.
+const SAFETY: f64 = 0.9;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 5.0;
+
+/// One Dormand–Prince step of size `h` from `y`. Returns the fifth-order solution and the
+/// local error estimate per component, or None where `f` is undefined (it returned None).
+pub(crate) fn dormand_prince<const N: usize>(
+    f: impl Fn(&[f64; N]) -> Option<[f64; N]>,
+    y: &[f64; N],
+    h: f64,
+) -> Option<([f64; N], [f64; N])> {
+    let mut k = [[0.0; N]; 7];
+    k[0] = f(y)?;
+    for s in 1..7 {
+        let mut stage = *y;
+        for (j, kj) in k.iter().enumerate().take(s) {
+            for i in 0..N {
+                stage[i] += h * A[s - 1][j] * kj[i];
+            }
+        }
+        k[s] = f(&stage)?;
+    }
+    let mut next = *y;
+    let mut error = [0.0; N];
+    for (s, ks) in k.iter().enumerate() {
+        for i in 0..N {
+            next[i] += h * B5[s] * ks[i];
+            error[i] += h * (B5[s] - B4[s]) * ks[i];
+        }
+    }
+    Some((next, error))
+}
+
+/// Step size after a step of size `h` whose error was `norm` times the tolerance.
+pub(crate) fn next_step(h: f64, norm: f64) -> f64 {
+    let factor = if norm > 0.0 { SAFETY * norm.powf(-0.2) } else { MAX_FACTOR };
+    h * factor.clamp(MIN_FACTOR, MAX_FACTOR)
+}
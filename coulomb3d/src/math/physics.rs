@@ -0,0 +1,1428 @@
+pub mod electrostatics {
+    /*
+    In this module, all physical calculations are defined, for more info please refer to each function.
+    All equations need vector-algebra which is defined in algebra.rs mod.
+    Functions defined:
+    I.      Electric Potential ϕ
+    II.     Electric Field E
+    III.    Electric Dipole Moment p
+    IV.     Field Line generation (fixed-step RK4 or adaptive Dormand–Prince, seeded in proportion
+            to |q|) through any `FieldSource`
+    V.      Coulomb Force on each charge F
+    VI.     Electrostatic Potential Energy U
+    VII.    Multipole (monopole + dipole + quadrupole) approximation of ϕ and E
+    VIII.   |E|, ϕ and arc length sampled along field lines, and arrows marking their direction
+    */
+    use crate::Charge;
+    use crate::math::ode::{dormand_prince, next_step};
+    use crate::math::{K, Vector3D};
+    use serde::{Deserialize, Serialize};
+
+    /// A traced field line: the points visited, in order.
+    pub type Polyline = Vec<(f32, f32, f32)>;
+
+    /// Radius around a point charge inside which field lines are absorbed.
+    pub(crate) const ABSORB_RADIUS: f32 = 0.12;
+
+    /// Steps a field line must take before it may count as closed on itself.
+    const CLOSE_MIN_STEPS: usize = 8;
+
+    /// Anything that produces an electrostatic field: point charges, continuous
+    /// distributions, or a superposition of several of them.
+    pub trait FieldSource {
+        /// Electric potential ϕ at `s` [V].
+        fn potential(&self, s: &(f32, f32, f32)) -> f64;
+        /// Electric field E at `s` [N/C].
+        fn field(&self, s: &(f32, f32, f32)) -> Vector3D;
+        /// Whether `s` lies on or inside the source, where field lines terminate.
+        fn absorbs(&self, _s: &(f32, f32, f32)) -> bool {
+            false
+        }
+        /// Largest |coordinate| occupied by the source, used to size the bounding cube.
+        fn extent(&self) -> f32 {
+            0.0
+        }
+    }
+
+    impl FieldSource for [Charge] {
+        fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+            electric_potential(self, s)
+        }
+        fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+            electric_field(self, s)
+        }
+        fn absorbs(&self, s: &(f32, f32, f32)) -> bool {
+            self.iter().any(|c| {
+                let dx = s.0 - c.position.0;
+                let dy = s.1 - c.position.1;
+                let dz = s.2 - c.position.2;
+                (dx * dx + dy * dy + dz * dz).sqrt() < ABSORB_RADIUS
+            })
+        }
+        fn extent(&self) -> f32 {
+            self.iter().fold(0.0f32, |acc, c| {
+                acc.max(c.position.0.abs().max(c.position.1.abs()).max(c.position.2.abs()))
+            })
+        }
+    }
+
+    /// Lists of sources (`Vec<Charge>`, `Vec<Distribution>`) can be used wherever a
+    /// sized `FieldSource` is needed, e.g. as `&dyn FieldSource` in a `Superposition`.
+    impl<T> FieldSource for Vec<T>
+    where
+        [T]: FieldSource,
+    {
+        fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+            self.as_slice().potential(s)
+        }
+        fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+            self.as_slice().field(s)
+        }
+        fn absorbs(&self, s: &(f32, f32, f32)) -> bool {
+            self.as_slice().absorbs(s)
+        }
+        fn extent(&self) -> f32 {
+            self.as_slice().extent()
+        }
+    }
+
+    /// The sum of several field sources, e.g. point charges plus continuous distributions.
+    pub struct Superposition<'a> {
+        pub sources: Vec<&'a dyn FieldSource>,
+    }
+
+    impl<'a> Superposition<'a> {
+        pub fn new(sources: Vec<&'a dyn FieldSource>) -> Self {
+            Self { sources }
+        }
+    }
+
+    impl FieldSource for Superposition<'_> {
+        fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+            self.sources.iter().map(|src| src.potential(s)).sum()
+        }
+        fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+            let mut e = Vector3D::new(0.0, 0.0, 0.0);
+            for src in &self.sources {
+                e += src.field(s);
+            }
+            e
+        }
+        fn absorbs(&self, s: &(f32, f32, f32)) -> bool {
+            self.sources.iter().any(|src| src.absorbs(s))
+        }
+        fn extent(&self) -> f32 {
+            self.sources.iter().fold(0.0f32, |acc, src| acc.max(src.extent()))
+        }
+    }
+
+    struct ChargeVector {
+        charge: f64,
+        position: Vector3D,
+    }
+
+    pub fn electric_potential(charges: &[Charge], s: &(f32, f32, f32)) -> f64 {
+        /*
+        Electric Potential
+        ϕ(r′)= K ∑ (q_i / |r' - r|)
+        */
+        let mut phi = 0.0;
+        for charge in charges {
+            let c = charge_translator(charge);
+            let r = separation_calculator(c.position, *s, true);
+            let w = c.charge / r.magnitude();
+            phi += w;
+        }
+        K * phi
+    }
+
+    /// Interaction energy of a single pair of charges (indices into the configuration).
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct PairEnergy {
+        pub i: usize,
+        pub j: usize,
+        /// U_ij = K q_i q_j / r_ij   [J]
+        pub energy: f64,
+    }
+
+    /// Total interaction energy of a configuration together with its per-pair breakdown.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct PotentialEnergy {
+        /// U = ∑_{i<j} U_ij   [J]
+        pub total: f64,
+        /// One entry per unordered pair, ordered (0,1), (0,2), …, (1,2), …
+        pub pairs: Vec<PairEnergy>,
+    }
+
+    pub fn potential_energy(charges: &[Charge]) -> PotentialEnergy {
+        /*
+        Electrostatic Potential Energy (interaction energy, self-energies excluded)
+        U = K ∑_{i<j} (q_i q_j / |r_i - r_j|)
+        */
+        let mut pairs = Vec::new();
+        let mut total = 0.0;
+        for (i, a) in charges.iter().enumerate() {
+            for (j, b) in charges.iter().enumerate().skip(i + 1) {
+                let r = separation_calculator(charge_translator(b).position, a.position, true);
+                let energy = K * a.charge * b.charge / r.magnitude();
+                total += energy;
+                pairs.push(PairEnergy { i, j, energy });
+            }
+        }
+        PotentialEnergy { total, pairs }
+    }
+
+    pub fn electric_field(charges: &[Charge], s: &(f32, f32, f32)) -> Vector3D {
+        /*
+        Electric Field
+        E(r′) = K ∑ (q_i / |r' - r|^3) . (r' - r)
+        */
+        let mut e = Vector3D::new(0.0, 0.0, 0.0);
+        for charge in charges {
+            let c = charge_translator(charge);
+            let r = separation_calculator(c.position, *s, true);
+            let w = r.scalar_product(&(c.charge / r.magnitude().powf(3.0)));
+            e += w;
+        }
+        e = e.scalar_product(&K);
+        e
+    }
+
+    pub fn coulomb_forces(charges: &[Charge]) -> Vec<Vector3D> {
+        /*
+        Coulomb Force on every charge of the configuration
+        F_i = q_i E_i(r_i),   E_i = K ∑_{j≠i} (q_j / |r_i - r_j|^3) . (r_i - r_j)
+        The charge's own field is excluded.
+        */
+        charges
+            .iter()
+            .enumerate()
+            .map(|(i, target)| {
+                let mut e = Vector3D::new(0.0, 0.0, 0.0);
+                for (j, charge) in charges.iter().enumerate() {
+                    if i == j {
+                        continue;
+                    }
+                    let c = charge_translator(charge);
+                    let r = separation_calculator(c.position, target.position, true);
+                    e += r.scalar_product(&(c.charge / r.magnitude().powf(3.0)));
+                }
+                e.scalar_product(&(K * target.charge))
+            })
+            .collect()
+    }
+
+    // pub fn electric_dipole_moment(charges: &[Charge], s: &(f32, f32, f32)) -> Vector3D {
+    //     /*
+    //     Electric Dipole Moment,
+    //     p = ∑ q_i (r_i − r′)
+    //     */
+    //     let mut p = Vector3D::new(0.0, 0.0, 0.0);
+    //     for charge in charges {
+    //         let c = charge_translator(charge);
+    //         let w = separation_calculator(c.position, *s, false);
+    //         let y = w.scalar_product(&c.charge);
+    //         p += y;
+    //     }
+    //     p
+    // }
+
+    /// Multipole moments up to quadrupole (ℓ = 0, 1, 2),
+    /// all computed relative to the expansion centre `s`.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct MultipoleMoments {
+        /// ℓ=0  Monopole   Q   = ∑ qᵢ            [C]
+        pub monopole: f64,
+        /// ℓ=1  Dipole     pᵢ  = ∑ qₖ dᵢ         [C·m]
+        pub dipole: Vector3D,
+        /// ℓ=2  Quadrupole Qᵢⱼ = ∑ qₖ(3dᵢdⱼ − |d|²δᵢⱼ)  [C·m²]
+        ///      Symmetric and traceless: Q_xx + Q_yy + Q_zz = 0 always.
+        pub quadrupole: [[f64; 3]; 3],
+    }
+
+    /// Compute monopole, dipole, and (traceless) quadrupole moments
+    /// for the given charge configuration relative to the reference point `s`.
+    pub fn multipole_moments(charges: &[Charge], s: &(f32, f32, f32)) -> MultipoleMoments {
+        let (sx, sy, sz) = (s.0 as f64, s.1 as f64, s.2 as f64);
+        let mut monopole = 0.0f64;
+        let mut dipole   = Vector3D::new(0.0, 0.0, 0.0);
+        let mut quad     = [[0.0f64; 3]; 3];
+
+        for ch in charges {
+            let q = ch.charge;
+            // displacement vector from expansion centre to this charge
+            let d = [
+                ch.position.0 as f64 - sx,
+                ch.position.1 as f64 - sy,
+                ch.position.2 as f64 - sz,
+            ];
+            let r2 = d[0]*d[0] + d[1]*d[1] + d[2]*d[2];
+
+            // ℓ = 0 — monopole
+            monopole += q;
+
+            // ℓ = 1 — dipole
+            dipole += Vector3D::new(q * d[0], q * d[1], q * d[2]);
+
+            // ℓ = 2 — traceless quadrupole tensor
+            for i in 0..3 {
+                for j in 0..3 {
+                    let kd = if i == j { 1.0 } else { 0.0 }; // Kronecker delta
+                    quad[i][j] += q * (3.0 * d[i] * d[j] - r2 * kd);
+                }
+            }
+        }
+
+        MultipoleMoments { monopole, dipole, quadrupole: quad }
+    }
+
+    /// The far field of a configuration approximated by its monopole, dipole and
+    /// quadrupole moments about `center`. It is accurate well outside the sphere
+    /// around `center` that encloses the charges and meaningless inside it.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct MultipoleApproximation {
+        pub center: (f32, f32, f32),
+        pub moments: MultipoleMoments,
+    }
+
+    impl MultipoleApproximation {
+        pub fn new(charges: &[Charge], center: &(f32, f32, f32)) -> Self {
+            Self { center: *center, moments: multipole_moments(charges, center) }
+        }
+
+        /// Displacement x = s − centre and the quadratic form xᵀQx.
+        fn offset(&self, s: &(f32, f32, f32)) -> ([f64; 3], f64) {
+            let x = [
+                s.0 as f64 - self.center.0 as f64,
+                s.1 as f64 - self.center.1 as f64,
+                s.2 as f64 - self.center.2 as f64,
+            ];
+            let q = &self.moments.quadrupole;
+            let xqx = (0..3).map(|i| (0..3).map(|j| x[i] * q[i][j] * x[j]).sum::<f64>()).sum();
+            (x, xqx)
+        }
+    }
+
+    impl FieldSource for MultipoleApproximation {
+        fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+            /*
+            ϕ(x) ≈ K [ Q/r + p·x/r³ + ½ xᵀQx/r⁵ ]
+            */
+            let (x, xqx) = self.offset(s);
+            let r = (x[0] * x[0] + x[1] * x[1] + x[2] * x[2]).sqrt();
+            let (px, py, pz) = self.moments.dipole.components();
+            let p_dot_x = px * x[0] + py * x[1] + pz * x[2];
+            K * (self.moments.monopole / r + p_dot_x / r.powi(3) + 0.5 * xqx / r.powi(5))
+        }
+
+        fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+            /*
+            E(x) ≈ K [ Q x/r³ + (3(p·x) x/r² − p)/r³ + (5/2 (xᵀQx) x/r² − Qx)/r⁵ ]
+            */
+            let (x, xqx) = self.offset(s);
+            let r2 = x[0] * x[0] + x[1] * x[1] + x[2] * x[2];
+            let r = r2.sqrt();
+            let xv = Vector3D::new(x[0], x[1], x[2]);
+            let p = self.moments.dipole;
+            let q = &self.moments.quadrupole;
+            let qx = Vector3D::new(
+                q[0][0] * x[0] + q[0][1] * x[1] + q[0][2] * x[2],
+                q[1][0] * x[0] + q[1][1] * x[1] + q[1][2] * x[2],
+                q[2][0] * x[0] + q[2][1] * x[1] + q[2][2] * x[2],
+            );
+            let r3 = r2 * r;
+            let monopole = xv.scalar_product(&(self.moments.monopole / r3));
+            let dipole = xv.scalar_product(&(3.0 * p.dot_product(&xv) / (r2 * r3))) - p.scalar_product(&(1.0 / r3));
+            let quadrupole = xv.scalar_product(&(2.5 * xqx / (r2 * r3 * r2))) - qx.scalar_product(&(1.0 / (r3 * r2)));
+            (monopole + dipole + quadrupole).scalar_product(&K)
+        }
+    }
+
+    /// |E_approx − E_exact| / |E_exact| at `s`: where the multipole approximation
+    /// can be trusted (small values) and where it cannot. Points where either field
+    /// is singular (the expansion centre, a charge) count as infinitely wrong.
+    pub fn multipole_relative_error(
+        charges: &[Charge],
+        approx: &MultipoleApproximation,
+        s: &(f32, f32, f32),
+    ) -> f64 {
+        let exact = electric_field(charges, s);
+        let error = (approx.field(s) - exact).magnitude() / exact.magnitude();
+        if error.is_finite() { error } else { f64::INFINITY }
+    }
+
+    fn charge_translator(charge: &Charge) -> ChargeVector {
+        ChargeVector {
+            charge: charge.charge,
+            position: Vector3D::new(
+                charge.position.0 as f64,
+                charge.position.1 as f64,
+                charge.position.2 as f64,
+            ),
+        }
+    }
+
+    fn separation_calculator(pos: Vector3D, sv: (f32, f32, f32), reverse: bool) -> Vector3D {
+        /*
+        A separation vector between r and r`:
+        * (r′ − r_i) – points from charge to reference  (reverse = true)
+        * (r_i − r′) – points from reference to charge  (reverse = false)
+        */
+        let sv_vector = Vector3D::new(sv.0 as f64, sv.1 as f64, sv.2 as f64);
+        if reverse { sv_vector - pos } else { pos - sv_vector }
+    }
+
+    // ── Field Line Tracing ────────────────────────────────────────────────────
+
+    /// Return the normalised E-field direction at `pos`, scaled by `fwd` (+1 / −1).
+    /// Returns None when the field magnitude is negligibly small.
+    fn eval_dir<F: FieldSource + ?Sized>(
+        field: &F,
+        pos: (f32, f32, f32),
+        fwd: f32,
+    ) -> Option<(f32, f32, f32)> {
+        let e = field.field(&pos);
+        let m = e.magnitude();
+        if m < 1e-20 {
+            return None;
+        }
+        let (ex, ey, ez) = e.components();
+        Some((
+            (ex / m * fwd as f64) as f32,
+            (ey / m * fwd as f64) as f32,
+            (ez / m * fwd as f64) as f32,
+        ))
+    }
+
+    #[inline]
+    fn step_pos(p: (f32, f32, f32), d: (f32, f32, f32), s: f32) -> (f32, f32, f32) {
+        (p.0 + d.0 * s, p.1 + d.1 * s, p.2 + d.2 * s)
+    }
+
+    /// Why a field line stopped.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum LineEnd {
+        /// Ran into a source (or, adaptively traced, into a singularity of the field)
+        Absorbed,
+        /// Left the bounding cube
+        OutOfBounds,
+        /// Reached a point where the field vanishes
+        NullField,
+        /// Took `max_steps` steps
+        MaxSteps,
+        /// Returned to its seed (magnetic field lines)
+        Closed,
+    }
+
+    impl LineEnd {
+        pub fn describe(&self) -> &'static str {
+            match self {
+                LineEnd::Absorbed => "absorbed",
+                LineEnd::OutOfBounds => "out of bounds",
+                LineEnd::NullField => "null field",
+                LineEnd::MaxSteps => "max steps",
+                LineEnd::Closed => "closed",
+            }
+        }
+    }
+
+    /// How a single field line was traced.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct LineStats {
+        /// Accepted steps
+        pub steps: usize,
+        /// Steps rejected by the error control (always 0 for RK4)
+        pub rejected: usize,
+        /// Arc length [m]
+        pub length: f32,
+        pub end: LineEnd,
+    }
+
+    fn distance(a: (f32, f32, f32), b: (f32, f32, f32)) -> f32 {
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2)).sqrt()
+    }
+
+    fn outside(p: (f32, f32, f32), bounds: f32) -> bool {
+        p.0.abs() > bounds || p.1.abs() > bounds || p.2.abs() > bounds
+    }
+
+    /// Trace a single field line using 4th-order Runge–Kutta integration with a fixed step.
+    ///
+    /// `fwd = +1.0` follows the field (away from +charges),
+    /// `fwd = −1.0` runs against it (away from −charges, tracing where lines come from).
+    fn trace_rk4<F: FieldSource + ?Sized>(
+        field: &F,
+        start: (f32, f32, f32),
+        step: f32,
+        max_steps: usize,
+        bounds: f32,
+        fwd: f32,
+    ) -> (Polyline, LineStats) {
+        let mut pts = vec![start];
+        let mut p = start;
+        let mut stats = LineStats { steps: 0, rejected: 0, length: 0.0, end: LineEnd::MaxSteps };
+
+        for _ in 0..max_steps {
+            // RK4 slopes
+            let slopes = eval_dir(field, p, fwd).and_then(|k1| {
+                let k2 = eval_dir(field, step_pos(p, k1, step * 0.5), fwd)?;
+                let k3 = eval_dir(field, step_pos(p, k2, step * 0.5), fwd)?;
+                let k4 = eval_dir(field, step_pos(p, k3, step), fwd)?;
+                Some((k1, k2, k3, k4))
+            });
+            let Some((k1, k2, k3, k4)) = slopes else {
+                stats.end = LineEnd::NullField;
+                break;
+            };
+
+            // Weighted average
+            let d = (
+                (k1.0 + 2.0 * k2.0 + 2.0 * k3.0 + k4.0) / 6.0,
+                (k1.1 + 2.0 * k2.1 + 2.0 * k3.1 + k4.1) / 6.0,
+                (k1.2 + 2.0 * k2.2 + 2.0 * k3.2 + k4.2) / 6.0,
+            );
+            let np = step_pos(p, d, step);
+
+            // Stop if the new point is outside the bounding box
+            if outside(np, bounds) {
+                stats.end = LineEnd::OutOfBounds;
+                break;
+            }
+
+            // Stop if the line gets absorbed by a source (avoids numerical blow-up)
+            if field.absorbs(&np) {
+                stats.end = LineEnd::Absorbed;
+                break;
+            }
+
+            stats.steps += 1;
+            stats.length += distance(p, np);
+
+            // Stop once the line closes on itself; only magnetic field lines do that
+            if pts.len() > CLOSE_MIN_STEPS && distance(np, start) < step {
+                pts.push(start);
+                stats.end = LineEnd::Closed;
+                break;
+            }
+
+            p = np;
+            pts.push(p);
+        }
+        (pts, stats)
+    }
+
+    /// One-line summary of a set of traced lines: steps taken and how many ended which way.
+    pub fn line_stats_summary(stats: &[LineStats]) -> String {
+        let steps: usize = stats.iter().map(|s| s.steps).sum();
+        let rejected: usize = stats.iter().map(|s| s.rejected).sum();
+        let ends = [LineEnd::Absorbed, LineEnd::OutOfBounds, LineEnd::NullField, LineEnd::MaxSteps, LineEnd::Closed]
+            .iter()
+            .filter_map(|end| {
+                let n = stats.iter().filter(|s| s.end == *end).count();
+                (n > 0).then(|| format!("{n} {}", end.describe()))
+            })
+            .collect::<Vec<_>>();
+        format!("{} lines, {steps} steps ({rejected} rejected): {}", stats.len(), ends.join(", "))
+    }
+
+    /// Unit field direction at `y`, scaled by `fwd`; None where the field vanishes.
+    fn direction<F: FieldSource + ?Sized>(field: &F, y: &[f64; 3], fwd: f64) -> Option<[f64; 3]> {
+        let e = field.field(&(y[0] as f32, y[1] as f32, y[2] as f32));
+        let m = e.magnitude();
+        if !(m >= 1e-20 && m.is_finite()) {
+            return None;
+        }
+        let (ex, ey, ez) = e.scalar_product(&(fwd / m)).components();
+        Some([ex, ey, ez])
+    }
+
+    /// Trace a single field line with the adaptive Dormand–Prince 5(4) scheme, parametrised by
+    /// arc length. Steps grow along straight runs and shrink where the line bends, keeping the
+    /// local position error below `tolerance`. A line that reaches an absorbing source ends on
+    /// its surface (the last step is bisected onto it); one whose step collapses below
+    /// `min_step` has run into a singularity such as a point charge.
+    fn trace_adaptive<F: FieldSource + ?Sized>(
+        field: &F,
+        start: (f32, f32, f32),
+        settings: &FieldLineSettings,
+        bounds: f32,
+        fwd: f32,
+    ) -> (Polyline, LineStats) {
+        let (min_step, max_step) = (settings.min_step, settings.max_step.max(settings.min_step));
+        let rhs = |y: &[f64; 3]| direction(field, y, fwd as f64);
+        let to_point = |y: &[f64; 3]| (y[0] as f32, y[1] as f32, y[2] as f32);
+
+        let mut pts = vec![start];
+        let mut y = [start.0 as f64, start.1 as f64, start.2 as f64];
+        let mut h = settings.step.clamp(min_step, max_step);
+        let mut stats = LineStats { steps: 0, rejected: 0, length: 0.0, end: LineEnd::MaxSteps };
+
+        while stats.steps < settings.max_steps && stats.rejected < settings.max_steps {
+            let Some((next, error)) = dormand_prince(rhs, &y, h) else {
+                // A stage hit a null point: creep up on it before giving up
+                if h > min_step {
+                    h = (h * 0.25).max(min_step);
+                    stats.rejected += 1;
+                    continue;
+                }
+                stats.end = LineEnd::NullField;
+                break;
+            };
+            let norm = error.iter().fold(0.0f64, |m, e| m.max(e.abs())) / settings.tolerance;
+            if norm > 1.0 {
+                if h <= min_step {
+                    stats.end = LineEnd::Absorbed;
+                    break;
+                }
+                h = next_step(h, norm).max(min_step);
+                stats.rejected += 1;
+                continue;
+            }
+
+            let mut np = to_point(&next);
+            if outside(np, bounds) {
+                stats.end = LineEnd::OutOfBounds;
+                break;
+            }
+            if field.absorbs(&np) {
+                // Bisect the step length for the surface of the absorbing source
+                let (mut lo, mut hi) = (0.0, h);
+                for _ in 0..12 {
+                    let mid = 0.5 * (lo + hi);
+                    match dormand_prince(rhs, &y, mid) {
+                        Some((p, _)) if !field.absorbs(&to_point(&p)) => lo = mid,
+                        _ => hi = mid,
+                    }
+                }
+                np = dormand_prince(rhs, &y, lo).map_or(to_point(&y), |(p, _)| to_point(&p));
+                stats.length += distance(to_point(&y), np);
+                stats.steps += 1;
+                pts.push(np);
+                stats.end = LineEnd::Absorbed;
+                break;
+            }
+
+            stats.steps += 1;
+            stats.length += distance(to_point(&y), np);
+            if pts.len() > CLOSE_MIN_STEPS && distance(np, start) < h as f32 {
+                pts.push(start);
+                stats.end = LineEnd::Closed;
+                break;
+            }
+            y = next;
+            pts.push(np);
+            h = next_step(h, norm).clamp(min_step, max_step);
+        }
+        (pts, stats)
+    }
+
+    /// How field lines are integrated.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Integrator {
+        /// Classic RK4 with the fixed `step`
+        #[default]
+        Rk4,
+        /// Dormand–Prince 5(4) with error control, steps between `min_step` and `max_step`
+        Adaptive,
+    }
+
+    /// How many field lines each charge gets.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Seeding {
+        /// `seeds` lines from every charge, whatever its magnitude
+        PerCharge,
+        /// Lines proportional to |q| (Gauss's law), `seeds` for the largest charge; a line
+        /// from a positive charge to a negative one is drawn once
+        #[default]
+        Flux,
+    }
+
+    /// Tunable parameters of the field line tracer.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct FieldLineSettings {
+        /// Seed points per charge; under flux seeding, for the largest |q|
+        pub seeds: usize,
+        pub seeding: Seeding,
+        /// Seed sphere radius (metres)
+        pub seed_radius: f64,
+        /// RK4 integration step; the first trial step of the adaptive integrator
+        pub step: f64,
+        /// Max steps per line
+        pub max_steps: usize,
+        pub integrator: Integrator,
+        /// Adaptive only: local position error allowed per step (metres)
+        pub tolerance: f64,
+        /// Adaptive only: smallest step (metres)
+        pub min_step: f64,
+        /// Adaptive only: largest step (metres)
+        pub max_step: f64,
+    }
+
+    impl Default for FieldLineSettings {
+        fn default() -> Self {
+            Self {
+                seeds: 12,
+                seeding: Seeding::Flux,
+                seed_radius: 0.35,
+                step: 0.1,
+                max_steps: 300,
+                integrator: Integrator::Rk4,
+                tolerance: 1e-5,
+                min_step: 1e-4,
+                max_step: 0.5,
+            }
+        }
+    }
+
+    /// Generate electric field lines for the given charge configuration
+    /// using the default [`FieldLineSettings`].
+    ///
+    /// Returns `(polylines, half_size_of_bounding_cube)`.
+    pub fn generate_field_lines(
+        charges: &[Charge],
+    ) -> (Vec<Polyline>, f32) {
+        generate_field_lines_with(charges, &FieldLineSettings::default())
+    }
+
+    /// Where a field line starts and which way it is traced:
+    /// `direction = +1` follows E, `direction = −1` runs against it.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Seed {
+        pub position: (f32, f32, f32),
+        pub direction: f32,
+    }
+
+    /// `n` unit vectors spread over the sphere on a Fibonacci lattice (the first and last at the poles).
+    fn fibonacci_sphere(n: usize) -> Vec<(f32, f32, f32)> {
+        // Fibonacci sphere golden angle
+        let ga = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+        (0..n)
+            .map(|i| {
+                let t  = if n > 1 { i as f32 / (n - 1) as f32 } else { 0.5 };
+                let y  = 1.0 - 2.0 * t;
+                let r  = (1.0 - y * y).sqrt();
+                let th = ga * i as f32;
+                (r * th.cos(), y, r * th.sin())
+            })
+            .collect()
+    }
+
+    /// Lines belonging to `charge` under flux seeding: `settings.seeds` for the largest |q| in
+    /// `charges`, proportionally fewer for the others.
+    fn flux_lines(charges: &[Charge], charge: f64, settings: &FieldLineSettings) -> usize {
+        let largest = charges.iter().fold(0.0f64, |m, c| m.max(c.charge.abs()));
+        if largest == 0.0 {
+            return 0;
+        }
+        (settings.seeds as f64 * charge.abs() / largest).round() as usize
+    }
+
+    /// Seeds placed on a small Fibonacci sphere around each charge.
+    /// Positive charges emit lines forward along E; negative charges emit
+    /// lines backward (showing the paths that terminate there). Every charge gets
+    /// `settings.seeds` with [`Seeding::PerCharge`], a number proportional to |q| with
+    /// [`Seeding::Flux`].
+    pub fn charge_seeds(charges: &[Charge], settings: &FieldLineSettings) -> Vec<Seed> {
+        let sr = settings.seed_radius as f32;
+        let mut out = Vec::new();
+
+        for ch in charges {
+            let (cx, cy, cz) = ch.position;
+            // Forward along E for positive charges, backward for negative
+            let fwd = if ch.charge >= 0.0 { 1.0f32 } else { -1.0 };
+            let n = match settings.seeding {
+                Seeding::PerCharge => settings.seeds,
+                Seeding::Flux => flux_lines(charges, ch.charge, settings),
+            };
+            out.extend(fibonacci_sphere(n).into_iter().map(|d| Seed {
+                position: (cx + sr * d.0, cy + sr * d.1, cz + sr * d.2),
+                direction: fwd,
+            }));
+        }
+        out
+    }
+
+    /// Field lines of `charges` through `field`, together with those from `extra` seeds (of
+    /// distributions, say), seeded by [`charge_seeds`].
+    ///
+    /// With [`Seeding::Flux`] a line running from a positive to a negative charge is drawn
+    /// once: of the lines traced backward from negative charges only those are kept that do
+    /// not end on a positive charge, i.e. the ones coming in from infinity (or from other
+    /// sources). Together with the proportional seeding, as many lines leave the picture as
+    /// the net charge carries.
+    ///
+    /// Returns `(polylines, statistics_per_line, half_size_of_bounding_cube)`.
+    pub fn generate_charge_field_lines<F: FieldSource + ?Sized>(
+        field: &F,
+        charges: &[Charge],
+        extra: &[Seed],
+        settings: &FieldLineSettings,
+    ) -> (Vec<Polyline>, Vec<LineStats>, f32) {
+        let (mut seeds, sinks): (Vec<Seed>, Vec<Seed>) = charge_seeds(charges, settings)
+            .into_iter()
+            .partition(|s| s.direction > 0.0 || settings.seeding == Seeding::PerCharge);
+        seeds.extend_from_slice(extra);
+        let (mut lines, mut stats, bounds) = generate_field_lines_with_stats(field, &seeds, settings);
+
+        // An absorbed line stops up to one step short of the absorbing sphere; adaptive steps
+        // grow from the first trial `step` up to `max_step`
+        let longest = match settings.integrator {
+            Integrator::Rk4 => settings.step,
+            Integrator::Adaptive => settings.max_step.max(settings.min_step),
+        };
+        let reach = ABSORB_RADIUS + longest as f32;
+        let on_positive = |p: &(f32, f32, f32)| charges.iter().any(|c| c.charge > 0.0 && distance(*p, c.position) < reach);
+        let (more, more_stats, _) = generate_field_lines_with_stats(field, &sinks, settings);
+        for (line, line_stats) in more.into_iter().zip(more_stats) {
+            if !(line_stats.end == LineEnd::Absorbed && line.last().is_some_and(on_positive)) {
+                lines.push(line);
+                stats.push(line_stats);
+            }
+        }
+        (lines, stats, bounds)
+    }
+
+    /// Generate electric field lines for the given charge configuration.
+    ///
+    /// Seeds are placed by [`charge_seeds`].
+    ///
+    /// Returns `(polylines, half_size_of_bounding_cube)`.
+    pub fn generate_field_lines_with(
+        charges: &[Charge],
+        settings: &FieldLineSettings,
+    ) -> (Vec<Polyline>, f32) {
+        if charges.is_empty() {
+            return (Vec::new(), 0.0);
+        }
+        let (lines, _, bounds) = generate_charge_field_lines(charges, charges, &[], settings);
+        (lines, bounds)
+    }
+
+    /// Half-size of the cube centred at the origin that field lines and sampled grids
+    /// are confined to: large enough to contain all sources plus a margin.
+    pub fn bounding_half_size<F: FieldSource + ?Sized>(field: &F) -> f32 {
+        (field.extent() + 3.0).max(4.0)
+    }
+
+    /// Trace one field line from every seed through an arbitrary field source.
+    ///
+    /// Returns `(polylines, half_size_of_bounding_cube)`.
+    pub fn generate_field_lines_in<F: FieldSource + ?Sized>(
+        field: &F,
+        seeds: &[Seed],
+        settings: &FieldLineSettings,
+    ) -> (Vec<Polyline>, f32) {
+        let (lines, _, bounds) = generate_field_lines_with_stats(field, seeds, settings);
+        (lines, bounds)
+    }
+
+    /// Like [`generate_field_lines_in`], also reporting how each line was traced.
+    ///
+    /// Returns `(polylines, statistics_per_line, half_size_of_bounding_cube)`.
+    pub fn generate_field_lines_with_stats<F: FieldSource + ?Sized>(
+        field: &F,
+        seeds: &[Seed],
+        settings: &FieldLineSettings,
+    ) -> (Vec<Polyline>, Vec<LineStats>, f32) {
+        let bounds = bounding_half_size(field);
+
+        let mut lines: Vec<Polyline> = Vec::new();
+        let mut stats: Vec<LineStats> = Vec::new();
+        for seed in seeds {
+            let (line, line_stats) = match settings.integrator {
+                Integrator::Rk4 => trace_rk4(
+                    field,
+                    seed.position,
+                    settings.step as f32,
+                    settings.max_steps,
+                    bounds,
+                    seed.direction,
+                ),
+                Integrator::Adaptive => trace_adaptive(field, seed.position, settings, bounds, seed.direction),
+            };
+            if line.len() > 3 {
+                lines.push(line);
+                stats.push(line_stats);
+            }
+        }
+
+        (lines, stats, bounds)
+    }
+
+    /// A scalar carried by every vertex of a field line, for colouring.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub enum LineQuantity {
+        /// |E| [N/C]
+        #[default]
+        FieldMagnitude,
+        /// ϕ [V]
+        Potential,
+        /// Distance travelled from the seed [m]
+        ArcLength,
+    }
+
+    impl LineQuantity {
+        pub fn label(&self) -> &'static str {
+            match self {
+                LineQuantity::FieldMagnitude => "|E| [N/C]",
+                LineQuantity::Potential => "phi [V]",
+                LineQuantity::ArcLength => "s [m]",
+            }
+        }
+    }
+
+    /// |E|, ϕ and arc length at each vertex of one field line.
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct LineScalars {
+        pub field: Vec<f64>,
+        pub potential: Vec<f64>,
+        pub arc_length: Vec<f64>,
+    }
+
+    impl LineScalars {
+        /// Sample `field` along `line`.
+        pub fn sample<F: FieldSource + ?Sized>(field: &F, line: &Polyline) -> Self {
+            let mut s = 0.0;
+            let arc_length = line
+                .iter()
+                .enumerate()
+                .map(|(i, p)| {
+                    if i > 0 {
+                        s += distance(line[i - 1], *p) as f64;
+                    }
+                    s
+                })
+                .collect();
+            Self {
+                field: line.iter().map(|p| field.field(p).magnitude()).collect(),
+                potential: line.iter().map(|p| field.potential(p)).collect(),
+                arc_length,
+            }
+        }
+
+        pub fn get(&self, quantity: LineQuantity) -> &[f64] {
+            match quantity {
+                LineQuantity::FieldMagnitude => &self.field,
+                LineQuantity::Potential => &self.potential,
+                LineQuantity::ArcLength => &self.arc_length,
+            }
+        }
+    }
+
+    /// A direction mark on a field line.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct LineArrow {
+        pub position: (f32, f32, f32),
+        /// Unit vector along E
+        pub direction: Vector3D,
+        /// Index of the segment (its first vertex) the mark lies on
+        pub segment: usize,
+    }
+
+    /// Marks every `spacing` metres of arc length along `line`, the first half a spacing from its
+    /// start. They point along E whichever way the line was traced: the segment's direction is
+    /// flipped where it runs against the field. Points where E vanishes get no mark.
+    pub fn line_arrows<F: FieldSource + ?Sized>(field: &F, line: &Polyline, spacing: f64) -> Vec<LineArrow> {
+        let mut arrows = Vec::new();
+        if spacing.is_nan() || spacing <= 0.0 {
+            return arrows;
+        }
+        let mut next = 0.5 * spacing;
+        let mut s = 0.0;
+        for (i, seg) in line.windows(2).enumerate() {
+            let (a, b) = (seg[0], seg[1]);
+            let length = distance(a, b) as f64;
+            let d = (b.0 - a.0, b.1 - a.1, b.2 - a.2);
+            let tangent = Vector3D::new(d.0 as f64, d.1 as f64, d.2 as f64).normalized();
+            while length > 0.0 && next <= s + length {
+                let position = step_pos(a, d, ((next - s) / length) as f32);
+                let along = field.field(&position).dot_product(&tangent);
+                if along.is_finite() && along != 0.0 {
+                    arrows.push(LineArrow { position, direction: tangent.scalar_product(&along.signum()), segment: i });
+                }
+                next += spacing;
+            }
+            s += length;
+        }
+        arrows
+    }
+
+    /// Smallest and largest finite value of `quantity` over all lines, or None if there is none.
+    pub fn line_scalar_range(scalars: &[LineScalars], quantity: LineQuantity) -> Option<(f64, f64)> {
+        scalars
+            .iter()
+            .flat_map(|s| s.get(quantity))
+            .filter(|v| v.is_finite())
+            .fold(None, |range, &v| match range {
+                None => Some((v, v)),
+                Some((lo, hi)) => Some((f64::min(lo, v), f64::max(hi, v))),
+            })
+    }
+}
+
+pub mod magnetostatics {
+    /*
+    Steady currents and the magnetic field they produce, by the Biot–Savart law
+    B(r′) = μ₀/4π ∮ I dl × (r′ − r) / |r′ − r|³, evaluated in closed form for each element.
+    Functions defined:
+    I.      Magnetic Field B of straight segments, circular loops and solenoids
+    II.     Magnetic Dipole Moment m
+    III.    B-field Line generation through the RK4 tracer of `electrostatics`
+    */
+    use crate::math::distributions::plane_basis;
+    use crate::math::{FieldLineSettings, FieldSource, Seed, Vector3D, MU_0, PI};
+    use serde::{Deserialize, Serialize};
+
+    /// Radius of the wires [m]: B-field lines coming closer are absorbed.
+    const WIRE_RADIUS: f64 = 0.05;
+
+    /// A current-carrying element. Points and directions are (x, y, z) in metres; directions
+    /// need not be unit length.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "kind", rename_all = "snake_case")]
+    pub enum Current {
+        /// Straight wire carrying `current` [A] from `start` to `end`
+        Segment { start: [f64; 3], end: [f64; 3], current: f64 },
+        /// Circular loop; a positive `current` circulates anticlockwise seen from the tip of `normal`.
+        Loop { center: [f64; 3], normal: [f64; 3], radius: f64, current: f64 },
+        /// `turns` loops spread evenly over `length` along `axis`, centred on `center`
+        Solenoid { center: [f64; 3], axis: [f64; 3], radius: f64, length: f64, turns: usize, current: f64 },
+    }
+
+    fn vector(a: [f64; 3]) -> Vector3D {
+        Vector3D::new(a[0], a[1], a[2])
+    }
+
+    /// Complete elliptic integrals K(m) and E(m) of parameter m = k², by the
+    /// arithmetic–geometric mean.
+    fn elliptic(m: f64) -> (f64, f64) {
+        let (mut a, mut b) = (1.0f64, (1.0 - m).max(0.0).sqrt());
+        let mut sum = 0.5 * m;
+        let mut weight = 0.5;
+        for _ in 0..64 {
+            let c = 0.5 * (a - b);
+            if c.abs() < 1e-15 * a {
+                break;
+            }
+            (a, b) = (0.5 * (a + b), (a * b).sqrt());
+            weight *= 2.0;
+            sum += weight * c * c;
+        }
+        let k = PI / (2.0 * a);
+        (k, k * (1.0 - sum))
+    }
+
+    /// Exact field of a circular loop (Simpson et al., NASA/TM-2001-210640): with ρ and z the
+    /// cylindrical coordinates of `p` about the loop axis and α², β² = a² + r² ∓ 2aρ,
+    ///     B_ρ = μ₀I z / (2πα²βρ) [(a² + r²) E(k²) − α² K(k²)]
+    ///     B_z = μ₀I / (2πα²β) [(a² − r²) E(k²) + α² K(k²)],   k² = 1 − α²/β²
+    fn loop_field(center: Vector3D, normal: Vector3D, a: f64, current: f64, p: Vector3D) -> Vector3D {
+        let d = p - center;
+        let z = d.dot_product(&normal);
+        let radial = d - normal.scalar_product(&z);
+        let rho = radial.magnitude();
+        let r2 = rho * rho + z * z;
+        let alpha2 = a * a + r2 - 2.0 * a * rho;
+        if alpha2 < 1e-18 * a * a {
+            return Vector3D::new(0.0, 0.0, 0.0);
+        }
+        let beta2 = a * a + r2 + 2.0 * a * rho;
+        let beta = beta2.sqrt();
+        let (k, e) = elliptic(1.0 - alpha2 / beta2);
+        let c = MU_0 * current / PI;
+        let bz = c / (2.0 * alpha2 * beta) * ((a * a - r2) * e + alpha2 * k);
+        let mut b = normal.scalar_product(&bz);
+        // On the axis B_ρ vanishes by symmetry
+        if rho > 1e-12 * a {
+            let brho = c * z / (2.0 * alpha2 * beta * rho) * ((a * a + r2) * e - alpha2 * k);
+            b += radial.scalar_product(&(brho / rho));
+        }
+        b
+    }
+
+    /// Exact field of a straight segment from `a` to `b`: with ρ the perpendicular from the
+    /// wire's line to `p` and θ₁, θ₂ the angles under which the ends are seen,
+    ///     B = μ₀I / (4π|ρ|) (cos θ₁ − cos θ₂) û × ρ̂
+    fn segment_field(a: Vector3D, b: Vector3D, current: f64, p: Vector3D) -> Vector3D {
+        let u = (b - a).normalized();
+        let (r1, r2) = (p - a, p - b);
+        let rho = r1 - u.scalar_product(&r1.dot_product(&u));
+        let d2 = rho.dot_product(&rho);
+        if d2 < 1e-20 {
+            return Vector3D::new(0.0, 0.0, 0.0);
+        }
+        let ends = r1.dot_product(&u) / r1.magnitude() - r2.dot_product(&u) / r2.magnitude();
+        u.cross_product(&rho).scalar_product(&(MU_0 * current / (4.0 * PI) * ends / d2))
+    }
+
+    impl Current {
+        /// Centres of the individual turns: one for a loop, `turns` for a solenoid.
+        fn turns(&self) -> Vec<Vector3D> {
+            match *self {
+                Current::Segment { .. } => Vec::new(),
+                Current::Loop { center, .. } => vec![vector(center)],
+                Current::Solenoid { center, axis, length, turns, .. } => {
+                    let n = vector(axis).normalized();
+                    (0..turns)
+                        .map(|i| {
+                            let t = (i as f64 + 0.5) / turns as f64 - 0.5;
+                            vector(center) + n.scalar_product(&(t * length))
+                        })
+                        .collect()
+                }
+            }
+        }
+
+        /// Magnetic field B at `p` [T].
+        pub fn field(&self, p: Vector3D) -> Vector3D {
+            match *self {
+                Current::Segment { start, end, current } => segment_field(vector(start), vector(end), current, p),
+                Current::Loop { normal: axis, radius, current, .. } | Current::Solenoid { axis, radius, current, .. } => {
+                    let n = vector(axis).normalized();
+                    let mut b = Vector3D::new(0.0, 0.0, 0.0);
+                    for c in self.turns() {
+                        b += loop_field(c, n, radius, current, p);
+                    }
+                    b
+                }
+            }
+        }
+
+        /// Magnetic dipole moment m = ½ ∮ r × I dl [A·m²]: I·area along the axis for loops
+        /// and solenoids. For segments it depends on the origin and is only meaningful summed
+        /// over a closed circuit.
+        pub fn dipole_moment(&self) -> Vector3D {
+            match *self {
+                Current::Segment { start, end, current } => {
+                    vector(start).cross_product(&vector(end)).scalar_product(&(0.5 * current))
+                }
+                Current::Loop { normal, radius, current, .. } => {
+                    vector(normal).normalized().scalar_product(&(current * PI * radius * radius))
+                }
+                Current::Solenoid { axis, radius, turns, current, .. } => {
+                    vector(axis).normalized().scalar_product(&(turns as f64 * current * PI * radius * radius))
+                }
+            }
+        }
+
+        /// Distance from `p` to the wire [m]; a solenoid counts as a cylindrical winding.
+        pub fn distance(&self, p: Vector3D) -> f64 {
+            match *self {
+                Current::Segment { start, end, .. } => {
+                    let (a, b) = (vector(start), vector(end));
+                    let u = (b - a).normalized();
+                    let t = (p - a).dot_product(&u).clamp(0.0, (b - a).magnitude());
+                    (p - a - u.scalar_product(&t)).magnitude()
+                }
+                Current::Loop { center, normal: axis, radius, .. }
+                | Current::Solenoid { center, axis, radius, .. } => {
+                    let half = match *self {
+                        Current::Solenoid { length, .. } => 0.5 * length,
+                        _ => 0.0,
+                    };
+                    let n = vector(axis).normalized();
+                    let d = p - vector(center);
+                    let z = d.dot_product(&n);
+                    let rho = (d - n.scalar_product(&z)).magnitude();
+                    let dz = z.abs() - half.min(z.abs());
+                    ((rho - radius).powi(2) + dz * dz).sqrt()
+                }
+            }
+        }
+
+        /// Largest |coordinate| reached by the wire.
+        pub fn extent(&self) -> f64 {
+            let reach = |c: [f64; 3]| c.iter().fold(0.0f64, |acc, x| acc.max(x.abs()));
+            match *self {
+                Current::Segment { start, end, .. } => reach(start).max(reach(end)),
+                Current::Loop { center, radius, .. } => reach(center) + radius,
+                Current::Solenoid { center, radius, length, .. } => reach(center) + radius + 0.5 * length,
+            }
+        }
+    }
+
+    pub fn magnetic_field(currents: &[Current], s: &(f32, f32, f32)) -> Vector3D {
+        /*
+        Magnetic Field
+        B(r′) = ∑ B_i(r′), each element in closed form (see `Current::field`)
+        */
+        let p = Vector3D::new(s.0 as f64, s.1 as f64, s.2 as f64);
+        let mut b = Vector3D::new(0.0, 0.0, 0.0);
+        for c in currents {
+            b += c.field(p);
+        }
+        b
+    }
+
+    /// Total magnetic dipole moment of the currents [A·m²].
+    pub fn magnetic_dipole_moment(currents: &[Current]) -> Vector3D {
+        let mut m = Vector3D::new(0.0, 0.0, 0.0);
+        for c in currents {
+            m += c.dipole_moment();
+        }
+        m
+    }
+
+    /// The B field of a set of currents presented as a `FieldSource`, so the field line tracer
+    /// can follow it. B has no scalar potential here: `potential` is always zero.
+    pub struct MagneticField<'a> {
+        pub currents: &'a [Current],
+    }
+
+    impl<'a> MagneticField<'a> {
+        pub fn new(currents: &'a [Current]) -> Self {
+            Self { currents }
+        }
+    }
+
+    impl FieldSource for MagneticField<'_> {
+        fn potential(&self, _s: &(f32, f32, f32)) -> f64 {
+            0.0
+        }
+        fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+            magnetic_field(self.currents, s)
+        }
+        fn absorbs(&self, s: &(f32, f32, f32)) -> bool {
+            let p = Vector3D::new(s.0 as f64, s.1 as f64, s.2 as f64);
+            self.currents.iter().any(|c| c.distance(p) < WIRE_RADIUS)
+        }
+        fn extent(&self) -> f32 {
+            self.currents.iter().fold(0.0f64, |acc, c| acc.max(c.extent())) as f32
+        }
+    }
+
+    /// Seeds for B-field lines, `settings.seeds` per element: spread over the disc inside
+    /// loops (in the middle plane of solenoids), and along a line leading away from the middle
+    /// of segments. Every line is traced along B until it closes on itself.
+    pub fn current_seeds(currents: &[Current], settings: &FieldLineSettings) -> Vec<Seed> {
+        let n = settings.seeds.max(1);
+        let ga = PI * (3.0 - 5.0f64.sqrt());
+        let seed = |p: Vector3D| {
+            let (x, y, z) = p.components();
+            Seed { position: (x as f32, y as f32, z as f32), direction: 1.0 }
+        };
+        let mut out = Vec::with_capacity(currents.len() * n);
+        for c in currents {
+            match *c {
+                Current::Segment { start, end, .. } => {
+                    let (a, b) = (vector(start), vector(end));
+                    let (u, _) = plane_basis((b - a).normalized());
+                    let middle = (a + b).scalar_product(&0.5);
+                    out.extend((0..n).map(|i| seed(middle + u.scalar_product(&(settings.seed_radius * (i + 1) as f64)))));
+                }
+                Current::Loop { center, normal: axis, radius, .. }
+                | Current::Solenoid { center, axis, radius, .. } => {
+                    // Fibonacci disc
+                    let (u, v) = plane_basis(vector(axis).normalized());
+                    out.extend((0..n).map(|i| {
+                        let r = 0.9 * radius * ((i as f64 + 0.5) / n as f64).sqrt();
+                        let th = ga * i as f64;
+                        seed(vector(center) + u.scalar_product(&(r * th.cos())) + v.scalar_product(&(r * th.sin())))
+                    }));
+                }
+            }
+        }
+        out
+    }
+}
+
+pub mod dynamics {
+    /*
+    Time-stepped dynamics: charges with a mass and velocity moving under their mutual Coulomb
+    forces. Positions are kept in f64 while the simulation runs and only rounded to the f32
+    `Charge` positions when the configuration is handed back to the rest of the app.
+    Functions defined:
+    I.      Velocity Verlet step (symplectic, 2nd order)
+    II.     Kinetic / potential / total energy and linear momentum
+    III.    Energy and momentum drift since the start of the run
+    */
+    use crate::Charge;
+    use crate::math::{K, Vector3D};
+
+    /// Mechanical properties that turn a static charge into a moving body.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Kinematics {
+        /// Mass [kg]
+        pub mass: f64,
+        /// Velocity [m/s]
+        pub velocity: (f64, f64, f64),
+        /// Pinned bodies never move but still push the others around.
+        pub pinned: bool,
+    }
+
+    impl Default for Kinematics {
+        fn default() -> Self {
+            Self { mass: 1.0, velocity: (0.0, 0.0, 0.0), pinned: false }
+        }
+    }
+
+    /// How far the conserved quantities have wandered since the run started.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Drift {
+        /// (E − E₀) / |E₀|, or the absolute change when E₀ = 0   [1] or [J]
+        pub energy: f64,
+        /// |P − P₀|   [kg·m/s]. Only meaningful when no body is pinned.
+        pub momentum: f64,
+    }
+
+    /// A running simulation of point charges under mutual Coulomb forces.
+    pub struct Simulation {
+        charges: Vec<f64>,
+        bodies: Vec<Kinematics>,
+        positions: Vec<Vector3D>,
+        velocities: Vec<Vector3D>,
+        accelerations: Vec<Vector3D>,
+        /// Plummer softening length ε [m]: pair distances are taken as √(r² + ε²)
+        /// so close encounters stay finite. 0 gives the exact Coulomb law.
+        softening: f64,
+        /// Elapsed simulated time [s]
+        pub time: f64,
+        initial_energy: f64,
+        initial_momentum: Vector3D,
+    }
+
+    impl Simulation {
+        /// Start a run from the given configuration; `bodies[i]` belongs to `charges[i]`.
+        pub fn new(charges: &[Charge], bodies: &[Kinematics], softening: f64) -> Self {
+            assert_eq!(charges.len(), bodies.len(), "one Kinematics per Charge");
+            let positions = charges
+                .iter()
+                .map(|c| Vector3D::new(c.position.0 as f64, c.position.1 as f64, c.position.2 as f64))
+                .collect();
+            let velocities = bodies
+                .iter()
+                .map(|b| {
+                    if b.pinned { Vector3D::new(0.0, 0.0, 0.0) }
+                    else { Vector3D::new(b.velocity.0, b.velocity.1, b.velocity.2) }
+                })
+                .collect();
+            let mut sim = Self {
+                charges: charges.iter().map(|c| c.charge).collect(),
+                bodies: bodies.to_vec(),
+                positions,
+                velocities,
+                accelerations: Vec::new(),
+                softening,
+                time: 0.0,
+                initial_energy: 0.0,
+                initial_momentum: Vector3D::new(0.0, 0.0, 0.0),
+            };
+            sim.accelerations = sim.compute_accelerations();
+            sim.initial_energy = sim.total_energy();
+            sim.initial_momentum = sim.momentum();
+            sim
+        }
+
+        /// Advance by one velocity Verlet step of length `dt` [s]:
+        /// v½ = v + a·dt/2,  x' = x + v½·dt,  v' = v½ + a(x')·dt/2
+        pub fn step(&mut self, dt: f64) {
+            let half = 0.5 * dt;
+            for i in 0..self.positions.len() {
+                if self.bodies[i].pinned {
+                    continue;
+                }
+                self.velocities[i] += self.accelerations[i].scalar_product(&half);
+                self.positions[i] += self.velocities[i].scalar_product(&dt);
+            }
+            self.accelerations = self.compute_accelerations();
+            for i in 0..self.positions.len() {
+                if !self.bodies[i].pinned {
+                    self.velocities[i] += self.accelerations[i].scalar_product(&half);
+                }
+            }
+            self.time += dt;
+        }
+
+        /// The current configuration, positions rounded to f32.
+        pub fn charges(&self) -> Vec<Charge> {
+            self.charges
+                .iter()
+                .zip(&self.positions)
+                .map(|(&q, p)| {
+                    let (x, y, z) = p.components();
+                    Charge::new(q, (x as f32, y as f32, z as f32))
+                })
+                .collect()
+        }
+
+        /// The current mass, velocity and pin state of every body.
+        pub fn kinematics(&self) -> Vec<Kinematics> {
+            self.bodies
+                .iter()
+                .zip(&self.velocities)
+                .map(|(b, v)| Kinematics { velocity: v.components(), ..b.clone() })
+                .collect()
+        }
+
+        /// T = ∑ ½ mᵢ |vᵢ|²   [J]
+        pub fn kinetic_energy(&self) -> f64 {
+            self.bodies
+                .iter()
+                .zip(&self.velocities)
+                .filter(|(b, _)| !b.pinned)
+                .map(|(b, v)| 0.5 * b.mass * v.dot_product(v))
+                .sum()
+        }
+
+        /// U = K ∑_{i<j} qᵢqⱼ / √(rᵢⱼ² + ε²)   [J]
+        pub fn potential_energy(&self) -> f64 {
+            let eps2 = self.softening * self.softening;
+            let mut u = 0.0;
+            for i in 0..self.positions.len() {
+                for j in (i + 1)..self.positions.len() {
+                    let r = self.positions[i] - self.positions[j];
+                    u += self.charges[i] * self.charges[j] / (r.dot_product(&r) + eps2).sqrt();
+                }
+            }
+            K * u
+        }
+
+        /// E = T + U   [J]
+        pub fn total_energy(&self) -> f64 {
+            self.kinetic_energy() + self.potential_energy()
+        }
+
+        /// P = ∑ mᵢ vᵢ over the free bodies   [kg·m/s]
+        pub fn momentum(&self) -> Vector3D {
+            let mut p = Vector3D::new(0.0, 0.0, 0.0);
+            for (b, v) in self.bodies.iter().zip(&self.velocities) {
+                if !b.pinned {
+                    p += v.scalar_product(&b.mass);
+                }
+            }
+            p
+        }
+
+        /// Energy and momentum drift relative to the start of the run.
+        pub fn drift(&self) -> Drift {
+            let de = self.total_energy() - self.initial_energy;
+            let energy = if self.initial_energy != 0.0 { de / self.initial_energy.abs() } else { de };
+            Drift { energy, momentum: (self.momentum() - self.initial_momentum).magnitude() }
+        }
+
+        /// aᵢ = (qᵢ / mᵢ) K ∑_{j≠i} qⱼ (rᵢ − rⱼ) / (rᵢⱼ² + ε²)^{3/2}
+        fn compute_accelerations(&self) -> Vec<Vector3D> {
+            let eps2 = self.softening * self.softening;
+            (0..self.positions.len())
+                .map(|i| {
+                    let mut e = Vector3D::new(0.0, 0.0, 0.0);
+                    if self.bodies[i].pinned {
+                        return e;
+                    }
+                    for (j, pj) in self.positions.iter().enumerate() {
+                        if i == j {
+                            continue;
+                        }
+                        let r = self.positions[i] - *pj;
+                        let d2 = r.dot_product(&r) + eps2;
+                        e += r.scalar_product(&(self.charges[j] / (d2 * d2.sqrt())));
+                    }
+                    e.scalar_product(&(K * self.charges[i] / self.bodies[i].mass))
+                })
+                .collect()
+        }
+    }
+}
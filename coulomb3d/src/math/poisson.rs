@@ -0,0 +1,339 @@
+/*
+Finite-difference solver for Poisson's equation ∇·(εᵣ∇ϕ) = −ρ/ε₀ on a cube, for boundary-value
+problems point charges cannot express: electrodes held at fixed potentials and dielectrics of
+any shape inside a box whose faces are grounded, held at a potential (Dirichlet) or insulating
+(Neumann, ∂ϕ/∂n = 0).
+
+The cube is covered by n³ lattice points with spacing h. Point charges are deposited onto the
+lattice with cloud-in-cell (trilinear) weights, and the 7-point stencil
+
+    ϕᵢⱼₖ = (∑ wₙ ϕₙ + h² ρᵢⱼₖ/ε₀) / ∑ wₙ        over the six neighbours n
+
+is relaxed with red–black successive over-relaxation (SOR) until the largest update falls below
+a tolerance. The weight of each link is the harmonic mean of εᵣ at its two ends, which keeps the normal
+component of D continuous across a dielectric interface; in vacuum every wₙ = 1. Neumann faces
+mirror the lattice across the face. With Neumann faces and no
+electrode the potential is only defined up to a constant and the net charge must vanish; the
+constant is fixed by giving ϕ zero mean.
+
+The solution is a `FieldSource`: ϕ and E = −∇ϕ (central differences) are interpolated
+trilinearly between lattice points. Near a point charge the grid smooths the 1/r singularity
+over a few cells, and outside the cube both are zero.
+*/
+use crate::math::{relative_permittivity, Dielectric, FieldSource, ScalarGrid, Vector3D, EPSILON};
+use crate::Charge;
+use serde::{Deserialize, Serialize};
+
+/// Boundary condition on the faces of the cube.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Boundary {
+    /// Faces held at `potential` [V]
+    Dirichlet { potential: f64 },
+    /// Insulating faces: no field component normal to the face
+    Neumann,
+}
+
+impl Default for Boundary {
+    fn default() -> Self {
+        Boundary::Dirichlet { potential: 0.0 }
+    }
+}
+
+/// A region of lattice points held at a fixed potential [V]. Points are (x, y, z) in metres.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Electrode {
+    /// Solid ball of `radius` [m]
+    Sphere { center: [f64; 3], radius: f64, potential: f64 },
+    /// Axis-aligned box between the corners `min` and `max`
+    Box { min: [f64; 3], max: [f64; 3], potential: f64 },
+}
+
+impl Electrode {
+    pub fn potential(&self) -> f64 {
+        match *self {
+            Electrode::Sphere { potential, .. } | Electrode::Box { potential, .. } => potential,
+        }
+    }
+
+    /// Whether the point `p` lies inside the electrode.
+    pub fn contains(&self, p: [f64; 3]) -> bool {
+        match *self {
+            Electrode::Sphere { center, radius, .. } => {
+                let d2: f64 = (0..3).map(|a| (p[a] - center[a]).powi(2)).sum();
+                d2 <= radius * radius
+            }
+            Electrode::Box { min, max, .. } => (0..3).all(|a| p[a] >= min[a] && p[a] <= max[a]),
+        }
+    }
+
+    /// Largest |coordinate| of the electrode.
+    pub fn extent(&self) -> f64 {
+        let reach = |c: [f64; 3]| c.iter().fold(0.0f64, |acc, x| acc.max(x.abs()));
+        match *self {
+            Electrode::Sphere { center, radius, .. } => reach(center) + radius,
+            Electrode::Box { min, max, .. } => reach(min).max(reach(max)),
+        }
+    }
+}
+
+/// Settings of the grid solver, as stored in scene files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridSolver {
+    /// Half-size of the cube [m]; by default sized like the bounding cube of the sources
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub half_size: Option<f64>,
+    /// Lattice points along each edge
+    pub resolution: usize,
+    pub boundary: Boundary,
+    /// SOR over-relaxation factor in (0, 2); by default the optimum for the resolution
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub omega: Option<f64>,
+    /// Largest update per sweep, relative to the largest |ϕ|, at which relaxation stops
+    pub tolerance: f64,
+    pub max_iterations: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub electrodes: Vec<Electrode>,
+}
+
+impl Default for GridSolver {
+    fn default() -> Self {
+        Self {
+            half_size: None,
+            resolution: 48,
+            boundary: Boundary::default(),
+            omega: None,
+            tolerance: 1e-6,
+            max_iterations: 5000,
+            electrodes: Vec::new(),
+        }
+    }
+}
+
+/// The relaxed potential on the lattice and the field derived from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoissonSolution {
+    /// ϕ [V] at every lattice point
+    pub potential: ScalarGrid,
+    /// SOR sweeps performed
+    pub iterations: usize,
+    /// Largest relative update of the last sweep
+    pub residual: f64,
+    field: Vec<Vector3D>,
+    electrodes: Vec<Electrode>,
+    charges: Vec<Charge>,
+    dielectrics: Vec<Dielectric>,
+}
+
+impl GridSolver {
+    /// Half-size of the cube the problem is solved in.
+    pub fn half_size(&self, charges: &[Charge], dielectrics: &[Dielectric]) -> f64 {
+        // Same margin as `bounding_half_size`, so the grid covers the region that is drawn
+        self.half_size.unwrap_or_else(|| (extent(&self.electrodes, charges, dielectrics) + 3.0).max(4.0))
+    }
+
+    /// Relax ϕ for `charges` and the electrodes in vacuum.
+    pub fn solve(&self, charges: &[Charge]) -> PoissonSolution {
+        self.solve_in(charges, &[])
+    }
+
+    /// Relax ϕ for `charges` and the electrodes with `dielectrics` filling the space.
+    pub fn solve_in(&self, charges: &[Charge], dielectrics: &[Dielectric]) -> PoissonSolution {
+        let n = self.resolution.max(3);
+        let half = self.half_size(charges, dielectrics);
+        let h = 2.0 * half / (n - 1) as f64;
+        let mut grid = ScalarGrid::sample_cube(half, n, |_| 0.0);
+        let idx = |i: usize, j: usize, k: usize| i + n * (j + n * k);
+
+        // Lattice points whose potential is fixed: electrodes, and the faces when Dirichlet
+        let mut fixed = vec![false; n * n * n];
+        for k in 0..n {
+            for j in 0..n {
+                for i in 0..n {
+                    let on_face = [i, j, k].iter().any(|&a| a == 0 || a == n - 1);
+                    let p = grid.point(i, j, k);
+                    if let Some(e) = self.electrodes.iter().find(|e| e.contains(p)) {
+                        fixed[idx(i, j, k)] = true;
+                        grid.values[idx(i, j, k)] = e.potential();
+                    } else if let (true, Boundary::Dirichlet { potential }) = (on_face, self.boundary) {
+                        fixed[idx(i, j, k)] = true;
+                        grid.values[idx(i, j, k)] = potential;
+                    }
+                }
+            }
+        }
+
+        // h²ρ/ε₀ at every lattice point, charge deposited with cloud-in-cell weights
+        let mut source = vec![0.0; n * n * n];
+        for c in charges {
+            let p = [c.position.0 as f64, c.position.1 as f64, c.position.2 as f64];
+            let u = p.map(|x| (x + half) / h);
+            if u.iter().any(|&x| !(0.0..=(n - 1) as f64).contains(&x)) {
+                continue;
+            }
+            for ([i, j, k], w) in trilinear(u, n) {
+                source[idx(i, j, k)] += c.charge * w / (h * EPSILON);
+            }
+        }
+
+        // A pure Neumann problem only has a solution for zero net charge; remove the mean
+        let floating = self.boundary == Boundary::Neumann && !fixed.iter().any(|&f| f);
+        if floating {
+            let mean = source.iter().sum::<f64>() / source.len() as f64;
+            source.iter_mut().for_each(|s| *s -= mean);
+        }
+
+        let omega = self
+            .omega
+            .filter(|w| *w > 0.0 && *w < 2.0)
+            .unwrap_or(2.0 / (1.0 + (std::f64::consts::PI / (n - 1) as f64).sin()));
+        // Neighbour across a face: mirrored for Neumann faces (never reached for Dirichlet)
+        let down = |a: usize| if a == 0 { 1 } else { a - 1 };
+        let up = |a: usize| if a == n - 1 { n - 2 } else { a + 1 };
+
+        // Link weights to the −x, +x, −y, +y, −z, +z neighbours
+        let eps: Vec<f64> = (0..n * n * n)
+            .map(|at| relative_permittivity(dielectrics, grid.point(at % n, at / n % n, at / (n * n))))
+            .collect();
+        let mut links = vec![[1.0; 6]; n * n * n];
+        if !dielectrics.is_empty() {
+            for k in 0..n {
+                for j in 0..n {
+                    for i in 0..n {
+                        let at = idx(i, j, k);
+                        let neighbours = [
+                            idx(down(i), j, k), idx(up(i), j, k),
+                            idx(i, down(j), k), idx(i, up(j), k),
+                            idx(i, j, down(k)), idx(i, j, up(k)),
+                        ];
+                        links[at] = neighbours.map(|nb| 2.0 * eps[at] * eps[nb] / (eps[at] + eps[nb]));
+                    }
+                }
+            }
+        }
+
+        let phi = &mut grid.values;
+        let (mut iterations, mut residual) = (0, f64::INFINITY);
+        while iterations < self.max_iterations && residual > self.tolerance {
+            let mut largest_change = 0.0f64;
+            for colour in 0..2 {
+                for k in 0..n {
+                    for j in 0..n {
+                        let start = (colour + j + k) % 2;
+                        for i in (start..n).step_by(2) {
+                            let at = idx(i, j, k);
+                            if fixed[at] {
+                                continue;
+                            }
+                            let w = &links[at];
+                            let sum = w[0] * phi[idx(down(i), j, k)] + w[1] * phi[idx(up(i), j, k)]
+                                + w[2] * phi[idx(i, down(j), k)] + w[3] * phi[idx(i, up(j), k)]
+                                + w[4] * phi[idx(i, j, down(k))] + w[5] * phi[idx(i, j, up(k))];
+                            let change = omega * ((sum + source[at]) / w.iter().sum::<f64>() - phi[at]);
+                            phi[at] += change;
+                            largest_change = largest_change.max(change.abs());
+                        }
+                    }
+                }
+            }
+            if floating {
+                let mean = phi.iter().sum::<f64>() / phi.len() as f64;
+                phi.iter_mut().for_each(|v| *v -= mean);
+            }
+            let scale = phi.iter().fold(0.0f64, |acc, v| acc.max(v.abs()));
+            residual = if scale > 0.0 { largest_change / scale } else { 0.0 };
+            iterations += 1;
+        }
+
+        // E = −∇ϕ, central differences inside, one-sided on the faces
+        let mut field = Vec::with_capacity(n * n * n);
+        for k in 0..n {
+            for j in 0..n {
+                for i in 0..n {
+                    let grad = |a: usize, at: &dyn Fn(usize) -> usize| {
+                        let (l, r) = (a.saturating_sub(1), (a + 1).min(n - 1));
+                        -(phi[at(r)] - phi[at(l)]) / ((r - l) as f64 * h)
+                    };
+                    field.push(Vector3D::new(
+                        grad(i, &|x| idx(x, j, k)),
+                        grad(j, &|y| idx(i, y, k)),
+                        grad(k, &|z| idx(i, j, z)),
+                    ));
+                }
+            }
+        }
+
+        PoissonSolution {
+            potential: grid,
+            iterations,
+            residual,
+            field,
+            electrodes: self.electrodes.clone(),
+            charges: charges.to_vec(),
+            dielectrics: dielectrics.to_vec(),
+        }
+    }
+}
+
+/// Largest |coordinate| occupied by the charges, electrodes and dielectrics.
+fn extent(electrodes: &[Electrode], charges: &[Charge], dielectrics: &[Dielectric]) -> f64 {
+    let e = electrodes.iter().fold(0.0f64, |acc, e| acc.max(e.extent()));
+    let d = dielectrics.iter().fold(0.0f64, |acc, d| acc.max(d.extent()));
+    e.max(d).max(charges.extent() as f64)
+}
+
+/// Lattice points around `u` (in units of the spacing, inside the lattice of `n` points
+/// per edge) with their trilinear weights.
+fn trilinear(u: [f64; 3], n: usize) -> [([usize; 3], f64); 8] {
+    let base = u.map(|x| (x.floor() as usize).min(n - 2));
+    std::array::from_fn(|corner| {
+        let mut w = 1.0;
+        let mut at = base;
+        for a in 0..3 {
+            let f = u[a] - base[a] as f64;
+            if corner >> a & 1 == 1 {
+                w *= f;
+                at[a] += 1;
+            } else {
+                w *= 1.0 - f;
+            }
+        }
+        (at, w)
+    })
+}
+
+impl PoissonSolution {
+    /// Lattice points and trilinear weights around `s`, or `None` outside the cube.
+    fn corners(&self, s: &(f32, f32, f32)) -> Option<[(usize, f64); 8]> {
+        let g = &self.potential;
+        let n = g.dims[0];
+        let p = [s.0 as f64, s.1 as f64, s.2 as f64];
+        let u: [f64; 3] = std::array::from_fn(|a| (p[a] - g.origin[a]) / g.spacing);
+        if u.iter().any(|&x| !(0.0..=(n - 1) as f64).contains(&x)) {
+            return None;
+        }
+        Some(trilinear(u, n).map(|([i, j, k], w)| (g.index(i, j, k), w)))
+    }
+}
+
+impl FieldSource for PoissonSolution {
+    fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+        self.corners(s).map_or(0.0, |c| c.iter().map(|&(at, w)| w * self.potential.values[at]).sum())
+    }
+    fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+        let mut e = Vector3D::new(0.0, 0.0, 0.0);
+        for (at, w) in self.corners(s).into_iter().flatten() {
+            e += self.field[at].scalar_product(&w);
+        }
+        e
+    }
+    fn absorbs(&self, s: &(f32, f32, f32)) -> bool {
+        let p = [s.0 as f64, s.1 as f64, s.2 as f64];
+        self.electrodes.iter().any(|e| e.contains(p)) || self.charges.absorbs(s)
+    }
+    fn extent(&self) -> f32 {
+        extent(&self.electrodes, &self.charges, &self.dielectrics) as f32
+    }
+}
@@ -0,0 +1,97 @@
+/*
+User-placed field line seeds: a single point, a rectangular grid or a circle in an arbitrary
+plane. Unlike the seeds around charges and distributions, which only follow the field away from
+their source, lines are traced both ways from every point (along E and against it), so a seed
+in empty space shows the whole line passing through it, e.g. between capacitor plates.
+*/
+use crate::math::distributions::plane_basis;
+use crate::math::{Seed, Vector3D, PI};
+use serde::{Deserialize, Serialize};
+
+/// Most points a single seed shape may produce.
+pub const MAX_SEED_POINTS: usize = 2500;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SeedShape {
+    Point {
+        position: [f64; 3],
+    },
+    /// `count[0] × count[1]` points spread evenly over a `size[0] × size[1]` rectangle centred
+    /// on `center`, perpendicular to `normal`
+    Grid {
+        center: [f64; 3],
+        normal: [f64; 3],
+        size: [f64; 2],
+        count: [usize; 2],
+    },
+    /// `count` points evenly spaced around a circle perpendicular to `normal`
+    Circle {
+        center: [f64; 3],
+        normal: [f64; 3],
+        radius: f64,
+        count: usize,
+    },
+}
+
+fn vector(a: [f64; 3]) -> Vector3D {
+    Vector3D::new(a[0], a[1], a[2])
+}
+
+impl SeedShape {
+    /// Number of seed points.
+    pub fn len(&self) -> usize {
+        match *self {
+            SeedShape::Point { .. } => 1,
+            SeedShape::Grid { count, .. } => count[0].saturating_mul(count[1]),
+            SeedShape::Circle { count, .. } => count,
+        }
+    }
+
+    /// Whether the shape has no points (a grid or circle with a count of zero).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The seed points.
+    pub fn points(&self) -> Vec<Vector3D> {
+        match *self {
+            SeedShape::Point { position } => vec![vector(position)],
+            SeedShape::Grid { center, normal, size, count } => {
+                let (u, v) = plane_basis(vector(normal).normalized());
+                // Cell centres, so a 1 × 1 grid is its centre point
+                let offset = |i: usize, n: usize, side: f64| side * ((i as f64 + 0.5) / n as f64 - 0.5);
+                (0..count[0])
+                    .flat_map(|i| (0..count[1]).map(move |j| (i, j)))
+                    .map(|(i, j)| {
+                        vector(center)
+                            + u.scalar_product(&offset(i, count[0], size[0]))
+                            + v.scalar_product(&offset(j, count[1], size[1]))
+                    })
+                    .collect()
+            }
+            SeedShape::Circle { center, normal, radius, count } => {
+                let (u, v) = plane_basis(vector(normal).normalized());
+                (0..count)
+                    .map(|k| {
+                        let th = 2.0 * PI * k as f64 / count as f64;
+                        vector(center) + u.scalar_product(&(radius * th.cos())) + v.scalar_product(&(radius * th.sin()))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Seeds for every point of every shape, each twice: traced along E and against it.
+pub fn user_seeds(shapes: &[SeedShape]) -> Vec<Seed> {
+    shapes
+        .iter()
+        .flat_map(SeedShape::points)
+        .flat_map(|p| {
+            let (x, y, z) = p.components();
+            let position = (x as f32, y as f32, z as f32);
+            [Seed { position, direction: 1.0 }, Seed { position, direction: -1.0 }]
+        })
+        .collect()
+}
@@ -0,0 +1,138 @@
+/*
+Planar cuts through the scene. A `SlicePlane` is a square of half-size `half_size` centred on
+`center` and perpendicular to `normal`; `sample_slice` evaluates the potential or the field
+strength on a regular grid over it, which the GUI turns into a colour-mapped heatmap.
+`sample_multipole_error` shows instead where the multipole approximation of the charges holds.
+*/
+use crate::math::{multipole_relative_error, FieldSource, MultipoleApproximation, Vector3D};
+use crate::Charge;
+
+/// What a slice shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SliceQuantity {
+    /// Electric potential ϕ [V]
+    Potential,
+    /// Field strength |E| [N/C]
+    FieldMagnitude,
+    /// Relative error of the multipole approximation, |E_approx − E| / |E|.
+    /// Sampled only by `sample_multipole_error`; `sample_slice` rejects it.
+    MultipoleError,
+}
+
+impl SliceQuantity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SliceQuantity::Potential => "Phi (V)",
+            SliceQuantity::FieldMagnitude => "|E| (N/C)",
+            SliceQuantity::MultipoleError => "|dE|/|E|",
+        }
+    }
+}
+
+/// A square cut through the scene.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlicePlane {
+    pub center: [f64; 3],
+    /// Plane normal; need not be unit length but must not be zero.
+    pub normal: [f64; 3],
+    pub half_size: f64,
+}
+
+impl SlicePlane {
+    /// In-plane unit vectors (u, v). Axis-aligned planes get the natural axes,
+    /// e.g. the XY plane gets u = x̂, v = ŷ.
+    pub fn axes(&self) -> (Vector3D, Vector3D) {
+        let n = Vector3D::new(self.normal[0], self.normal[1], self.normal[2]).normalized();
+        let (nx, _, _) = n.components();
+        let a = if nx.abs() < 0.9 { Vector3D::new(1.0, 0.0, 0.0) } else { Vector3D::new(0.0, 1.0, 0.0) };
+        let u = (a - n.scalar_product(&a.dot_product(&n))).normalized();
+        (u, n.cross_product(&u))
+    }
+
+    /// The point at in-plane coordinates (a, b) ∈ [−1, 1]², corners at (±1, ±1).
+    pub fn point(&self, a: f64, b: f64) -> (f32, f32, f32) {
+        let (u, v) = self.axes();
+        let c = Vector3D::new(self.center[0], self.center[1], self.center[2]);
+        let p = c + u.scalar_product(&(a * self.half_size)) + v.scalar_product(&(b * self.half_size));
+        let (x, y, z) = p.components();
+        (x as f32, y as f32, z as f32)
+    }
+
+    /// The points of a `resolution` × `resolution` grid over the plane, in `SliceGrid::values` order.
+    pub fn grid_points(&self, resolution: usize) -> impl Iterator<Item = (f32, f32, f32)> + '_ {
+        let n = resolution.max(2);
+        let coord = move |i: usize| -1.0 + 2.0 * i as f64 / (n - 1) as f64;
+        (0..n).flat_map(move |j| (0..n).map(move |i| self.point(coord(i), coord(j))))
+    }
+}
+
+/// Values sampled on a `resolution` × `resolution` grid over a slice plane.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SliceGrid {
+    pub plane: SlicePlane,
+    pub quantity: SliceQuantity,
+    pub resolution: usize,
+    /// `values[j * resolution + i]` is taken at in-plane coordinates
+    /// a = −1 + 2i/(resolution − 1), b = −1 + 2j/(resolution − 1).
+    pub values: Vec<f64>,
+}
+
+impl SliceGrid {
+    /// Range of the finite values from the 2nd to the 98th percentile, so a few
+    /// samples right next to a point charge do not wash out the rest of the map.
+    pub fn value_range(&self) -> (f64, f64) {
+        let mut v: Vec<f64> = self.values.iter().copied().filter(|x| x.is_finite()).collect();
+        percentile_range(&mut v)
+    }
+}
+
+/// The 2nd and 98th percentiles of `values` (sorted in place), (0, 0) if there are none.
+pub fn percentile_range(values: &mut [f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let at = |q: f64| values[((values.len() - 1) as f64 * q).round() as usize];
+    (at(0.02), at(0.98))
+}
+
+/// Evaluate `quantity` on a `resolution` × `resolution` grid over `plane`.
+/// `MultipoleError` needs the exact charges and is sampled with `sample_multipole_error`;
+/// asking for it here is a debug assertion, and release builds fall back to |E|.
+pub fn sample_slice<F: FieldSource + ?Sized>(
+    field: &F,
+    plane: &SlicePlane,
+    quantity: SliceQuantity,
+    resolution: usize,
+) -> SliceGrid {
+    debug_assert!(quantity != SliceQuantity::MultipoleError, "use sample_multipole_error for the multipole error");
+    match quantity {
+        SliceQuantity::Potential => sample_slice_with(plane, quantity, resolution, |p| field.potential(p)),
+        _ => sample_slice_with(plane, SliceQuantity::FieldMagnitude, resolution, |p| field.field(p).magnitude()),
+    }
+}
+
+/// Relative error of the monopole + dipole + quadrupole approximation of `charges`
+/// about `center`, sampled over `plane`.
+pub fn sample_multipole_error(
+    charges: &[Charge],
+    center: &(f32, f32, f32),
+    plane: &SlicePlane,
+    resolution: usize,
+) -> SliceGrid {
+    let approx = MultipoleApproximation::new(charges, center);
+    sample_slice_with(plane, SliceQuantity::MultipoleError, resolution, |p| {
+        multipole_relative_error(charges, &approx, p)
+    })
+}
+
+/// Sample `f` on a `resolution` × `resolution` grid over `plane`.
+pub fn sample_slice_with(
+    plane: &SlicePlane,
+    quantity: SliceQuantity,
+    resolution: usize,
+    f: impl Fn(&(f32, f32, f32)) -> f64,
+) -> SliceGrid {
+    let values = plane.grid_points(resolution).map(|p| f(&p)).collect();
+    SliceGrid { plane: plane.clone(), quantity, resolution: resolution.max(2), values }
+}
@@ -0,0 +1,117 @@
+/*
+Spherical multipole expansion of a point-charge configuration to arbitrary order, in the
+convention of Jackson (Classical Electrodynamics, §4.1):
+
+    Q_ℓm = ∑ᵢ qᵢ rᵢˡ Y*_ℓm(θᵢ, φᵢ)                       positions rᵢ relative to the centre
+    ϕ(r) = 4πK ∑ₗ ∑ₘ Q_ℓm Y_ℓm(θ, φ) / ((2ℓ + 1) rˡ⁺¹)     valid for r > max rᵢ
+
+with orthonormal Y_ℓm (Condon–Shortley phase), so Q_ℓ,−m = (−1)ᵐ Q*_ℓm. The ℓ ≤ 2 coefficients
+carry the same information as `MultipoleMoments`, e.g. Q₀₀ = Q/√(4π), Q₁₀ = √(3/4π) p_z and
+Q₂₀ = ½√(5/4π) Q_zz. The coefficients are obtained from the solid harmonics of harmonics.rs,
+which differ from rˡ Y_ℓm only by a normalisation factor.
+*/
+use crate::math::harmonics::{expansion_len, index, irregular, regular, Complex};
+use crate::math::{Vector3D, K, PI};
+use crate::Charge;
+
+/// Spherical multipole moments Q_ℓm for ℓ ≤ `l_max` about `center`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SphericalMultipoles {
+    pub center: (f32, f32, f32),
+    pub l_max: usize,
+    /// Distance from the centre to the farthest charge; the expansion converges outside it
+    pub radius: f64,
+    /// Q_ℓm at `index(ℓ, m)`
+    moments: Vec<Complex>,
+}
+
+/// (ℓ + m)! / (ℓ − m)! computed as a product, for 0 ≤ m ≤ ℓ.
+fn factorial_ratio(l: usize, m: usize) -> f64 {
+    ((l - m + 1)..=(l + m)).map(|k| k as f64).product()
+}
+
+/// Normalisation of Y_ℓm: √((2ℓ + 1)/4π · (ℓ − m)!/(ℓ + m)!).
+fn norm(l: usize, m: usize) -> f64 {
+    ((2 * l + 1) as f64 / (4.0 * PI) / factorial_ratio(l, m)).sqrt()
+}
+
+fn to_vector(s: &(f32, f32, f32)) -> Vector3D {
+    Vector3D::new(s.0 as f64, s.1 as f64, s.2 as f64)
+}
+
+/// Compute Q_ℓm for ℓ ≤ `l_max` about `center`.
+pub fn spherical_multipoles(charges: &[Charge], center: &(f32, f32, f32), l_max: usize) -> SphericalMultipoles {
+    // Solid harmonic moments Mₗᵐ = ∑ q Υₗᵐ(rᵢ), then
+    // Q_ℓm = (−1)ᵐ (ℓ + m)! N_ℓm (Mₗᵐ)*   since  rˡ Y_ℓm = (−1)ᵐ (ℓ + m)! N_ℓm Υₗᵐ
+    let c = to_vector(center);
+    let mut m_sum = vec![Complex::ZERO; expansion_len(l_max)];
+    let mut radius = 0.0f64;
+    for ch in charges {
+        let r = to_vector(&ch.position) - c;
+        radius = radius.max(r.magnitude());
+        for (acc, u) in m_sum.iter_mut().zip(regular(r, l_max)) {
+            *acc += u.scale(ch.charge);
+        }
+    }
+
+    let mut moments = vec![Complex::ZERO; expansion_len(l_max)];
+    for l in 0..=l_max {
+        let mut fact = 1.0; // (ℓ + m)!
+        for k in 1..=l {
+            fact *= k as f64;
+        }
+        for m in 0..=l {
+            if m > 0 {
+                fact *= (l + m) as f64;
+            }
+            let sign = if m % 2 == 0 { 1.0 } else { -1.0 };
+            let q = m_sum[index(l, m as i64)].conj().scale(sign * fact * norm(l, m));
+            moments[index(l, m as i64)] = q;
+            moments[index(l, -(m as i64))] = q.conj().scale(sign);
+        }
+    }
+    SphericalMultipoles { center: *center, l_max, radius, moments }
+}
+
+impl SphericalMultipoles {
+    /// Q_ℓm [C·mˡ]; zero beyond `l_max`.
+    pub fn moment(&self, l: usize, m: i64) -> Complex {
+        if l > self.l_max || m.unsigned_abs() as usize > l {
+            return Complex::ZERO;
+        }
+        self.moments[index(l, m)]
+    }
+
+    /// The degree-ℓ term of the expansion at `s`:
+    /// 4πK/(2ℓ + 1) ∑ₘ Q_ℓm Y_ℓm / rˡ⁺¹ [V].
+    pub fn potential_term(&self, l: usize, s: &(f32, f32, f32)) -> f64 {
+        if l > self.l_max {
+            return 0.0;
+        }
+        // Y_ℓm / rˡ⁺¹ = (−1)ᵐ N_ℓm Θₗᵐ / (ℓ − m)!, and the ±m terms are complex conjugates
+        let t = irregular(to_vector(s) - to_vector(&self.center), l);
+        let mut sum = 0.0;
+        let mut fact = 1.0; // (ℓ − m)!, built downwards from ℓ!
+        for k in 1..=l {
+            fact *= k as f64;
+        }
+        for m in 0..=l {
+            let sign = if m % 2 == 0 { 1.0 } else { -1.0 };
+            let y = t[index(l, m as i64)].scale(sign * norm(l, m) / fact);
+            let term = (self.moments[index(l, m as i64)] * y).re;
+            sum += if m == 0 { term } else { 2.0 * term };
+            fact /= (l - m).max(1) as f64;
+        }
+        4.0 * PI * K * sum / (2 * l + 1) as f64
+    }
+
+    /// Potential at `s` truncated after degree `l` (at most `l_max`) [V].
+    pub fn potential_to(&self, l: usize, s: &(f32, f32, f32)) -> f64 {
+        (0..=l.min(self.l_max)).map(|k| self.potential_term(k, s)).sum()
+    }
+
+    /// Potential at `s` from every term up to `l_max` [V].
+    pub fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+        self.potential_to(self.l_max, s)
+    }
+}
@@ -0,0 +1,181 @@
+/*
+Test particles in the static fields of the configuration. A particle of charge q and mass m
+follows the Lorentz force
+
+    m dv/dt = q (E + v × B),    dx/dt = v
+
+with E from any `FieldSource` and B from the currents. The particle does not act back on the
+sources. The equations are integrated with the adaptive Dormand–Prince 5(4) scheme of ode.rs;
+each step is also limited in length so the drawn path stays smooth and no source is jumped over.
+A trajectory ends when the particle hits a source (where field lines are absorbed), leaves the
+bounding cube the field lines are confined to, or runs out of time or steps.
+*/
+use crate::math::ode::{dormand_prince, next_step};
+use crate::math::{bounding_half_size, Current, FieldSource, MagneticField, Polyline, Vector3D};
+use serde::{Deserialize, Serialize};
+
+/// Longest step as a fraction of the bounding cube's half-size.
+const MAX_STEP_FRACTION: f64 = 0.02;
+/// Step sizes are abandoned below this fraction of the duration.
+const MIN_STEP_FRACTION: f64 = 1e-14;
+
+/// A test particle at launch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Particle {
+    /// Charge [C]
+    pub charge: f64,
+    /// Mass [kg]
+    pub mass: f64,
+    /// Launch position (x, y, z) [m]
+    pub position: [f64; 3],
+    /// Launch velocity [m/s]
+    #[serde(default)]
+    pub velocity: [f64; 3],
+}
+
+/// Tunable parameters of the trajectory integrator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrajectorySettings {
+    /// Longest time followed [s]
+    pub duration: f64,
+    /// Relative error allowed per step
+    pub tolerance: f64,
+    /// Most steps taken (accepted or rejected)
+    pub max_steps: usize,
+}
+
+impl Default for TrajectorySettings {
+    fn default() -> Self {
+        Self { duration: 10.0, tolerance: 1e-7, max_steps: 20_000 }
+    }
+}
+
+/// Why a trajectory stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrajectoryEnd {
+    /// Hit a charge or another absorbing source
+    Collided,
+    /// Left the bounding cube
+    LeftBounds,
+    /// Reached `duration`
+    TimeLimit,
+    /// Ran out of steps, or the step size collapsed
+    StepLimit,
+}
+
+impl TrajectoryEnd {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            TrajectoryEnd::Collided => "hit a source",
+            TrajectoryEnd::LeftBounds => "left the bounding cube",
+            TrajectoryEnd::TimeLimit => "reached the time limit",
+            TrajectoryEnd::StepLimit => "ran out of steps",
+        }
+    }
+}
+
+/// The path of one particle: positions, times and velocities at every accepted step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trajectory {
+    pub points: Polyline,
+    pub times: Vec<f64>,
+    pub velocities: Vec<Vector3D>,
+    pub end: TrajectoryEnd,
+}
+
+fn point(y: &[f64; 6]) -> (f32, f32, f32) {
+    (y[0] as f32, y[1] as f32, y[2] as f32)
+}
+
+/// Follow `particle` through the electric field `field` and the magnetic field of `currents`.
+pub fn trace_particle<F: FieldSource + ?Sized>(
+    particle: &Particle,
+    field: &F,
+    currents: &[Current],
+    settings: &TrajectorySettings,
+) -> Trajectory {
+    let magnetic = MagneticField::new(currents);
+    let bounds = bounding_half_size(field).max(bounding_half_size(&magnetic)) as f64;
+    let qm = particle.charge / particle.mass;
+    let rhs = |y: &[f64; 6]| -> Option<[f64; 6]> {
+        let s = point(y);
+        let v = Vector3D::new(y[3], y[4], y[5]);
+        let force = field.field(&s) + v.cross_product(&magnetic.field(&s));
+        let (ax, ay, az) = force.scalar_product(&qm).components();
+        [y[3], y[4], y[5], ax, ay, az].iter().all(|x| x.is_finite()).then_some([y[3], y[4], y[5], ax, ay, az])
+    };
+    let stops = |s: &(f32, f32, f32)| {
+        if field.absorbs(s) || magnetic.absorbs(s) {
+            Some(TrajectoryEnd::Collided)
+        } else if s.0.abs() as f64 > bounds || s.1.abs() as f64 > bounds || s.2.abs() as f64 > bounds {
+            Some(TrajectoryEnd::LeftBounds)
+        } else {
+            None
+        }
+    };
+
+    let [x, y, z] = particle.position;
+    let [vx, vy, vz] = particle.velocity;
+    let mut state = [x, y, z, vx, vy, vz];
+    let mut trajectory = Trajectory {
+        points: vec![point(&state)],
+        times: vec![0.0],
+        velocities: vec![Vector3D::new(vx, vy, vz)],
+        end: TrajectoryEnd::StepLimit,
+    };
+    if let Some(end) = stops(&point(&state)) {
+        trajectory.end = end;
+        return trajectory;
+    }
+
+    // Error scales: position relative to the cube, velocity to the fastest speed seen so far
+    let max_length = MAX_STEP_FRACTION * bounds;
+    let accel = rhs(&state).map_or(0.0, |d| Vector3D::new(d[3], d[4], d[5]).magnitude());
+    let mut speed_scale = Vector3D::new(vx, vy, vz).magnitude().max((accel * bounds).sqrt()).max(f64::MIN_POSITIVE);
+    let mut h = (0.1 * max_length / speed_scale).min(settings.duration);
+    let mut t = 0.0;
+    for _ in 0..settings.max_steps {
+        if t >= settings.duration {
+            trajectory.end = TrajectoryEnd::TimeLimit;
+            return trajectory;
+        }
+        h = h.min(settings.duration - t);
+        let Some((next, error)) = dormand_prince(rhs, &state, h) else {
+            // A stage hit a singular point: creep up on it, but not forever
+            h *= 0.25;
+            if h < MIN_STEP_FRACTION * settings.duration {
+                return trajectory;
+            }
+            continue;
+        };
+        let norm = (0..6)
+            .map(|i| {
+                let scale = if i < 3 { bounds } else { speed_scale };
+                error[i].abs() / (settings.tolerance * scale)
+            })
+            .fold(0.0f64, f64::max);
+        let length = ((next[0] - state[0]).powi(2) + (next[1] - state[1]).powi(2) + (next[2] - state[2]).powi(2)).sqrt();
+        if norm > 1.0 || length > max_length {
+            h = if length > max_length { h * 0.5 * max_length / length } else { next_step(h, norm) };
+            if h < MIN_STEP_FRACTION * settings.duration {
+                return trajectory;
+            }
+            continue;
+        }
+
+        t += h;
+        state = next;
+        let v = Vector3D::new(state[3], state[4], state[5]);
+        speed_scale = speed_scale.max(v.magnitude());
+        trajectory.points.push(point(&state));
+        trajectory.times.push(t);
+        trajectory.velocities.push(v);
+        if let Some(end) = stops(&point(&state)) {
+            trajectory.end = end;
+            return trajectory;
+        }
+        h = next_step(h, norm);
+    }
+    trajectory
+}
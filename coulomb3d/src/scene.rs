@@ -0,0 +1,654 @@
+/*
+Scene files: a charge configuration stored on disk as TOML, e.g.
+
+    version   = 1
+    reference = [0.0, 0.0, 0.0]
+
+    [field_lines]
+    show       = true
+    seeds      = 12
+    seeding    = "flux"
+    integrator = "adaptive"
+    tolerance  = 1e-5
+
+    [[charges]]
+    charge   = 1e-5
+    position = [1.0, 1.0, 1.0]
+    color    = [0.9, 0.2, 0.2, 1.0]
+    mass     = 1.0
+    velocity = [0.0, 0.0, 0.0]
+    pinned   = false
+
+    [[distributions]]
+    kind   = "ring"
+    center = [0.0, 0.0, 0.0]
+    normal = [0.0, 1.0, 0.0]
+    radius = 1.5
+    charge = -2e-5
+
+    [[conductors]]
+    kind     = "sphere"
+    center   = [0.0, 3.0, 0.0]
+    radius   = 1.0
+    grounded = true
+    charge   = 0.0
+
+    [grid_solver]
+    resolution = 48
+    boundary   = { kind = "neumann" }
+    electrodes = [{ kind = "sphere", center = [0.0, -3.0, 0.0], radius = 1.0, potential = 100.0 }]
+
+    [[dielectrics]]
+    kind      = "sphere"
+    center    = [3.0, 0.0, 0.0]
+    radius    = 1.0
+    epsilon_r = 4.0
+
+    [[currents]]
+    kind    = "loop"
+    center  = [0.0, 0.0, 3.0]
+    normal  = [0.0, 0.0, 1.0]
+    radius  = 1.0
+    current = 2.0
+
+    [trajectories]
+    duration = 10.0
+
+    [[particles]]
+    charge   = -1e-6
+    mass     = 1e-3
+    position = [-3.0, 0.0, 0.0]
+    velocity = [1.0, 0.0, 0.0]
+
+    [[seeds]]
+    kind   = "grid"
+    center = [0.0, 0.0, 0.0]
+    normal = [1.0, 0.0, 0.0]
+    size   = [2.0, 2.0]
+    count  = [4, 4]
+
+Everything is optional and falls back to the app's defaults. Files without a `version` are
+read as version 1; files written by a newer Coulomb3D are rejected.
+Version history: 1 point charges only, 2 added `distributions`, 3 added `conductors`,
+4 added `grid_solver`, 5 added `dielectrics`, 6 added `currents`,
+7 added `particles` and `trajectories`, 8 added the adaptive field line `integrator`,
+9 added flux-proportional field line `seeding` (the default from 9 on; older files without the
+key keep "per_charge"),
+10 added user-placed field line `seeds`.
+*/
+use crate::math::{
+    coulomb_forces, Boundary, Conductor, Current, Dielectric, Distribution, Electrode, FieldBackend, FieldLineSettings,
+    FieldSource, GridSolver, ImageSystem, Kinematics, Particle, PointCharges, PoissonSolution, ScreenedCharges, SeedShape,
+    Seeding, TrajectorySettings, Vector3D, MAX_SEED_POINTS,
+};
+use crate::Charge;
+use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
+use std::fmt;
+use std::path::Path;
+
+/// The scene format version written by this build.
+pub const SCENE_VERSION: u32 = 10;
+
+/// Largest grid solver resolution accepted from a scene file (lattice points per edge).
+pub const MAX_GRID_RESOLUTION: usize = 160;
+
+/// Largest field line step budget accepted from a scene file; the seed count per charge is
+/// bounded by [`MAX_SEED_POINTS`] like a user seed shape.
+pub const MAX_FIELD_LINE_STEPS: usize = 100_000;
+
+/// A single charge as it appears in a scene file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneCharge {
+    /// Charge in coulombs.
+    pub charge: f64,
+    /// Position (x, y, z) in metres.
+    pub position: [f64; 3],
+    /// Display colour (r, g, b, a), each in 0..=1. Only used by the GUI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<[f32; 4]>,
+    /// Mass in kilograms for the dynamics simulation (default 1 kg).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mass: Option<f64>,
+    /// Initial velocity in m/s for the dynamics simulation (default at rest).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub velocity: Option<[f64; 3]>,
+    /// Pinned charges never move in the dynamics simulation.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+}
+
+/// Field line display state saved with a scene.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SceneFieldLines {
+    #[serde(default)]
+    pub show: bool,
+    #[serde(flatten)]
+    pub settings: FieldLineSettings,
+}
+
+/// A charge configuration as stored on disk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    /// The reference point r' the calculations are evaluated at.
+    #[serde(default)]
+    pub reference: [f64; 3],
+    #[serde(default)]
+    pub field_lines: SceneFieldLines,
+    #[serde(default)]
+    pub charges: Vec<SceneCharge>,
+    /// Continuous charge distributions (lines, rings, disks, …).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub distributions: Vec<Distribution>,
+    /// Grounded planes and grounded or isolated spheres, solved by the method of images.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conductors: Vec<Conductor>,
+    /// When present the point charges are solved on a grid, together with the electrodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grid_solver: Option<GridSolver>,
+    /// Dielectric spheres, boxes and half-spaces. With `grid_solver` they are solved on its grid.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dielectrics: Vec<Dielectric>,
+    /// Wire segments, loops and solenoids; they only produce a magnetic field.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub currents: Vec<Current>,
+    /// Test particles launched into the static fields.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub particles: Vec<Particle>,
+    #[serde(default)]
+    pub trajectories: TrajectorySettings,
+    /// Points, grids and circles field lines are traced from in both directions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seeds: Vec<SeedShape>,
+}
+
+/// Convert an in-memory point to its on-disk form. Going through the shortest decimal
+/// representation keeps `0.1` as `0.1` in the file instead of `0.10000000149011612`.
+pub fn widen(p: (f32, f32, f32)) -> [f64; 3] {
+    let w = |x: f32| x.to_string().parse::<f64>().unwrap_or(x as f64);
+    [w(p.0), w(p.1), w(p.2)]
+}
+
+fn narrow(p: [f64; 3]) -> (f32, f32, f32) {
+    (p[0] as f32, p[1] as f32, p[2] as f32)
+}
+
+/// Files without a `version` predate the field and are read as version 1.
+fn default_version() -> u32 {
+    1
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self {
+            version: SCENE_VERSION,
+            reference: [0.0; 3],
+            field_lines: SceneFieldLines::default(),
+            charges: Vec::new(),
+            distributions: Vec::new(),
+            conductors: Vec::new(),
+            grid_solver: None,
+            dielectrics: Vec::new(),
+            currents: Vec::new(),
+            particles: Vec::new(),
+            trajectories: TrajectorySettings::default(),
+            seeds: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    /// The file could not be read or written.
+    Io(std::io::Error),
+    /// The file is not a valid scene.
+    Parse(String),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Io(e) => write!(f, "Could not access scene file: {e}"),
+            SceneError::Parse(e) => write!(f, "Invalid scene file: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<std::io::Error> for SceneError {
+    fn from(e: std::io::Error) -> Self {
+        SceneError::Io(e)
+    }
+}
+
+impl Scene {
+    /// Parse a scene from TOML text.
+    pub fn from_toml(text: &str) -> Result<Self, SceneError> {
+        let mut scene: Scene = toml::from_str(text).map_err(|e| {
+            // Report "line N: message" rather than toml's multi-line snippet
+            let msg = e.message().trim().to_string();
+            match e.span() {
+                Some(span) => {
+                    let line = text[..span.start].matches('\n').count() + 1;
+                    SceneError::Parse(format!("line {line}: {msg}"))
+                }
+                None => SceneError::Parse(msg),
+            }
+        })?;
+        // Flux seeding became the default in version 9; older files keep their per-charge look
+        if scene.version < 9 && !declares_seeding(text) {
+            scene.field_lines.settings.seeding = Seeding::PerCharge;
+        }
+        scene.validate()?;
+        Ok(scene)
+    }
+
+    /// Serialise the scene as TOML text.
+    pub fn to_toml(&self) -> Result<String, SceneError> {
+        toml::to_string(self).map_err(|e| SceneError::Parse(e.to_string()))
+    }
+
+    /// Read and parse a scene file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Write the scene to `path`, replacing any existing file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SceneError> {
+        std::fs::write(path, self.to_toml()?)?;
+        Ok(())
+    }
+
+    /// The reference point r' as a tuple.
+    pub fn reference(&self) -> (f32, f32, f32) {
+        narrow(self.reference)
+    }
+
+    /// The bare charge configuration described by this scene.
+    pub fn configuration(&self) -> Vec<Charge> {
+        self.charges
+            .iter()
+            .map(|c| Charge::new(c.charge, narrow(c.position)))
+            .collect()
+    }
+
+    /// Everything the scene's electric field comes from, with the point charges summed directly.
+    pub fn sources(&self) -> SceneSources {
+        SceneSources::new(
+            &self.configuration(),
+            FieldBackend::Direct,
+            &self.distributions,
+            &self.conductors,
+            &self.dielectrics,
+            self.grid_solver.as_ref(),
+        )
+    }
+
+    /// Mass, velocity and pin state of every charge, defaults filled in.
+    pub fn kinematics(&self) -> Vec<Kinematics> {
+        self.charges
+            .iter()
+            .map(|c| {
+                let d = Kinematics::default();
+                Kinematics {
+                    mass: c.mass.unwrap_or(d.mass),
+                    velocity: c.velocity.map_or(d.velocity, |[x, y, z]| (x, y, z)),
+                    pinned: c.pinned,
+                }
+            })
+            .collect()
+    }
+
+    fn validate(&self) -> Result<(), SceneError> {
+        if self.version > SCENE_VERSION {
+            return Err(SceneError::Parse(format!(
+                "version {} is newer than the supported version {SCENE_VERSION}",
+                self.version
+            )));
+        }
+        if self.reference.iter().any(|x| !x.is_finite()) {
+            return Err(SceneError::Parse(String::from("reference point is not finite")));
+        }
+        let fl = &self.field_lines.settings;
+        let positive = |x: f64| x.is_finite() && x > 0.0;
+        if !positive(fl.step) || !positive(fl.seed_radius) {
+            return Err(SceneError::Parse(String::from(
+                "field line step and seed_radius must be positive",
+            )));
+        }
+        if !(positive(fl.tolerance) && positive(fl.min_step) && fl.max_step.is_finite() && fl.max_step >= fl.min_step) {
+            return Err(SceneError::Parse(String::from(
+                "field line tolerance and min_step must be positive, max_step at least min_step",
+            )));
+        }
+        if fl.seeds > MAX_SEED_POINTS || fl.max_steps > MAX_FIELD_LINE_STEPS {
+            return Err(SceneError::Parse(format!(
+                "field line seeds must be at most {MAX_SEED_POINTS} and max_steps at most {MAX_FIELD_LINE_STEPS}"
+            )));
+        }
+        for (i, c) in self.charges.iter().enumerate() {
+            if !c.charge.is_finite() || c.position.iter().any(|x| !x.is_finite()) {
+                return Err(SceneError::Parse(format!("charge #{} has a non-finite value", i + 1)));
+            }
+            if let Some(col) = c.color {
+                if col.iter().any(|x| !(0.0..=1.0).contains(x)) {
+                    return Err(SceneError::Parse(format!(
+                        "charge #{} has a colour component outside 0..1",
+                        i + 1
+                    )));
+                }
+            }
+            if c.mass.is_some_and(|m| !(m.is_finite() && m > 0.0)) {
+                return Err(SceneError::Parse(format!("charge #{} must have a positive mass", i + 1)));
+            }
+            if c.velocity.is_some_and(|v| v.iter().any(|x| !x.is_finite())) {
+                return Err(SceneError::Parse(format!("charge #{} has a non-finite velocity", i + 1)));
+            }
+            if self.charges[..i].iter().any(|o| o.position == c.position) {
+                return Err(SceneError::Parse(format!(
+                    "charge #{} shares its position with another charge",
+                    i + 1
+                )));
+            }
+        }
+        for (i, d) in self.distributions.iter().enumerate() {
+            validate_distribution(d).map_err(|e| SceneError::Parse(format!("distribution #{}: {e}", i + 1)))?;
+        }
+        for (i, k) in self.conductors.iter().enumerate() {
+            validate_conductor(k).map_err(|e| SceneError::Parse(format!("conductor #{}: {e}", i + 1)))?;
+        }
+        if let Some(g) = &self.grid_solver {
+            validate_grid_solver(g).map_err(|e| SceneError::Parse(format!("grid_solver: {e}")))?;
+        }
+        for (i, d) in self.dielectrics.iter().enumerate() {
+            validate_dielectric(d).map_err(|e| SceneError::Parse(format!("dielectric #{}: {e}", i + 1)))?;
+        }
+        for (i, c) in self.currents.iter().enumerate() {
+            validate_current(c).map_err(|e| SceneError::Parse(format!("current #{}: {e}", i + 1)))?;
+        }
+        for (i, p) in self.particles.iter().enumerate() {
+            let finite = p.position.iter().chain(&p.velocity).all(|x| x.is_finite()) && p.charge.is_finite();
+            if !finite {
+                return Err(SceneError::Parse(format!("particle #{} has a non-finite value", i + 1)));
+            }
+            if !(p.mass.is_finite() && p.mass > 0.0) {
+                return Err(SceneError::Parse(format!("particle #{} must have a positive mass", i + 1)));
+            }
+        }
+        let t = &self.trajectories;
+        if !(t.duration.is_finite() && t.duration > 0.0 && t.tolerance.is_finite() && t.tolerance > 0.0) {
+            return Err(SceneError::Parse(String::from("trajectories: duration and tolerance must be positive")));
+        }
+        for (i, s) in self.seeds.iter().enumerate() {
+            validate_seed_shape(s).map_err(|e| SceneError::Parse(format!("seed #{}: {e}", i + 1)))?;
+        }
+        Ok(())
+    }
+}
+
+/// The electric field of a scene. The point charges are replaced by their screened field when
+/// there are dielectrics, or by the grid solution (which includes the electrodes) when the grid
+/// solver is used; the continuous distributions and the charge induced on the conductors are
+/// added to either. The CLI and the GUI both evaluate ϕ, E and the forces through this.
+pub struct SceneSources {
+    /// The bare point charges.
+    pub charges: Vec<Charge>,
+    pub point_charges: PointCharges,
+    pub screened: Option<ScreenedCharges>,
+    pub grid: Option<PoissonSolution>,
+    pub distributions: Vec<Distribution>,
+    pub images: ImageSystem,
+    solver: GridSolver,
+    /// Field of the grid's electrodes and polarisation at every charge, solved on first use.
+    reaction: OnceCell<Option<Vec<Vector3D>>>,
+}
+
+impl SceneSources {
+    /// Solve everything derived from the charge configuration: the grid (when `grid` is given
+    /// or dielectrics need it) and the image charges.
+    pub fn new(
+        charges: &[Charge],
+        backend: FieldBackend,
+        distributions: &[Distribution],
+        conductors: &[Conductor],
+        dielectrics: &[Dielectric],
+        grid: Option<&GridSolver>,
+    ) -> Self {
+        let screened = (!dielectrics.is_empty()).then(|| ScreenedCharges::new(charges, dielectrics, grid));
+        Self {
+            charges: charges.to_vec(),
+            point_charges: PointCharges::new(charges, backend),
+            grid: grid.filter(|_| screened.is_none()).map(|g| g.solve(charges)),
+            screened,
+            distributions: distributions.to_vec(),
+            images: ImageSystem::new(charges, conductors),
+            solver: grid.cloned().unwrap_or_default(),
+            reaction: OnceCell::new(),
+        }
+    }
+
+    /// The field standing in for Coulomb's law between the point charges.
+    pub fn point_field(&self) -> &dyn FieldSource {
+        match (&self.screened, &self.grid) {
+            (Some(screened), _) => screened,
+            (None, Some(solution)) => solution,
+            (None, None) => &self.point_charges,
+        }
+    }
+
+    /// Whether there is nothing to produce a field.
+    pub fn is_empty(&self) -> bool {
+        self.charges.is_empty()
+            && self.distributions.is_empty()
+            && self.images.images().is_empty()
+            && self.solver.electrodes.is_empty()
+    }
+
+    /// Net force on every point charge: Coulomb's law between the charges, the push from the
+    /// distributions, the pull of the charge induced on conductors and, when solved on a grid,
+    /// of the electrodes and dielectrics. Dielectrics solved exactly are not included.
+    pub fn forces(&self) -> Vec<Vector3D> {
+        let reaction = self.reaction.get_or_init(|| self.grid_reaction());
+        let mut forces = coulomb_forces(&self.charges);
+        for (i, (force, ch)) in forces.iter_mut().zip(&self.charges).enumerate() {
+            let mut external = self.distributions.field(&ch.position) + self.images.field(&ch.position);
+            if let Some(reaction) = reaction {
+                external += reaction[i];
+            }
+            *force += external.scalar_product(&ch.charge);
+        }
+        forces
+    }
+
+    /// The grid solution at every charge less that of the charges alone in the same box (no
+    /// electrodes, vacuum): what the electrodes, the charge they induce and the polarisation
+    /// add. No charge feels its own smoothed field, and the charges act on each other through
+    /// `coulomb_forces` rather than the grid.
+    fn grid_reaction(&self) -> Option<Vec<Vector3D>> {
+        let solution = match (&self.screened, &self.grid) {
+            (Some(ScreenedCharges::Grid(solution)), _) | (None, Some(solution)) => solution,
+            _ => return None,
+        };
+        let alone = GridSolver {
+            half_size: Some(-solution.potential.origin[0]),
+            electrodes: Vec::new(),
+            ..self.solver.clone()
+        }
+        .solve(&self.charges);
+        Some(self.charges.iter().map(|c| solution.field(&c.position) - alone.field(&c.position)).collect())
+    }
+}
+
+impl FieldSource for SceneSources {
+    fn potential(&self, s: &(f32, f32, f32)) -> f64 {
+        self.point_field().potential(s) + self.distributions.potential(s) + self.images.potential(s)
+    }
+    fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+        self.point_field().field(s) + self.distributions.field(s) + self.images.field(s)
+    }
+    fn absorbs(&self, s: &(f32, f32, f32)) -> bool {
+        self.point_field().absorbs(s) || self.distributions.absorbs(s) || self.images.absorbs(s)
+    }
+    fn extent(&self) -> f32 {
+        self.point_field().extent().max(self.distributions.extent()).max(self.images.extent())
+    }
+}
+
+fn declares_seeding(text: &str) -> bool {
+    let table = text.parse::<toml::Table>().ok();
+    table.as_ref().and_then(|t| t.get("field_lines")?.get("seeding")).is_some()
+}
+
+fn validate_distribution(d: &Distribution) -> Result<(), &'static str> {
+    let finite = |v: &[f64]| v.iter().all(|x| x.is_finite());
+    let nonzero = |n: [f64; 3]| n.iter().any(|x| *x != 0.0);
+    let positive = |x: f64| x.is_finite() && x > 0.0;
+    let (ok, normal_ok, size_ok) = match *d {
+        Distribution::Line { start, end, lambda } => {
+            (finite(&start) && finite(&end) && lambda.is_finite(), true, start != end)
+        }
+        Distribution::Ring { center, normal, radius, charge } => {
+            (finite(&center) && finite(&normal) && charge.is_finite(), nonzero(normal), positive(radius))
+        }
+        Distribution::Disk { center, normal, radius, sigma } => {
+            (finite(&center) && finite(&normal) && sigma.is_finite(), nonzero(normal), positive(radius))
+        }
+        Distribution::Sphere { center, radius, charge, .. } => (finite(&center) && charge.is_finite(), true, positive(radius)),
+        Distribution::Plane { point, normal, sigma } => (finite(&point) && finite(&normal) && sigma.is_finite(), nonzero(normal), true),
+        Distribution::Slab { point, normal, thickness, rho } => {
+            (finite(&point) && finite(&normal) && rho.is_finite(), nonzero(normal), positive(thickness))
+        }
+    };
+    if !ok {
+        Err("has a non-finite value")
+    } else if !normal_ok {
+        Err("normal must not be the zero vector")
+    } else if !size_ok {
+        Err("must have a positive size")
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_conductor(k: &Conductor) -> Result<(), &'static str> {
+    let finite = |v: &[f64]| v.iter().all(|x| x.is_finite());
+    match *k {
+        Conductor::Plane { point, normal } => {
+            if !(finite(&point) && finite(&normal)) {
+                Err("has a non-finite value")
+            } else if normal.iter().all(|x| *x == 0.0) {
+                Err("normal must not be the zero vector")
+            } else {
+                Ok(())
+            }
+        }
+        Conductor::Sphere { center, radius, charge, .. } => {
+            if !(finite(&center) && charge.is_finite()) {
+                Err("has a non-finite value")
+            } else if !(radius.is_finite() && radius > 0.0) {
+                Err("must have a positive radius")
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+fn validate_dielectric(d: &Dielectric) -> Result<(), &'static str> {
+    let finite = |v: &[f64]| v.iter().all(|x| x.is_finite());
+    let (ok, size_ok) = match *d {
+        Dielectric::Sphere { center, radius, .. } => (finite(&center), radius.is_finite() && radius > 0.0),
+        Dielectric::Box { min, max, .. } => (finite(&min) && finite(&max), (0..3).all(|a| min[a] < max[a])),
+        Dielectric::HalfSpace { point, normal, .. } => (finite(&point) && finite(&normal), normal.iter().any(|x| *x != 0.0)),
+    };
+    if !ok {
+        Err("has a non-finite value")
+    } else if !size_ok {
+        Err("must have a positive size and a non-zero normal")
+    } else if !(d.epsilon_r().is_finite() && d.epsilon_r() >= 1.0) {
+        Err("epsilon_r must be at least 1")
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_current(c: &Current) -> Result<(), &'static str> {
+    let finite = |v: &[f64]| v.iter().all(|x| x.is_finite());
+    let positive = |x: f64| x.is_finite() && x > 0.0;
+    let nonzero = |n: [f64; 3]| n.iter().any(|x| *x != 0.0);
+    let (ok, size_ok) = match *c {
+        Current::Segment { start, end, current } => (finite(&start) && finite(&end) && current.is_finite(), start != end),
+        Current::Loop { center, normal, radius, current } => {
+            (finite(&center) && finite(&normal) && current.is_finite(), nonzero(normal) && positive(radius))
+        }
+        Current::Solenoid { center, axis, radius, length, turns, current } => (
+            finite(&center) && finite(&axis) && current.is_finite(),
+            nonzero(axis) && positive(radius) && positive(length) && turns > 0,
+        ),
+    };
+    if !ok {
+        Err("has a non-finite value")
+    } else if !size_ok {
+        Err("must have a positive size, at least one turn and a non-zero direction")
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_seed_shape(s: &SeedShape) -> Result<(), String> {
+    let finite = |v: &[f64]| v.iter().all(|x| x.is_finite());
+    let positive = |x: f64| x.is_finite() && x > 0.0;
+    let nonzero = |n: [f64; 3]| n.iter().any(|x| *x != 0.0);
+    let (ok, size_ok) = match *s {
+        SeedShape::Point { position } => (finite(&position), true),
+        SeedShape::Grid { center, normal, size, .. } => {
+            (finite(&center) && finite(&normal), nonzero(normal) && size.iter().all(|x| positive(*x)))
+        }
+        SeedShape::Circle { center, normal, radius, .. } => (finite(&center) && finite(&normal), nonzero(normal) && positive(radius)),
+    };
+    if !ok {
+        Err(String::from("has a non-finite value"))
+    } else if !size_ok {
+        Err(String::from("must have a positive size and a non-zero normal"))
+    } else if !(1..=MAX_SEED_POINTS).contains(&s.len()) {
+        Err(format!("must have between 1 and {MAX_SEED_POINTS} points"))
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_grid_solver(g: &GridSolver) -> Result<(), String> {
+    if !(3..=MAX_GRID_RESOLUTION).contains(&g.resolution) {
+        return Err(format!("resolution must be between 3 and {MAX_GRID_RESOLUTION}"));
+    }
+    if g.half_size.is_some_and(|h| !(h.is_finite() && h > 0.0)) {
+        return Err(String::from("half_size must be positive"));
+    }
+    if g.omega.is_some_and(|w| !(w > 0.0 && w < 2.0)) {
+        return Err(String::from("omega must lie between 0 and 2"));
+    }
+    if !(g.tolerance.is_finite() && g.tolerance > 0.0) {
+        return Err(String::from("tolerance must be positive"));
+    }
+    if matches!(g.boundary, Boundary::Dirichlet { potential } if !potential.is_finite()) {
+        return Err(String::from("boundary potential is not finite"));
+    }
+    for (i, e) in g.electrodes.iter().enumerate() {
+        let finite = |v: &[f64]| v.iter().all(|x| x.is_finite());
+        let ok = match *e {
+            Electrode::Sphere { center, radius, potential } => {
+                finite(&center) && potential.is_finite() && radius.is_finite() && radius > 0.0
+            }
+            Electrode::Box { min, max, potential } => {
+                finite(&min) && finite(&max) && potential.is_finite() && (0..3).all(|a| min[a] < max[a])
+            }
+        };
+        if !ok {
+            return Err(format!("electrode #{} must be finite with a positive size", i + 1));
+        }
+    }
+    Ok(())
+}
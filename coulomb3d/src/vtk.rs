@@ -0,0 +1,137 @@
+/*
+Export to the legacy VTK file format (ASCII `.vtk`), which ParaView, VisIt and most other
+visualisation tools read directly. Three kinds of data are written, each to its own file:
+
+    field lines     POLYDATA, one LINES cell per polyline, with a `line` id per cell and
+                    `field_magnitude` [N/C], `potential` [V] and `arc_length` [m] per point
+    charges         POLYDATA, one VERTICES cell per charge, with a `charge` [C] scalar
+    sampled fields  STRUCTURED_POINTS over a cube, with `potential` [V] and `field` [N/C]
+
+The writers return the file contents as a `String`; `export` writes all three next to each
+other. Coordinates are in metres.
+*/
+use crate::math::{FieldSource, LineQuantity, LineScalars, Polyline, ScalarGrid};
+use crate::Charge;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+fn header(out: &mut String, title: &str, dataset: &str) {
+    // writing to a String cannot fail
+    let _ = write!(out, "# vtk DataFile Version 3.0\n{title}\nASCII\nDATASET {dataset}\n");
+}
+
+/// Field lines as a polydata file with one polyline cell per line, carrying the `scalars`
+/// sampled along each (one `LineScalars` per line). Non-finite values are written as 0.
+pub fn field_lines(lines: &[Polyline], scalars: &[LineScalars]) -> String {
+    let (lines, scalars): (Vec<&Polyline>, Vec<&LineScalars>) =
+        lines.iter().zip(scalars).filter(|(l, _)| l.len() >= 2).unzip();
+    let n_points: usize = lines.iter().map(|l| l.len()).sum();
+    let mut out = String::new();
+    header(&mut out, "Coulomb3D field lines", "POLYDATA");
+
+    let _ = writeln!(out, "POINTS {n_points} double");
+    for (x, y, z) in lines.iter().flat_map(|l| l.iter()) {
+        let _ = writeln!(out, "{x} {y} {z}");
+    }
+    let _ = writeln!(out, "LINES {} {}", lines.len(), n_points + lines.len());
+    let mut first = 0;
+    for l in &lines {
+        let ids: Vec<String> = (first..first + l.len()).map(|i| i.to_string()).collect();
+        let _ = writeln!(out, "{} {}", l.len(), ids.join(" "));
+        first += l.len();
+    }
+    let _ = writeln!(out, "CELL_DATA {}\nSCALARS line int 1\nLOOKUP_TABLE default", lines.len());
+    for i in 0..lines.len() {
+        let _ = writeln!(out, "{i}");
+    }
+    let _ = writeln!(out, "POINT_DATA {n_points}");
+    let finite = |v: f64| if v.is_finite() { v } else { 0.0 };
+    for (name, quantity) in [
+        ("field_magnitude", LineQuantity::FieldMagnitude),
+        ("potential", LineQuantity::Potential),
+        ("arc_length", LineQuantity::ArcLength),
+    ] {
+        let _ = writeln!(out, "SCALARS {name} double 1\nLOOKUP_TABLE default");
+        for &v in scalars.iter().flat_map(|s| s.get(quantity)) {
+            let _ = writeln!(out, "{:e}", finite(v));
+        }
+    }
+    out
+}
+
+/// Point charges as a polydata file of vertices carrying a `charge` scalar.
+pub fn charges(charges: &[Charge]) -> String {
+    let mut out = String::new();
+    header(&mut out, "Coulomb3D point charges", "POLYDATA");
+
+    let _ = writeln!(out, "POINTS {} double", charges.len());
+    for ch in charges {
+        let (x, y, z) = ch.position;
+        let _ = writeln!(out, "{x} {y} {z}");
+    }
+    let _ = writeln!(out, "VERTICES {} {}", charges.len(), 2 * charges.len());
+    for i in 0..charges.len() {
+        let _ = writeln!(out, "1 {i}");
+    }
+    let _ = writeln!(out, "POINT_DATA {}\nSCALARS charge double 1\nLOOKUP_TABLE default", charges.len());
+    for ch in charges {
+        let _ = writeln!(out, "{:e}", ch.charge);
+    }
+    out
+}
+
+/// ϕ and E sampled on a cube of half-size `bounds` centred at the origin with
+/// `resolution` points per edge. Values at singular points (on top of a point
+/// charge) are written as 0 so the file stays readable.
+pub fn sampled_fields<F: FieldSource + ?Sized>(field: &F, bounds: f64, resolution: usize) -> String {
+    let grid = ScalarGrid::sample_cube(bounds, resolution, |p| field.potential(p));
+    let [nx, ny, nz] = grid.dims;
+    let finite = |v: f64| if v.is_finite() { v } else { 0.0 };
+    let mut out = String::new();
+    header(&mut out, "Coulomb3D sampled potential and field", "STRUCTURED_POINTS");
+
+    let [ox, oy, oz] = grid.origin;
+    let h = grid.spacing;
+    let _ = writeln!(out, "DIMENSIONS {nx} {ny} {nz}\nORIGIN {ox} {oy} {oz}\nSPACING {h} {h} {h}");
+    let _ = writeln!(out, "POINT_DATA {}\nSCALARS potential double 1\nLOOKUP_TABLE default", grid.values.len());
+    for &v in &grid.values {
+        let _ = writeln!(out, "{:e}", finite(v));
+    }
+    // VTK expects x fastest, then y, then z: the same order as `ScalarGrid`
+    let _ = writeln!(out, "VECTORS field double");
+    for k in 0..nz {
+        for j in 0..ny {
+            for i in 0..nx {
+                let [x, y, z] = grid.point(i, j, k);
+                let (ex, ey, ez) = field.field(&(x as f32, y as f32, z as f32)).components();
+                let _ = writeln!(out, "{:e} {:e} {:e}", finite(ex), finite(ey), finite(ez));
+            }
+        }
+    }
+    out
+}
+
+/// Write `<prefix>_field_lines.vtk`, `<prefix>_charges.vtk` and `<prefix>_fields.vtk`,
+/// returning the paths written.
+pub fn export<F: FieldSource + ?Sized>(
+    prefix: impl AsRef<Path>,
+    point_charges: &[Charge],
+    field: &F,
+    lines: &[Polyline],
+    bounds: f64,
+    resolution: usize,
+) -> std::io::Result<Vec<PathBuf>> {
+    let prefix = prefix.as_ref().display().to_string();
+    let scalars: Vec<LineScalars> = lines.iter().map(|l| LineScalars::sample(field, l)).collect();
+    let files = [
+        (format!("{prefix}_field_lines.vtk"), field_lines(lines, &scalars)),
+        (format!("{prefix}_charges.vtk"), charges(point_charges)),
+        (format!("{prefix}_fields.vtk"), sampled_fields(field, bounds, resolution)),
+    ];
+    let mut written = Vec::with_capacity(files.len());
+    for (path, contents) in files {
+        std::fs::write(&path, contents)?;
+        written.push(PathBuf::from(path));
+    }
+    Ok(written)
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::{cloud, field_error, probes};
+use coulomb3d::*;
+
+#[test]
+fn theta_zero_matches_direct_summation() {
+    let charges = cloud(500, 1);
+    let tree = BarnesHut::new(&charges, 0.0);
+    assert_eq!(tree.len(), 500);
+    for p in probes(2) {
+        let (phi, exact) = (tree.potential(&p), electric_potential(&charges, &p));
+        assert!((phi - exact).abs() <= 1e-9 * exact.abs().max(1e-3), "{phi} vs {exact} at {p:?}");
+    }
+    assert!(field_error(&charges, &tree, &probes(2)) < 1e-12);
+}
+
+#[test]
+fn thousands_of_charges_are_accurate_at_default_theta() {
+    let charges = cloud(3000, 3);
+    let points = probes(4);
+    let tree = BarnesHut::new(&charges, 0.5);
+
+    // About 1 % for a mixed-sign cloud, where cancellations make the relative error largest
+    let e = field_error(&charges, &tree, &points);
+    assert!(e < 2e-2, "{e}");
+    let (mut err, mut norm) = (0.0, 0.0);
+    for p in &points {
+        let exact = electric_potential(&charges, p);
+        err += (tree.potential(p) - exact).powi(2);
+        norm += exact * exact;
+    }
+    assert!((err / norm).sqrt() < 2e-2, "{}", (err / norm).sqrt());
+}
+
+#[test]
+fn error_shrinks_with_opening_angle() {
+    let charges = cloud(2000, 5);
+    let points = probes(6);
+    let errors: Vec<f64> = [0.9, 0.6, 0.3]
+        .iter()
+        .map(|&theta| field_error(&charges, &BarnesHut::new(&charges, theta), &points))
+        .collect();
+    assert!(errors[0] > errors[1] && errors[1] > errors[2], "{errors:?}");
+    assert!(errors[0] < 5e-2, "{errors:?}");
+}
+
+#[test]
+fn absorbs_and_extent_match_the_charge_list() {
+    let charges = cloud(400, 7);
+    let tree = BarnesHut::new(&charges, 0.5);
+    assert_eq!(tree.extent(), charges.extent());
+    for p in probes(8).iter().chain(charges.iter().map(|c| &c.position)) {
+        assert_eq!(tree.absorbs(p), charges.absorbs(p), "at {p:?}");
+    }
+}
+
+#[test]
+fn point_charges_backend_selects_the_evaluator() {
+    let charges = cloud(300, 9);
+    let p = (5.0, -4.0, 3.0);
+    let direct = PointCharges::new(&charges, FieldBackend::Direct);
+    let tree = PointCharges::new(&charges, FieldBackend::BarnesHut { theta: 0.5 });
+    assert_eq!(direct.potential(&p), electric_potential(&charges, &p));
+    let rel = (tree.field(&p) - direct.field(&p)).magnitude() / direct.field(&p).magnitude();
+    assert!(rel > 0.0 && rel < 5e-2, "{rel}");
+}
+
+#[test]
+fn wide_opening_angles_never_expand_a_cell_around_a_point_inside_it() {
+    // A tight cluster at one corner and a lone charge at the other: the root cell's centre of
+    // charge sits in the cluster, far from a query point right next to the lone charge
+    let mut s = 21;
+    let mut charges: Vec<_> = (0..50)
+        .map(|_| {
+            let mut c = || (0.2 * common::lcg(&mut s) - 0.1) as f32;
+            Charge::new(1e-9, (c(), c(), c()))
+        })
+        .collect();
+    charges.push(Charge::new(1e-9, (4.0, 4.0, 4.0)));
+    let points = [(3.95, 3.95, 3.95), (3.9, 4.0, 4.05), (2.0, 2.0, 2.0)];
+    for theta in [0.5, 0.8, 1.0] {
+        let e = field_error(&charges, &BarnesHut::new(&charges, theta), &points);
+        assert!(e < 1e-2, "θ = {theta}: {e}");
+    }
+}
@@ -0,0 +1,82 @@
+use coulomb3d::*;
+
+fn close(a: [f32; 4], b: [f32; 4]) -> bool {
+    a.iter().zip(&b).all(|(x, y)| (x - y).abs() < 1e-6)
+}
+
+#[test]
+fn maps_start_and_end_on_their_anchor_colours_and_clamp_beyond() {
+    assert!(close(Colormap::Viridis.color(0.0), [0.267, 0.005, 0.329, 1.0]));
+    assert!(close(Colormap::Viridis.color(1.0), [0.993, 0.906, 0.144, 1.0]));
+    assert!(close(Colormap::Plasma.color(0.0), [0.051, 0.031, 0.529, 1.0]));
+    assert!(close(Colormap::Plasma.color(1.0), [0.941, 0.976, 0.129, 1.0]));
+    // The diverging map is grey-white in the middle
+    assert!(close(Colormap::Diverging.color(0.5), [0.865, 0.865, 0.865, 1.0]));
+    for map in [Colormap::Viridis, Colormap::Plasma, Colormap::Diverging] {
+        assert_eq!(map.color(-3.0), map.color(0.0));
+        assert_eq!(map.color(7.0), map.color(1.0));
+        assert!(map.color(0.37).iter().all(|x| (0.0..=1.0).contains(x)));
+    }
+}
+
+#[test]
+fn linear_scale_clamps_outside_its_range() {
+    let scale = ColorScale::new(Colormap::Viridis, Scale::Linear, 2.0, 6.0);
+    assert_eq!((scale.normalize(2.0), scale.normalize(4.0), scale.normalize(6.0)), (0.0, 0.5, 1.0));
+    assert_eq!((scale.normalize(-100.0), scale.normalize(100.0)), (0.0, 1.0));
+    assert_eq!((scale.normalize(f64::INFINITY), scale.normalize(f64::NEG_INFINITY)), (1.0, 0.0));
+    assert_eq!(scale.normalize(f64::NAN), 0.0);
+    assert_eq!((scale.value_at(0.0), scale.value_at(1.0)), (2.0, 6.0));
+    assert_eq!(scale.color(100.0), Colormap::Viridis.color(1.0));
+    // An empty range puts every value in the middle
+    assert_eq!(ColorScale::new(Colormap::Viridis, Scale::Linear, 3.0, 3.0).normalize(3.0), 0.5);
+}
+
+#[test]
+fn diverging_scales_are_symmetric_about_zero() {
+    let scale = ColorScale::new(Colormap::Diverging, Scale::Linear, -1.0, 3.0);
+    assert_eq!((scale.lo, scale.hi), (-3.0, 3.0));
+    assert_eq!(scale.normalize(0.0), 0.5);
+}
+
+#[test]
+fn log_scale_spans_four_decades_below_the_top() {
+    let scale = ColorScale::new(Colormap::Viridis, Scale::Log, 1e-2, 1e2);
+    assert_eq!((scale.normalize(1e-2), scale.normalize(1e2)), (0.0, 1.0));
+    assert!((scale.normalize(1.0) - 0.5).abs() < 1e-6);
+    assert!((scale.value_at(0.5) - 1.0).abs() < 1e-9);
+    assert_eq!((scale.normalize(1e-9), scale.normalize(1e9)), (0.0, 1.0));
+
+    // A lower bound of zero (or one too small) is raised to four decades below hi
+    for lo in [0.0, 1e-12] {
+        let scale = ColorScale::new(Colormap::Viridis, Scale::Log, lo, 10.0);
+        assert_eq!((scale.normalize(0.0), scale.normalize(1e-3), scale.normalize(10.0)), (0.0, 0.0, 1.0));
+        assert!((scale.normalize(1e-1) - 0.5).abs() < 1e-6);
+        assert!((scale.value_at(0.0) - 1e-3).abs() < 1e-15);
+    }
+    // Nothing positive to take the log of still gives finite colours
+    let flat = ColorScale::new(Colormap::Viridis, Scale::Log, 0.0, 0.0);
+    assert!([-1.0, 0.0, 1.0].iter().all(|&v| (0.0..=1.0).contains(&flat.normalize(v))));
+    assert!(flat.value_at(1.0).is_finite());
+}
+
+#[test]
+fn log_scale_with_negative_values_is_signed() {
+    let scale = ColorScale::new(Colormap::Viridis, Scale::Log, -10.0, 10.0);
+    assert_eq!((scale.normalize(-10.0), scale.normalize(10.0)), (0.0, 1.0));
+    assert!((scale.normalize(0.0) - 0.5).abs() < 1e-6);
+    let t: Vec<f32> = [-10.0, -1.0, -1e-3, 0.0, 1e-3, 1.0, 10.0].iter().map(|&v| scale.normalize(v)).collect();
+    assert!(t.windows(2).all(|w| w[0] < w[1]), "{t:?}");
+    // Symmetric about zero, and value_at inverts normalize
+    assert!((scale.normalize(1.0) + scale.normalize(-1.0) - 1.0).abs() < 1e-6);
+    for v in [-5.0, -0.01, 0.2, 7.0] {
+        assert!((scale.value_at(scale.normalize(v)) - v).abs() < 1e-5 * v.abs().max(1.0), "{v}");
+    }
+}
+
+#[test]
+fn multipole_error_scale_runs_from_1e_4_to_1() {
+    let scale = multipole_error_scale();
+    assert_eq!((scale.normalize(1e-4), scale.normalize(1.0)), (0.0, 1.0));
+    assert!((scale.normalize(1e-2) - 0.5).abs() < 1e-6);
+}
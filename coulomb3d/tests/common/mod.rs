@@ -0,0 +1,47 @@
+//! Charge clouds and probe points shared by the fast-evaluation tests.
+#![allow(dead_code)] // each test binary uses a different subset
+
+use coulomb3d::{electric_field, Charge, FieldSource};
+
+/// Deterministic pseudo-random numbers in [0, 1) (a 64-bit LCG), so the tests need no rand crate.
+pub fn lcg(state: &mut u64) -> f64 {
+    *state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    (*state >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// `n` charges of either sign scattered uniformly in a cube of half-size 2.
+pub fn cloud(n: usize, seed: u64) -> Vec<Charge> {
+    let mut s = seed;
+    (0..n)
+        .map(|_| {
+            let mut c = || (4.0 * lcg(&mut s) - 2.0) as f32;
+            let p = (c(), c(), c());
+            let q = (lcg(&mut s) - 0.4) * 1e-9;
+            Charge::new(q, p)
+        })
+        .collect()
+}
+
+/// Query points both inside and well outside the cloud.
+pub fn probes(seed: u64) -> Vec<(f32, f32, f32)> {
+    let mut s = seed;
+    (0..60)
+        .map(|i| {
+            let scale = if i % 2 == 0 { 2.5 } else { 8.0 };
+            let mut c = || ((2.0 * lcg(&mut s) - 1.0) * scale) as f32;
+            (c(), c(), c())
+        })
+        .collect()
+}
+
+/// RMS error of the approximate field over the probes, relative to the RMS direct field.
+pub fn field_error(charges: &[Charge], approx: &dyn FieldSource, points: &[(f32, f32, f32)]) -> f64 {
+    let (mut err, mut norm) = (0.0, 0.0);
+    for p in points {
+        let exact = electric_field(charges, p);
+        let diff = approx.field(p) - exact;
+        err += diff.dot_product(&diff);
+        norm += exact.dot_product(&exact);
+    }
+    (err / norm).sqrt()
+}
@@ -0,0 +1,74 @@
+use coulomb3d::*;
+
+fn close(a: f64, b: f64, tol: f64) -> bool {
+    (a - b).abs() <= tol * b.abs().max(1e-30)
+}
+
+#[test]
+fn half_space_keeps_potential_and_normal_displacement_continuous() {
+    let slab = Dielectric::HalfSpace { point: [0.0, 0.0, 0.0], normal: [0.0, 0.0, 1.0], epsilon_r: 4.0 };
+    let charges = vec![Charge::new(1e-9, (0.3, 0.0, 0.5))];
+    let field = ScreenedCharges::new(&charges, std::slice::from_ref(&slab), None);
+    assert!(field.is_exact());
+    for (x, y) in [(0.0f32, 0.0f32), (0.7, -0.4), (-1.5, 2.0)] {
+        let (above, below) = ((x, y, 1e-4), (x, y, -1e-4));
+        assert!(close(field.potential(&above), field.potential(&below), 1e-3));
+        let up = material_fields(&field, std::slice::from_ref(&slab), &above);
+        let down = material_fields(&field, std::slice::from_ref(&slab), &below);
+        assert_eq!(down.epsilon_r, 4.0);
+        assert!(close(up.d.components().2, down.d.components().2, 1e-3));
+        assert!(close(up.e.components().0, down.e.components().0, 5e-3));
+    }
+}
+
+#[test]
+fn vacuum_sphere_is_plain_coulomb() {
+    let ball = Dielectric::Sphere { center: [0.0, 0.0, 0.0], radius: 1.0, epsilon_r: 1.0 };
+    let charges = vec![Charge::new(1e-9, (0.0, 0.4, 1.5))];
+    let field = ScreenedCharges::new(&charges, &[ball], None);
+    for p in [(0.2f32, 0.1f32, -0.3f32), (2.0, -1.0, 0.5)] {
+        assert!(close(field.potential(&p), electric_potential(&charges, &p), 1e-9));
+        assert!((field.field(&p) - electric_field(&charges, &p)).magnitude() < 1e-9 * electric_field(&charges, &p).magnitude());
+    }
+}
+
+#[test]
+fn distant_charge_polarises_a_sphere_uniformly() {
+    // A far charge is a nearly uniform field E₀; inside the sphere E = 3E₀/(εᵣ + 2)
+    let eps = 5.0;
+    let ball = Dielectric::Sphere { center: [0.0, 0.0, 0.0], radius: 0.5, epsilon_r: eps };
+    let charges = vec![Charge::new(1e-6, (0.0, 0.0, 200.0))];
+    let field = ScreenedCharges::new(&charges, &[ball], None);
+    let e0 = electric_field(&charges, &(0.0, 0.0, 0.0)).magnitude();
+    let e = field.field(&(0.1, -0.1, 0.2));
+    assert!(close(e.magnitude(), 3.0 * e0 / (eps + 2.0), 1e-2), "{e:?}");
+}
+
+#[test]
+fn centred_charge_is_screened_by_its_bound_charge() {
+    let eps = 3.0;
+    let q = 1e-9;
+    let ball = Dielectric::Sphere { center: [0.0, 0.0, 0.0], radius: 1.0, epsilon_r: eps };
+    let charges = vec![Charge::new(q, (0.0, 0.0, 0.0))];
+    let field = ScreenedCharges::new(&charges, std::slice::from_ref(&ball), None);
+    assert!(close(field.field(&(0.0, 2.0, 0.0)).magnitude(), K * q / 4.0, 1e-6));
+    assert!(close(field.field(&(0.0, 0.5, 0.0)).magnitude(), K * q / (eps * 0.25), 1e-6));
+    // Bound charge on the surface: q (εᵣ − 1)/εᵣ
+    let surfaces = sample_bound_charge(&field, &ball, 48, 1.0);
+    let total: f64 = surfaces.iter().map(SurfaceCharge::total_charge).sum();
+    assert!(close(total, q * (eps - 1.0) / eps, 2e-2), "{total}");
+}
+
+#[test]
+fn grid_solution_agrees_with_the_series_for_a_dipole() {
+    let ball = Dielectric::Sphere { center: [0.0, 0.0, 0.0], radius: 0.8, epsilon_r: 4.0 };
+    let charges = vec![Charge::new(1e-9, (0.0, 0.15, 1.4)), Charge::new(-1e-9, (0.0, -0.15, 1.4))];
+    let exact = ScreenedCharges::new(&charges, std::slice::from_ref(&ball), None);
+    let solver = GridSolver { half_size: Some(3.0), resolution: 61, ..GridSolver::default() };
+    let grid = ScreenedCharges::new(&charges, &[ball], Some(&solver));
+    assert!(!grid.is_exact());
+    for p in [(0.0f32, 0.0f32, 0.3f32), (0.0, 0.0, -0.2), (0.4, 0.2, 0.0)] {
+        let (a, b) = (exact.field(&p), grid.field(&p));
+        assert!((a - b).magnitude() < 0.1 * a.magnitude(), "at {p:?}: {a:?} vs {b:?}");
+    }
+}
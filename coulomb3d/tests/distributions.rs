@@ -0,0 +1,80 @@
+use coulomb3d::*;
+
+fn epsilon_0() -> f64 {
+    1.0 / (4.0 * std::f64::consts::PI * K)
+}
+
+fn close(a: f64, b: f64, rel: f64) -> bool {
+    (a - b).abs() <= rel * b.abs().max(1e-300)
+}
+
+#[test]
+fn ring_matches_its_on_axis_closed_form() {
+    let (q, r) = (2e-6, 1.5);
+    let ring = Distribution::Ring { center: [0.0; 3], normal: [0.0, 0.0, 1.0], radius: r, charge: q };
+    for z in [0.25f64, 1.0, 4.0] {
+        let d2 = z * z + r * r;
+        let at = (0.0, 0.0, z as f32);
+        let (ex, ey, ez) = ring.field(&at).components();
+        assert!(close(ez, K * q * z / d2.powf(1.5), 1e-9), "{ez} at z = {z}");
+        assert!(ex.abs() + ey.abs() < 1e-9 * ez.abs());
+        assert!(close(ring.potential(&at), K * q / d2.sqrt(), 1e-9));
+    }
+}
+
+#[test]
+fn disk_matches_its_on_axis_closed_form() {
+    let (sigma, r) = (1e-8, 1.0);
+    let disk = Distribution::Disk { center: [0.0; 3], normal: [0.0, 0.0, 1.0], radius: r, sigma };
+    let e0 = sigma / (2.0 * epsilon_0());
+    for z in [0.25f64, 1.0, 3.0] {
+        let d = (z * z + r * r).sqrt();
+        let at = (0.0, 0.0, z as f32);
+        let ez = disk.field(&at).components().2;
+        assert!(close(ez, e0 * (1.0 - z / d), 1e-6), "{ez} at z = {z}");
+        assert!(close(disk.potential(&at), e0 * (d - z), 1e-6));
+        // and mirrored below the disk
+        assert!(close(disk.field(&(0.0, 0.0, -z as f32)).components().2, -ez, 1e-9));
+    }
+}
+
+#[test]
+fn sphere_obeys_gauss_law_outside() {
+    let q = 3e-9;
+    for solid in [false, true] {
+        let sphere = Distribution::Sphere { center: [0.5, 0.0, 0.0], radius: 1.0, charge: q, solid };
+        // Flux through a sphere of radius 2.5 about a point off the centre
+        let (c, rs) = ([0.0, 0.3, 0.0], 2.5);
+        let (nt, np) = (200, 400);
+        let mut flux = 0.0;
+        for i in 0..nt {
+            let t = std::f64::consts::PI * (i as f64 + 0.5) / nt as f64;
+            for j in 0..np {
+                let p = 2.0 * std::f64::consts::PI * (j as f64 + 0.5) / np as f64;
+                let n = [t.sin() * p.cos(), t.sin() * p.sin(), t.cos()];
+                let at = ((c[0] + rs * n[0]) as f32, (c[1] + rs * n[1]) as f32, (c[2] + rs * n[2]) as f32);
+                let (ex, ey, ez) = sphere.field(&at).components();
+                let area = rs * rs * t.sin() * (std::f64::consts::PI / nt as f64) * (2.0 * std::f64::consts::PI / np as f64);
+                flux += (ex * n[0] + ey * n[1] + ez * n[2]) * area;
+            }
+        }
+        assert!(close(flux, q / epsilon_0(), 1e-3), "flux {flux} (solid: {solid})");
+
+        // Outside it looks like a point charge at the centre
+        let at = (0.5, 2.0, 0.0);
+        assert!(close(sphere.field(&at).magnitude(), K * q / 4.0, 1e-6));
+        assert!(close(sphere.potential(&at), K * q / 2.0, 1e-6));
+    }
+}
+
+#[test]
+fn plane_field_is_sigma_over_two_epsilon_0() {
+    let sigma = 5e-9;
+    let plane = Distribution::Plane { point: [0.0, 1.0, 0.0], normal: [0.0, 2.0, 0.0], sigma };
+    let e0 = sigma / (2.0 * epsilon_0());
+    for (at, side) in [((0.3, 1.5, -2.0), 1.0), ((4.0, 7.0, 1.0), 1.0), ((0.0, -3.0, 0.0), -1.0)] {
+        let (ex, ey, ez) = plane.field(&at).components();
+        assert!(close(ey, side * e0, 1e-9), "{ey} at {at:?}");
+        assert!(ex.abs() + ez.abs() < 1e-9 * e0);
+    }
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::{cloud, lcg};
+use coulomb3d::*;
+
+const Q: f64 = 1e-6;
+const M: f64 = 1e-3;
+
+/// +Q and −Q of mass M, 1 m apart, launched at `fraction` of the circular orbit speed.
+fn binary(fraction: f64) -> Simulation {
+    let v = (0.5 * K * Q * Q / M).sqrt() * fraction;
+    let charges = vec![Charge::new(Q, (-0.5, 0.0, 0.0)), Charge::new(-Q, (0.5, 0.0, 0.0))];
+    let bodies = vec![
+        Kinematics { mass: M, velocity: (0.0, -v, 0.0), pinned: false },
+        Kinematics { mass: M, velocity: (0.0, v, 0.0), pinned: false },
+    ];
+    Simulation::new(&charges, &bodies, 0.0)
+}
+
+#[test]
+fn velocity_verlet_keeps_the_orbit_energy_bounded() {
+    let mut sim = binary(0.9);
+    // The orbit takes about 1.5 s; follow it for about ten of them
+    let dt = 1e-3;
+    let steps = 15_000;
+    let mut worst = Vec::new();
+    for chunk in 0..10 {
+        let mut largest = 0.0f64;
+        for _ in 0..steps / 10 {
+            sim.step(dt);
+            largest = largest.max(sim.drift().energy.abs());
+        }
+        worst.push(largest);
+        assert!(largest < 1e-4, "energy drift {largest} in chunk {chunk}");
+    }
+    // Oscillating, not growing: the last stretch is no worse than the first
+    assert!(worst[9] < 2.0 * worst[0], "{worst:?}");
+}
+
+#[test]
+fn momentum_is_conserved_without_pinned_bodies() {
+    let charges = cloud(20, 3);
+    let mut s = 17;
+    let bodies: Vec<_> = charges
+        .iter()
+        .map(|_| {
+            let mass = 1e-9 * (1.0 + lcg(&mut s));
+            let mut v = || 1e-3 * (2.0 * lcg(&mut s) - 1.0);
+            Kinematics { mass, velocity: (v(), v(), v()), pinned: false }
+        })
+        .collect();
+    let mut sim = Simulation::new(&charges, &bodies, 0.05);
+    let scale: f64 = bodies.iter().map(|b| b.mass * 1e-3).sum();
+    for _ in 0..500 {
+        sim.step(1e-3);
+    }
+    assert!(sim.charges() != charges, "the bodies should have moved");
+    assert!(sim.drift().momentum < 1e-10 * scale, "{}", sim.drift().momentum);
+}
+
+#[test]
+fn pinned_bodies_never_move() {
+    let charges = vec![Charge::new(Q, (0.0, 0.0, 0.0)), Charge::new(Q, (1.0, 0.0, 0.0))];
+    let bodies = vec![
+        Kinematics { mass: M, velocity: (3.0, 0.0, 0.0), pinned: true },
+        Kinematics { mass: M, velocity: (0.0, 0.0, 0.0), pinned: false },
+    ];
+    let mut sim = Simulation::new(&charges, &bodies, 0.0);
+    for _ in 0..1000 {
+        sim.step(1e-3);
+    }
+    let after = sim.charges();
+    assert_eq!(after[0].position, (0.0, 0.0, 0.0));
+    assert_eq!(sim.kinematics()[0].velocity, (0.0, 0.0, 0.0));
+    // The free charge is pushed away along x
+    assert!(after[1].position.0 > 1.5);
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::cloud;
+use coulomb3d::*;
+
+#[test]
+fn a_pair_has_the_coulomb_energy() {
+    let charges = vec![Charge::new(2e-6, (0.0, 0.0, 0.0)), Charge::new(-3e-6, (0.0, 4.0, 3.0))];
+    let pe = potential_energy(&charges);
+    let expected = K * 2e-6 * -3e-6 / 5.0;
+    assert_eq!(pe.pairs.len(), 1);
+    assert_eq!((pe.pairs[0].i, pe.pairs[0].j), (0, 1));
+    assert!((pe.pairs[0].energy - expected).abs() < 1e-12 * expected.abs());
+    assert_eq!(pe.total, pe.pairs[0].energy);
+}
+
+#[test]
+fn every_pair_is_counted_once() {
+    let n = 12;
+    let charges = cloud(n, 5);
+    let pe = potential_energy(&charges);
+    assert_eq!(pe.pairs.len(), n * (n - 1) / 2);
+
+    // Ordered (0,1), (0,2), …, (1,2), …: every i < j exactly once
+    let mut expected = Vec::new();
+    for i in 0..n {
+        for j in i + 1..n {
+            expected.push((i, j));
+        }
+    }
+    let order: Vec<_> = pe.pairs.iter().map(|p| (p.i, p.j)).collect();
+    assert_eq!(order, expected);
+
+    let sum: f64 = pe.pairs.iter().map(|p| p.energy).sum();
+    let scale: f64 = pe.pairs.iter().map(|p| p.energy.abs()).sum();
+    assert!((pe.total - sum).abs() < 1e-12 * scale);
+    for p in &pe.pairs {
+        let pair = potential_energy(&[charges[p.i].clone(), charges[p.j].clone()]);
+        assert!((pair.total - p.energy).abs() < 1e-12 * p.energy.abs());
+    }
+}
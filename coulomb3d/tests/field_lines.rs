@@ -0,0 +1,140 @@
+use coulomb3d::*;
+
+fn dipole() -> Vec<Charge> {
+    vec![Charge::new(1e-9, (-1.0, 0.0, 0.0)), Charge::new(-1e-9, (1.0, 0.0, 0.0))]
+}
+
+/// Flux function of the dipole, q₁cos θ₁ + q₂cos θ₂ (angles from +x at each charge): constant
+/// along every field line.
+fn flux(p: &(f32, f32, f32)) -> f64 {
+    let cos = |cx: f32| {
+        let (dx, r) = ((p.0 - cx) as f64, (((p.0 - cx).powi(2) + p.1 * p.1 + p.2 * p.2) as f64).sqrt());
+        dx / r
+    };
+    cos(-1.0) - cos(1.0)
+}
+
+fn drift(line: &Polyline) -> f64 {
+    let f0 = flux(&line[0]);
+    line.iter().fold(0.0f64, |m, p| m.max((flux(p) - f0).abs()))
+}
+
+#[test]
+fn adaptive_lines_follow_the_dipole_more_closely_than_rk4() {
+    let charges = dipole();
+    let rk4 = FieldLineSettings::default();
+    let adaptive = FieldLineSettings { integrator: Integrator::Adaptive, ..FieldLineSettings::default() };
+    let seeds: Vec<Seed> = charge_seeds(&charges, &rk4).into_iter().filter(|s| s.direction > 0.0).collect();
+    let (fixed, fixed_stats, _) = generate_field_lines_with_stats(&charges, &seeds, &rk4);
+    let (lines, stats, _) = generate_field_lines_with_stats(&charges, &seeds, &adaptive);
+    assert_eq!(lines.len(), stats.len());
+    let worst = |ls: &[Polyline]| ls.iter().map(drift).fold(0.0f64, f64::max);
+    assert!(worst(&lines) < 1e-3, "{}", worst(&lines));
+    assert!(worst(&lines) < worst(&fixed));
+    // Fewer steps in total: the step grows along the straight stretches
+    let total = |st: &[LineStats]| st.iter().map(|s| s.steps).sum::<usize>();
+    assert!(total(&stats) < total(&fixed_stats), "{} vs {}", total(&stats), total(&fixed_stats));
+}
+
+#[test]
+fn adaptive_lines_end_on_the_absorbing_sphere() {
+    let charges = dipole();
+    let settings = FieldLineSettings { integrator: Integrator::Adaptive, ..FieldLineSettings::default() };
+    let seeds = [Seed { position: (-0.65, 0.05, 0.0), direction: 1.0 }];
+    let (lines, stats, _) = generate_field_lines_with_stats(&charges, &seeds, &settings);
+    assert_eq!(stats[0].end, LineEnd::Absorbed);
+    let (x, y, z) = *lines[0].last().unwrap();
+    let r = ((x - 1.0).powi(2) + y * y + z * z).sqrt();
+    assert!((r - 0.12).abs() < 1e-3, "{r}");
+}
+
+#[test]
+fn statistics_report_why_lines_stop() {
+    let single = vec![Charge::new(1e-9, (0.0, 0.0, 0.0))];
+    let seeds = [Seed { position: (0.5, 0.0, 0.0), direction: 1.0 }];
+    for integrator in [Integrator::Rk4, Integrator::Adaptive] {
+        let settings = FieldLineSettings { integrator, ..FieldLineSettings::default() };
+        let (_, stats, _) = generate_field_lines_with_stats(&single, &seeds, &settings);
+        assert_eq!(stats[0].end, LineEnd::OutOfBounds);
+        assert!((stats[0].length - 3.5).abs() < 0.6, "{}", stats[0].length);
+        let short = FieldLineSettings { max_steps: 5, ..settings };
+        assert_eq!(generate_field_lines_with_stats(&single, &seeds, &short).1[0].end, LineEnd::MaxSteps);
+    }
+}
+
+#[test]
+fn flux_seeding_gives_lines_in_proportion_to_charge() {
+    let charges = vec![Charge::new(2e-9, (-2.0, 0.0, 0.0)), Charge::new(1e-9, (2.0, 0.0, 0.0))];
+    let settings = FieldLineSettings::default();
+    assert_eq!(charge_seeds(&charges, &settings).len(), 12 + 6);
+    let opposite = vec![Charge::new(-4e-9, (0.0, 0.0, 0.0)), Charge::new(1e-9, (3.0, 0.0, 0.0))];
+    assert_eq!(charge_seeds(&opposite, &settings).iter().filter(|s| s.direction > 0.0).count(), 3);
+    let per_charge = FieldLineSettings { seeding: Seeding::PerCharge, ..settings };
+    assert_eq!(charge_seeds(&charges, &per_charge).len(), 24);
+}
+
+#[test]
+fn net_negative_lines_obey_gauss() {
+    // +1 nC and −3 nC: all 4 lines of the positive charge end on the negative one and are
+    // drawn once; the other 8 come in from infinity, as many as the net charge −2 nC carries
+    let charges = vec![Charge::new(1e-9, (0.0, 0.0, 0.0)), Charge::new(-3e-9, (2.0, 0.0, 0.0))];
+    let settings = FieldLineSettings::default();
+    let (lines, stats, _) = generate_charge_field_lines(&charges, &charges, &[], &settings);
+    assert_eq!(lines.len(), 12);
+    let count = |end| stats.iter().filter(|s| s.end == end).count();
+    assert_eq!(count(LineEnd::Absorbed), 4);
+    assert_eq!(count(LineEnd::OutOfBounds), 8);
+}
+
+#[test]
+fn scalars_along_a_radial_line_follow_coulomb() {
+    let q = 1e-9;
+    let single = vec![Charge::new(q, (0.0, 0.0, 0.0))];
+    let seeds = [Seed { position: (0.5, 0.0, 0.0), direction: 1.0 }];
+    let (lines, _) = generate_field_lines_in(&single, &seeds, &FieldLineSettings::default());
+    let scalars = LineScalars::sample(&single, &lines[0]);
+    assert_eq!(scalars.field.len(), lines[0].len());
+    for (i, &(x, _, _)) in lines[0].iter().enumerate() {
+        let r = x as f64;
+        assert!((scalars.field[i] - K * q / (r * r)).abs() < 1e-4 * scalars.field[i]);
+        assert!((scalars.potential[i] - K * q / r).abs() < 1e-4 * scalars.potential[i]);
+        assert!((scalars.arc_length[i] - (r - 0.5)).abs() < 1e-4);
+    }
+    let (lo, hi) = line_scalar_range(&[scalars], LineQuantity::FieldMagnitude).unwrap();
+    assert!((hi - K * q / 0.25).abs() < 1e-6 * hi && lo < hi);
+}
+
+#[test]
+fn arrows_point_along_e_whichever_way_a_line_was_traced() {
+    let charges = dipole();
+    let settings = FieldLineSettings::default();
+    let (lines, _, _) = generate_charge_field_lines(&charges, &charges, &[], &settings);
+    let mut marks = 0;
+    for line in &lines {
+        let arrows = line_arrows(&charges, line, 0.5);
+        for a in &arrows {
+            assert!(a.direction.dot_product(&electric_field(&charges, &a.position)) > 0.0);
+            assert!((a.direction.magnitude() - 1.0).abs() < 1e-9);
+        }
+        // One mark per half metre of arc length, the first a quarter metre in
+        let length: f32 = line.windows(2).map(|s| {
+            ((s[1].0 - s[0].0).powi(2) + (s[1].1 - s[0].1).powi(2) + (s[1].2 - s[0].2).powi(2)).sqrt()
+        }).sum();
+        assert_eq!(arrows.len(), ((length as f64 - 0.25) / 0.5).floor() as usize + 1);
+        marks += arrows.len();
+    }
+    assert!(marks > lines.len());
+}
+
+#[test]
+fn adaptive_lines_between_charges_are_drawn_once() {
+    // As above, but with the adaptive integrator whose first trial step says nothing about
+    // how long the final step into a charge is
+    let charges = vec![Charge::new(1e-9, (0.0, 0.0, 0.0)), Charge::new(-3e-9, (2.0, 0.0, 0.0))];
+    for step in [0.001, 0.05, 0.4] {
+        let settings = FieldLineSettings { integrator: Integrator::Adaptive, step, ..FieldLineSettings::default() };
+        let (lines, stats, _) = generate_charge_field_lines(&charges, &charges, &[], &settings);
+        let count = |end| stats.iter().filter(|s| s.end == end).count();
+        assert_eq!((lines.len(), count(LineEnd::Absorbed), count(LineEnd::OutOfBounds)), (12, 4, 8), "step {step}");
+    }
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::{cloud, field_error, probes};
+use coulomb3d::harmonics::{index, irregular, regular};
+use coulomb3d::*;
+
+/// RMS error of the FMM potential over the probes, relative to the RMS direct potential.
+fn potential_error(charges: &[Charge], fmm: &Fmm, points: &[(f32, f32, f32)]) -> f64 {
+    let (mut err, mut norm) = (0.0, 0.0);
+    for p in points {
+        let exact = electric_potential(charges, p);
+        err += (fmm.potential(p) - exact).powi(2);
+        norm += exact * exact;
+    }
+    (err / norm).sqrt()
+}
+
+#[test]
+fn solid_harmonics_expand_the_coulomb_kernel() {
+    // 1/|x − y| = ∑ Υₙᵐ(y)* Θₙᵐ(x) for |y| < |x|
+    let (x, y) = (Vector3D::new(1.3, -0.7, 2.1), Vector3D::new(0.2, 0.3, -0.25));
+    let p = 30;
+    let (u, t) = (regular(y, p), irregular(x, p));
+    let sum: f64 = u.iter().zip(&t).map(|(u, t)| (u.conj() * *t).re).sum();
+    assert!((sum - 1.0 / (x - y).magnitude()).abs() < 1e-12, "{sum}");
+}
+
+#[test]
+fn low_orders_are_the_multipole_moments() {
+    // The ℓ ≤ 2 coefficients of ∑ q Υₙᵐ(r − s) are Q, p and Q_ij in another basis
+    let charges = cloud(50, 13);
+    let s = (0.3f32, -0.2f32, 0.1f32);
+    let centre = Vector3D::new(s.0 as f64, s.1 as f64, s.2 as f64);
+    let mut m = [harmonics::Complex::ZERO; 9];
+    for c in &charges {
+        let (x, y, z) = c.position;
+        let r = Vector3D::new(x as f64, y as f64, z as f64) - centre;
+        for (acc, u) in m.iter_mut().zip(regular(r, 2)) {
+            *acc += u.scale(c.charge);
+        }
+    }
+    let mm = multipole_moments(&charges, &s);
+    let (px, py, pz) = mm.dipole.components();
+    let close = |a: f64, b: f64| (a - b).abs() <= 1e-6 * b.abs().max(1e-12);
+    assert!(close(m[index(0, 0)].re, mm.monopole));
+    assert!(close(m[index(1, 0)].re, pz));
+    assert!(close(m[index(1, 1)].re, px / 2.0) && close(m[index(1, 1)].im, py / 2.0));
+    assert!(close(m[index(2, 0)].re, mm.quadrupole[2][2] / 4.0));
+}
+
+#[test]
+fn error_falls_with_expansion_order() {
+    let charges = cloud(2000, 11);
+    let points = probes(12);
+    let mut errors = Vec::new();
+    for order in [1, 2, 4, 6, 8] {
+        let fmm = Fmm::new(&charges, order);
+        assert_eq!(fmm.len(), 2000);
+        errors.push((order, field_error(&charges, &fmm, &points), potential_error(&charges, &fmm, &points)));
+    }
+    for pair in errors.windows(2) {
+        assert!(pair[1].1 < pair[0].1 && pair[1].2 < pair[0].2, "{errors:?}");
+    }
+    // Measured: field error ≈ 4e-2, 6e-3, 6e-4, 1e-4, 2e-5 for ℓ = 1, 2, 4, 6, 8
+    let (_, e8, phi8) = errors[4];
+    assert!(e8 < 1e-4 && phi8 < 1e-5, "{errors:?}");
+
+    // Order 0 has no field in its local expansions and is raised to the lowest order, 1
+    let lowest = Fmm::new(&charges, 0);
+    assert_eq!(lowest.order, 1);
+    assert_eq!(field_error(&charges, &lowest, &points), errors[0].1);
+}
+
+#[test]
+fn lowest_order_keeps_the_far_field() {
+    // A cluster far from the query points, seen only through the local expansions
+    let mut charges: Vec<_> = cloud(40, 17)
+        .into_iter()
+        .map(|c| Charge::new(c.charge.abs(), (c.position.0 * 0.1 - 2.0, c.position.1 * 0.1 - 2.0, c.position.2 * 0.1 - 2.0)))
+        .collect();
+    charges.push(Charge::new(1e-9, (2.0, 2.0, 2.0)));
+    let points = [(0.0, 0.0, 0.0), (0.3, 0.2, -0.1), (-0.5, 0.5, 0.5)];
+    let e: Vec<f64> = [0, 1, 2, 4].iter().map(|&o| field_error(&charges, &Fmm::new(&charges, o), &points)).collect();
+    // Measured ≈ 0.28, 0.28, 0.06, 0.006; an order-0 local expansion would lose E entirely (≈ 1)
+    assert_eq!(e[0], e[1]);
+    assert!(e[1] < 0.5 && e[2] < e[1] && e[3] < e[2], "{e:?}");
+}
+
+#[test]
+fn larger_clouds_use_a_deeper_tree() {
+    let charges = cloud(4000, 15);
+    let points = probes(16);
+    let fmm = Fmm::new(&charges, 4);
+    assert!(field_error(&charges, &fmm, &points) < 5e-3);
+    assert!(potential_error(&charges, &fmm, &points) < 5e-4);
+}
+
+#[test]
+fn far_points_use_the_multipole_walk() {
+    let charges = cloud(500, 17);
+    let fmm = Fmm::new(&charges, 8);
+    for p in [(30.0, 0.0, 0.0), (-6.0, 7.0, 2.5), (2.5, 2.5, -2.5)] {
+        let exact = electric_field(&charges, &p);
+        let rel = (fmm.field(&p) - exact).magnitude() / exact.magnitude();
+        assert!(rel < 1e-4, "{rel} at {p:?}");
+    }
+}
+
+#[test]
+fn absorbs_and_extent_match_the_charge_list() {
+    let charges = cloud(400, 19);
+    let fmm = Fmm::new(&charges, 2);
+    assert_eq!(fmm.extent(), charges.extent());
+    for p in probes(20).iter().chain(charges.iter().map(|c| &c.position)) {
+        assert_eq!(fmm.absorbs(p), charges.absorbs(p), "at {p:?}");
+    }
+    assert!(Fmm::new(&[], 4).field(&(1.0, 0.0, 0.0)).magnitude() == 0.0);
+}
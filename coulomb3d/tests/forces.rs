@@ -0,0 +1,37 @@
+mod common;
+
+use common::cloud;
+use coulomb3d::*;
+
+#[test]
+fn two_charges_follow_coulombs_law() {
+    let charges = vec![Charge::new(2e-6, (0.0, 0.0, 0.0)), Charge::new(-3e-6, (1.0, 2.0, 2.0))];
+    let forces = coulomb_forces(&charges);
+    // |F| = K|q₁q₂|/r² with r = 3, attractive: F₁ points from charge 1 towards charge 2
+    let magnitude = K * 6e-12 / 9.0;
+    let (fx, fy, fz) = forces[0].components();
+    let expected = [1.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0].map(|c| c * magnitude);
+    for (f, e) in [fx, fy, fz].iter().zip(expected) {
+        assert!((f - e).abs() < 1e-12 * magnitude, "{f} != {e}");
+    }
+    let (gx, gy, gz) = forces[1].components();
+    assert!((gx + fx).abs() + (gy + fy).abs() + (gz + fz).abs() < 1e-12 * magnitude);
+
+    // Like charges repel
+    let like = coulomb_forces(&[Charge::new(1e-6, (0.0, 0.0, 0.0)), Charge::new(1e-6, (0.0, 0.0, 2.0))]);
+    let (_, _, z) = like[0].components();
+    assert!((z + K * 1e-12 / 4.0).abs() < 1e-12 * K * 1e-12);
+}
+
+#[test]
+fn forces_in_a_cloud_sum_to_zero() {
+    let charges = cloud(40, 11);
+    let forces = coulomb_forces(&charges);
+    let largest = forces.iter().fold(0.0f64, |acc, f| acc.max(f.magnitude()));
+    let mut net = Vector3D::new(0.0, 0.0, 0.0);
+    for f in &forces {
+        net += *f;
+    }
+    assert!(largest > 0.0);
+    assert!(net.magnitude() < 1e-10 * largest, "{} vs {largest}", net.magnitude());
+}
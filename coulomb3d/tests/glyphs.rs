@@ -0,0 +1,43 @@
+use coulomb3d::*;
+
+#[test]
+fn lattice_skips_the_charge_and_points_away_from_it() {
+    let charges = vec![Charge::new(1e-9, (0.0, 0.0, 0.0))];
+    let glyphs = sample_glyphs_cube(&charges, 2.0, 5);
+    // The centre of the 5 × 5 × 5 lattice sits on the charge
+    assert_eq!(glyphs.len(), 124);
+    for g in &glyphs {
+        let (x, y, z) = g.position;
+        let r = Vector3D::new(x as f64, y as f64, z as f64);
+        assert!(g.field.dot_product(&r) > 0.999 * g.field.magnitude() * r.magnitude());
+    }
+}
+
+#[test]
+fn lengths_are_capped_and_log_scaling_lifts_weak_fields() {
+    let charges = vec![Charge::new(1e-9, (0.0, 0.0, 0.0))];
+    let glyphs = sample_glyphs_cube(&charges, 2.0, 6);
+    let linear = glyph_lengths(&glyphs, GlyphScaling::Linear, 0.5);
+    let log = glyph_lengths(&glyphs, GlyphScaling::Log, 0.5);
+    assert!(linear.iter().chain(&log).all(|&l| (0.0..=0.5).contains(&l)));
+    // The strongest samples (next to the charge) are clipped to the longest arrow
+    let strongest = (0..glyphs.len()).max_by(|&a, &b| glyphs[a].field.magnitude().total_cmp(&glyphs[b].field.magnitude())).unwrap();
+    assert_eq!(linear[strongest], 0.5);
+    // Longer with the field, and never shorter on the log scale
+    for (i, j) in [(0, 1), (1, 2), (0, 7)] {
+        let (mi, mj) = (glyphs[i].field.magnitude(), glyphs[j].field.magnitude());
+        assert_eq!(mi < mj, linear[i] < linear[j]);
+    }
+    assert!(linear.iter().zip(&log).all(|(a, b)| b >= a));
+    let (lo, hi) = glyph_magnitude_range(&glyphs);
+    assert!(lo > 0.0 && lo < hi);
+}
+
+#[test]
+fn slice_glyphs_lie_in_the_plane() {
+    let charges = vec![Charge::new(1e-9, (0.0, 0.0, 1.0))];
+    let plane = SlicePlane { center: [0.0, 0.0, 0.0], normal: [0.0, 0.0, 1.0], half_size: 2.0 };
+    let glyphs = sample_glyphs_slice(&charges, &plane, 7);
+    assert_eq!(glyphs.len(), 49);
+    assert!(glyphs.iter().all(|g| g.position.2 == 0.0));
+}
@@ -0,0 +1,85 @@
+use coulomb3d::*;
+
+const Q: f64 = 1e-6;
+
+fn total_potential(charges: &[Charge], images: &ImageSystem, s: &(f32, f32, f32)) -> f64 {
+    electric_potential(charges, s) + images.potential(s)
+}
+
+#[test]
+fn grounded_sphere_has_the_kelvin_image() {
+    let charges = vec![Charge::new(Q, (0.0, 0.0, 2.0))];
+    let sphere = Conductor::Sphere { center: [0.0; 3], radius: 1.0, grounded: true, charge: 0.0 };
+    let images = ImageSystem::new(&charges, std::slice::from_ref(&sphere));
+    assert_eq!(images.images().len(), 1);
+    let image = &images.images()[0];
+    assert!((image.charge + Q / 2.0).abs() < 1e-15);
+    assert!((image.position.2 - 0.5).abs() < 1e-6);
+
+    // The surface sits at zero potential
+    for p in [(1.0, 0.0, 0.0), (0.0, 0.0, -1.0), (0.0, 0.6, 0.8)] {
+        let phi = total_potential(&charges, &images, &p);
+        assert!(phi.abs() < 1e-6 * K * Q, "{phi} at {p:?}");
+    }
+
+    // The induced charge adds up to the image charge −qR/d
+    let field = Superposition::new(vec![&charges, &images]);
+    let induced = sample_surface_charge(&field, &sphere, 121, 0.0).total_charge();
+    assert!((induced + Q / 2.0).abs() < 2e-3 * Q, "{induced}");
+}
+
+#[test]
+fn grounded_plane_induces_the_textbook_density() {
+    let d = 1.5;
+    let charges = vec![Charge::new(Q, (0.0, d as f32, 0.0))];
+    let plane = Conductor::Plane { point: [0.0; 3], normal: [0.0, 1.0, 0.0] };
+    let images = ImageSystem::new(&charges, std::slice::from_ref(&plane));
+    let field = Superposition::new(vec![&charges, &images]);
+    for rho in [0.0, 0.5, 2.0] {
+        let s = (rho as f32, 0.0, 0.0);
+        let sigma = surface_charge_density(&field, &plane, &s);
+        let exact = -Q * d / (2.0 * std::f64::consts::PI * (rho * rho + d * d).powf(1.5));
+        assert!((sigma - exact).abs() < 1e-5 * exact.abs(), "{sigma} vs {exact}");
+        assert!(field.potential(&s).abs() < 1e-9 * K * Q);
+    }
+    // Field lines stop at the plane
+    assert!(field.absorbs(&(0.0, -0.01, 0.0)) && !field.absorbs(&(0.0, 0.01, 0.0)));
+}
+
+#[test]
+fn isolated_sphere_keeps_its_charge() {
+    let charges = vec![Charge::new(Q, (3.0, 0.0, 0.0))];
+    let net = 2e-7;
+    let sphere = Conductor::Sphere { center: [0.0; 3], radius: 1.0, grounded: false, charge: net };
+    let images = ImageSystem::new(&charges, std::slice::from_ref(&sphere));
+    let inside: f64 = images.images().iter().map(|c| c.charge).sum();
+    assert!((inside - net).abs() < 1e-18);
+
+    // The surface is an equipotential, but not at zero
+    let field = Superposition::new(vec![&charges, &images]);
+    let phi0 = field.potential(&(1.0, 0.0, 0.0));
+    for p in [(-1.0, 0.0, 0.0), (0.0, 1.0, 0.0), (0.0, 0.0, -1.0)] {
+        assert!((field.potential(&p) - phi0).abs() < 1e-6 * phi0.abs(), "at {p:?}");
+    }
+    let induced = sample_surface_charge(&field, &sphere, 121, 0.0).total_charge();
+    assert!((induced - net).abs() < 2e-3 * Q, "{induced}");
+}
+
+#[test]
+fn plane_and_sphere_images_converge() {
+    // A grounded sphere resting above a grounded plane, charge beside it
+    let charges = vec![Charge::new(Q, (2.0, 1.0, 0.0))];
+    let conductors = [
+        Conductor::Plane { point: [0.0; 3], normal: [0.0, 1.0, 0.0] },
+        Conductor::Sphere { center: [0.0, 1.5, 0.0], radius: 1.0, grounded: true, charge: 0.0 },
+    ];
+    let images = ImageSystem::new(&charges, &conductors);
+    assert!(images.images().len() > 3);
+    let field = Superposition::new(vec![&charges, &images]);
+    for p in [(3.0, 0.0, 1.0), (0.0, 2.5, 0.0), (1.0, 1.5, 0.0), (-0.6, 0.7, 0.0)] {
+        assert!(field.potential(&p).abs() < 2e-3 * K * Q, "{} at {p:?}", field.potential(&p));
+    }
+    // Charges inside a conductor are ignored
+    let hidden = ImageSystem::new(&[Charge::new(Q, (0.0, -1.0, 0.0))], &conductors[..1]);
+    assert!(hidden.images().is_empty());
+}
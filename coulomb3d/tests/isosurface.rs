@@ -0,0 +1,54 @@
+use coulomb3d::*;
+
+/// Surface area of a triangle soup.
+fn area(tris: &[Triangle]) -> f64 {
+    tris.iter()
+        .map(|[a, b, c]| {
+            let ab = Vector3D::new((b.0 - a.0) as f64, (b.1 - a.1) as f64, (b.2 - a.2) as f64);
+            let ac = Vector3D::new((c.0 - a.0) as f64, (c.1 - a.1) as f64, (c.2 - a.2) as f64);
+            0.5 * ab.cross_product(&ac).magnitude()
+        })
+        .sum()
+}
+
+#[test]
+fn single_charge_gives_sphere_of_radius_kq_over_phi() {
+    let q = 1e-9;
+    let charges = vec![Charge::new(q, (0.0, 0.0, 0.0))];
+    let grid = ScalarGrid::sample_cube(2.0, 81, |p| electric_potential(&charges, p));
+
+    for phi in [6.0, 10.0, 20.0] {
+        let radius = K * q / phi;
+        let tris = marching_cubes(&grid, phi);
+        assert!(!tris.is_empty());
+
+        for [a, b, c] in &tris {
+            for p in [a, b, c] {
+                let r = ((p.0 * p.0 + p.1 * p.1 + p.2 * p.2) as f64).sqrt();
+                assert!((r - radius).abs() < 0.01 * radius, "vertex at r = {r}, expected {radius}");
+            }
+        }
+        let sphere = 4.0 * std::f64::consts::PI * radius * radius;
+        assert!((area(&tris) - sphere).abs() < 0.02 * sphere);
+    }
+}
+
+#[test]
+fn triangles_face_towards_higher_values() {
+    // φ decreases outwards from a positive charge, so normals point inwards
+    let charges = vec![Charge::new(1e-9, (0.0, 0.0, 0.0))];
+    let grid = ScalarGrid::sample_cube(2.0, 41, |p| electric_potential(&charges, p));
+    for [a, b, c] in marching_cubes(&grid, 10.0) {
+        let ab = Vector3D::new((b.0 - a.0) as f64, (b.1 - a.1) as f64, (b.2 - a.2) as f64);
+        let ac = Vector3D::new((c.0 - a.0) as f64, (c.1 - a.1) as f64, (c.2 - a.2) as f64);
+        let outward = Vector3D::new(a.0 as f64, a.1 as f64, a.2 as f64);
+        assert!(ab.cross_product(&ac).dot_product(&outward) <= 0.0);
+    }
+}
+
+#[test]
+fn level_outside_the_sampled_range_gives_nothing() {
+    let grid = ScalarGrid::sample_cube(1.0, 10, |p| p.0 as f64);
+    assert!(marching_cubes(&grid, 5.0).is_empty());
+    assert!(!marching_cubes(&grid, 0.1).is_empty());
+}
@@ -0,0 +1,78 @@
+use coulomb3d::*;
+
+fn close(a: f64, b: f64, tol: f64) -> bool {
+    (a - b).abs() <= tol * b.abs()
+}
+
+#[test]
+fn long_wire_follows_amperes_law() {
+    // |B| = μ₀I/2πd around a long straight wire, circling it by the right-hand rule
+    let wire = [Current::Segment { start: [0.0, 0.0, -1e4], end: [0.0, 0.0, 1e4], current: 5.0 }];
+    let b = magnetic_field(&wire, &(0.5, 0.0, 0.0));
+    assert!(close(b.magnitude(), MU_0 * 5.0 / (2.0 * std::f64::consts::PI * 0.5), 1e-6), "{b:?}");
+    assert!(b.components().1 > 0.999 * b.magnitude());
+}
+
+#[test]
+fn loop_matches_the_on_axis_formula_and_a_dipole_far_away() {
+    let (a, i) = (0.5, 2.0);
+    let coil = [Current::Loop { center: [0.0, 0.0, 0.0], normal: [0.0, 0.0, 1.0], radius: a, current: i }];
+    for z in [0.0f32, 0.3, -1.2] {
+        let on_axis = MU_0 * i * a * a / (2.0 * (a * a + (z as f64).powi(2)).powf(1.5));
+        let b = magnetic_field(&coil, &(0.0, 0.0, z));
+        assert!(close(b.components().2, on_axis, 1e-9) && b.components().0.abs() < 1e-15);
+    }
+    // Far field of the moment m = Iπa² ẑ, off the axis
+    let m = magnetic_dipole_moment(&coil);
+    assert!(close(m.components().2, i * std::f64::consts::PI * a * a, 1e-12));
+    let r = Vector3D::new(12.0, 5.0, 20.0);
+    let rr = r.magnitude();
+    let rhat = r.normalized();
+    let dipole = (rhat.scalar_product(&(3.0 * m.dot_product(&rhat))) - m)
+        .scalar_product(&(MU_0 / (4.0 * std::f64::consts::PI * rr.powi(3))));
+    let b = magnetic_field(&coil, &(12.0, 5.0, 20.0));
+    assert!((b - dipole).magnitude() < 1e-3 * dipole.magnitude(), "{b:?} vs {dipole:?}");
+}
+
+#[test]
+fn square_of_segments_has_the_moment_of_its_area() {
+    let corners = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+    let square: Vec<_> = (0..4)
+        .map(|k| Current::Segment { start: corners[k], end: corners[(k + 1) % 4], current: 3.0 })
+        .collect();
+    let m = magnetic_dipole_moment(&square);
+    assert!((m - Vector3D::new(0.0, 0.0, 3.0)).magnitude() < 1e-12);
+}
+
+#[test]
+fn long_solenoid_has_a_uniform_interior_field() {
+    let coil = [Current::Solenoid {
+        center: [0.0, 0.0, 0.0],
+        axis: [1.0, 0.0, 0.0],
+        radius: 0.2,
+        length: 4.0,
+        turns: 400,
+        current: 1.5,
+    }];
+    let inside = MU_0 * 100.0 * 1.5;
+    for p in [(0.0f32, 0.0f32, 0.0f32), (0.3, 0.1, -0.05)] {
+        let b = magnetic_field(&coil, &p);
+        assert!(close(b.components().0, inside, 1e-2), "{b:?}");
+    }
+}
+
+#[test]
+fn field_lines_of_a_loop_close_on_themselves() {
+    let coil = vec![Current::Loop { center: [0.0, 0.0, 0.0], normal: [0.0, 1.0, 0.0], radius: 1.0, current: 1.0 }];
+    let settings = FieldLineSettings { seeds: 4, max_steps: 2000, step: 0.02, ..FieldLineSettings::default() };
+    let (lines, bounds) = generate_field_lines_in(&MagneticField::new(&coil), &current_seeds(&coil, &settings), &settings);
+    assert_eq!(lines.len(), 4);
+    // Lines through the middle of the loop swing far out and may leave the bounding cube;
+    // the others return to their seed
+    let closed = lines.iter().filter(|l| l.first() == l.last()).count();
+    assert!(closed >= 2, "{closed} closed");
+    for line in lines.iter().filter(|l| l.first() != l.last()) {
+        let (x, y, z) = *line.last().unwrap();
+        assert!(x.abs().max(y.abs()).max(z.abs()) > bounds - settings.step as f32 * 2.0);
+    }
+}
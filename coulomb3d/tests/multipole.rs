@@ -0,0 +1,49 @@
+mod common;
+
+use common::cloud;
+use coulomb3d::*;
+
+#[test]
+fn approximate_field_is_minus_the_gradient_of_the_approximate_potential() {
+    let charges = cloud(20, 31);
+    let approx = MultipoleApproximation::new(&charges, &(0.2, -0.1, 0.3));
+    let p = (4.0f32, -3.0f32, 5.0f32);
+    let h = 1e-2f32;
+    let d = |dx: f32, dy: f32, dz: f32| {
+        let a = approx.potential(&(p.0 + dx, p.1 + dy, p.2 + dz));
+        let b = approx.potential(&(p.0 - dx, p.1 - dy, p.2 - dz));
+        -(a - b) / (2.0 * h as f64)
+    };
+    let numeric = Vector3D::new(d(h, 0.0, 0.0), d(0.0, h, 0.0), d(0.0, 0.0, h));
+    let e = approx.field(&p);
+    assert!((e - numeric).magnitude() < 1e-3 * e.magnitude(), "{e:?} vs {numeric:?}");
+}
+
+#[test]
+fn relative_error_falls_off_with_distance() {
+    // The first neglected term is the octupole, so the relative error of E
+    // drops roughly like (size / r)³ along any ray
+    let charges = cloud(30, 33);
+    let approx = MultipoleApproximation::new(&charges, &(0.0, 0.0, 0.0));
+    let errors: Vec<f64> = [5.0f32, 10.0, 20.0, 40.0]
+        .iter()
+        .map(|&r| multipole_relative_error(&charges, &approx, &(0.6 * r, -0.48 * r, 0.64 * r)))
+        .collect();
+    for pair in errors.windows(2) {
+        assert!(pair[1] < pair[0] / 4.0, "{errors:?}");
+    }
+    assert!(errors[3] < 1e-3, "{errors:?}");
+}
+
+#[test]
+fn error_slice_is_large_near_the_charges() {
+    let charges = cloud(10, 35);
+    let plane = SlicePlane { center: [0.0, 0.0, 0.0], normal: [0.0, 0.0, 1.0], half_size: 20.0 };
+    let grid = sample_multipole_error(&charges, &(0.0, 0.0, 0.0), &plane, 41);
+    assert_eq!(grid.quantity, SliceQuantity::MultipoleError);
+    // The middle sample sits on the expansion centre itself
+    assert_eq!(grid.values[20 * 41 + 20], f64::INFINITY);
+    let near = grid.values[21 * 41 + 21];
+    let corner = grid.values[0];
+    assert!(corner < 1e-2 && near > 10.0 * corner, "{near} {corner}");
+}
@@ -0,0 +1,58 @@
+use coulomb3d::*;
+
+#[test]
+fn parallel_plates_give_a_uniform_field() {
+    // Plates at z = ±1 m, 100 V apart, insulating faces: ϕ is linear in z between them
+    let solver = GridSolver {
+        half_size: Some(2.0),
+        resolution: 25,
+        boundary: Boundary::Neumann,
+        electrodes: vec![
+            Electrode::Box { min: [-3.0, -3.0, -3.0], max: [3.0, 3.0, -1.0], potential: 0.0 },
+            Electrode::Box { min: [-3.0, -3.0, 1.0], max: [3.0, 3.0, 3.0], potential: 100.0 },
+        ],
+        ..GridSolver::default()
+    };
+    let solution = solver.solve(&[]);
+    assert!(solution.residual <= solver.tolerance);
+    for z in [-0.5f32, 0.0, 0.3, 0.75] {
+        let p = (0.4, -0.7, z);
+        assert!((solution.potential(&p) - 50.0 * (z as f64 + 1.0)).abs() < 1e-3, "at {p:?}");
+        let e = solution.field(&p);
+        assert!((e - Vector3D::new(0.0, 0.0, -50.0)).magnitude() < 1e-3, "{e:?}");
+    }
+    assert!(solution.absorbs(&(0.0, 0.0, 1.2)) && !solution.absorbs(&(0.0, 0.0, 0.9)));
+}
+
+#[test]
+fn point_charge_matches_coulomb_away_from_the_walls() {
+    // Inside a large grounded box the walls only add a nearly constant potential near the
+    // centre, so differences of ϕ and the field follow Coulomb's law
+    let q = 1e-9;
+    let solver = GridSolver { half_size: Some(6.0), resolution: 61, ..GridSolver::default() };
+    let solution = solver.solve(&[Charge::new(q, (0.0, 0.0, 0.0))]);
+    let drop = solution.potential(&(1.0, 0.0, 0.0)) - solution.potential(&(0.0, 2.0, 0.0));
+    assert!((drop - K * q * 0.5).abs() < 0.03 * K * q * 0.5, "{drop}");
+    let e = solution.field(&(0.0, 0.0, -1.5));
+    let exact = K * q / 2.25;
+    assert!((e.magnitude() - exact).abs() < 0.05 * exact, "{e:?}");
+    assert!(e.dot_product(&Vector3D::new(0.0, 0.0, -1.0)) > 0.99 * e.magnitude());
+}
+
+#[test]
+fn field_lines_end_on_a_grounded_electrode() {
+    let charges = vec![Charge::new(1e-9, (0.0, 0.0, 0.0))];
+    let solver = GridSolver {
+        resolution: 41,
+        electrodes: vec![Electrode::Sphere { center: [2.0, 0.0, 0.0], radius: 0.6, potential: 0.0 }],
+        ..GridSolver::default()
+    };
+    let solution = solver.solve(&charges);
+    let settings = FieldLineSettings::default();
+    let (lines, _) = generate_field_lines_in(&solution, &charge_seeds(&charges, &settings), &settings);
+    let ends_on_sphere = |line: &Polyline| {
+        let (x, y, z) = *line.last().unwrap();
+        ((x - 2.0).powi(2) + y * y + z * z).sqrt() < 0.6 + 1.5 * settings.step as f32
+    };
+    assert!(lines.iter().filter(|l| ends_on_sphere(l)).count() >= 2);
+}
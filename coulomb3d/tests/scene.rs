@@ -0,0 +1,140 @@
+use coulomb3d::scene::{Scene, SceneCharge, MAX_FIELD_LINE_STEPS, SCENE_VERSION};
+use coulomb3d::*;
+
+fn charge(q: f64, position: [f64; 3]) -> SceneCharge {
+    SceneCharge { charge: q, position, color: None, mass: None, velocity: None, pinned: false }
+}
+
+fn error(text: &str) -> String {
+    Scene::from_toml(text).unwrap_err().to_string()
+}
+
+#[test]
+fn scene_round_trips_through_toml() {
+    let scene = Scene {
+        reference: [0.5, -1.0, 2.0],
+        charges: vec![
+            SceneCharge { color: Some([0.9, 0.2, 0.2, 1.0]), mass: Some(2.0), pinned: true, ..charge(1e-6, [1.0, 0.0, 0.0]) },
+            SceneCharge { velocity: Some([0.0, 1.0, 0.0]), ..charge(-2e-6, [-1.0, 0.0, 0.0]) },
+        ],
+        distributions: vec![Distribution::Ring { center: [0.0; 3], normal: [0.0, 1.0, 0.0], radius: 1.5, charge: -2e-5 }],
+        conductors: vec![Conductor::Sphere { center: [0.0, 3.0, 0.0], radius: 1.0, grounded: true, charge: 0.0 }],
+        seeds: vec![SeedShape::Point { position: [0.0, 0.0, 1.0] }],
+        ..Scene::default()
+    };
+    let text = scene.to_toml().unwrap();
+    assert_eq!(Scene::from_toml(&text).unwrap(), scene);
+}
+
+#[test]
+fn unversioned_files_are_read_as_version_one() {
+    let scene = Scene::from_toml("[[charges]]\ncharge = 1e-6\nposition = [0.0, 0.0, 0.0]\n").unwrap();
+    assert_eq!(scene.version, 1);
+    assert_eq!(scene.configuration().len(), 1);
+}
+
+#[test]
+fn newer_versions_are_rejected() {
+    let message = error(&format!("version = {}\n", SCENE_VERSION + 1));
+    assert_eq!(
+        message,
+        format!("Invalid scene file: version {} is newer than the supported version {SCENE_VERSION}", SCENE_VERSION + 1)
+    );
+}
+
+#[test]
+fn validation_errors_name_the_offending_entry() {
+    let shared = "[[charges]]\ncharge = 1e-6\nposition = [1.0, 0.0, 0.0]\n\
+                  [[charges]]\ncharge = -1e-6\nposition = [1.0, 0.0, 0.0]\n";
+    assert_eq!(error(shared), "Invalid scene file: charge #2 shares its position with another charge");
+
+    let massless = "[[charges]]\ncharge = 1e-6\nposition = [0.0, 0.0, 0.0]\nmass = 0.0\n";
+    assert_eq!(error(massless), "Invalid scene file: charge #1 must have a positive mass");
+
+    let flat = "[[distributions]]\nkind = \"disk\"\ncenter = [0.0, 0.0, 0.0]\nnormal = [0.0, 0.0, 0.0]\n\
+                radius = 1.0\nsigma = 1e-9\n";
+    assert_eq!(error(flat), "Invalid scene file: distribution #1: normal must not be the zero vector");
+
+    let step = "[field_lines]\nstep = -0.1\n";
+    assert_eq!(error(step), "Invalid scene file: field line step and seed_radius must be positive");
+
+    let budget = format!(
+        "Invalid scene file: field line seeds must be at most {MAX_SEED_POINTS} and max_steps at most {MAX_FIELD_LINE_STEPS}"
+    );
+    assert_eq!(error(&format!("[field_lines]\nseeds = {}\n", MAX_SEED_POINTS + 1)), budget);
+    assert_eq!(error(&format!("[field_lines]\nmax_steps = {}\n", MAX_FIELD_LINE_STEPS + 1)), budget);
+    assert!(Scene::from_toml(&format!("[field_lines]\nseeds = {MAX_SEED_POINTS}\n")).is_ok());
+
+    // Syntax errors report the line they occur on
+    assert!(error("version = 1\nreference = \"origin\"\n").starts_with("Invalid scene file: line 2:"));
+}
+
+#[test]
+fn only_version_nine_and_later_default_to_flux_seeding() {
+    let old = Scene::from_toml("version = 8\n[field_lines]\nshow = true\n").unwrap();
+    assert_eq!(old.field_lines.settings.seeding, Seeding::PerCharge);
+    assert_eq!(Scene::from_toml("").unwrap().field_lines.settings.seeding, Seeding::PerCharge);
+
+    let explicit = Scene::from_toml("version = 8\n[field_lines]\nseeding = \"flux\"\n").unwrap();
+    assert_eq!(explicit.field_lines.settings.seeding, Seeding::Flux);
+
+    let new = Scene::from_toml(&format!("version = {SCENE_VERSION}\n")).unwrap();
+    assert_eq!(new.field_lines.settings.seeding, Seeding::Flux);
+    assert_eq!(Scene::default().field_lines.settings.seeding, Seeding::Flux);
+}
+
+#[test]
+fn sources_add_distributions_and_images_to_the_point_charges() {
+    let scene = Scene {
+        charges: vec![charge(1e-6, [0.0, 1.0, 0.0])],
+        distributions: vec![Distribution::Ring { center: [0.0; 3], normal: [0.0, 1.0, 0.0], radius: 1.5, charge: -2e-6 }],
+        conductors: vec![Conductor::Plane { point: [0.0, -2.0, 0.0], normal: [0.0, 1.0, 0.0] }],
+        ..Scene::default()
+    };
+    let sources = scene.sources();
+    assert!(sources.grid.is_none() && sources.screened.is_none());
+    assert_eq!(sources.images.images().len(), 1);
+
+    let charges = scene.configuration();
+    let parts = Superposition::new(vec![&charges, &scene.distributions, &sources.images]);
+    let at = (0.5, 0.3, -0.2);
+    assert_eq!(sources.potential(&at), parts.potential(&at));
+    assert!((sources.field(&at) - parts.field(&at)).magnitude() < 1e-12 * parts.field(&at).magnitude());
+
+    let gridded = Scene { grid_solver: Some(GridSolver { resolution: 9, ..GridSolver::default() }), ..scene };
+    assert!(gridded.sources().grid.is_some());
+    assert!(Scene::default().sources().is_empty());
+}
+
+#[test]
+fn grid_electrodes_push_on_the_point_charges() {
+    // Plates 4 m apart held 100 V apart: E ≈ 25 V/m towards the grounded plate
+    let plate = |y: f64, potential: f64| Electrode::Box { min: [-3.0, y - 0.1, -3.0], max: [3.0, y + 0.1, 3.0], potential };
+    let scene = Scene {
+        charges: vec![charge(1e-9, [0.0, 0.5, 0.0]), charge(1e-9, [0.0, -0.5, 0.0])],
+        grid_solver: Some(GridSolver {
+            half_size: Some(4.0),
+            resolution: 41,
+            electrodes: vec![plate(-2.1, 0.0), plate(2.1, 100.0)],
+            ..GridSolver::default()
+        }),
+        ..Scene::default()
+    };
+    let forces = scene.sources().forces();
+    let (f1, f2) = (forces[0].components(), forces[1].components());
+    let plates = -1e-9 * 25.0;
+    assert!(((f1.1 + f2.1) / 2.0 - plates).abs() < 0.05 * plates.abs(), "{f1:?} {f2:?}");
+    assert!(f1.0.abs() < 1e-3 * plates.abs() && f1.2.abs() < 1e-3 * plates.abs());
+    // The charges still repel each other
+    assert!(f1.1 - f2.1 > K * 1e-18);
+}
+
+#[test]
+fn distributions_need_a_finite_size() {
+    let ring = "[[distributions]]\nkind = \"ring\"\ncenter = [0.0, 0.0, 0.0]\nnormal = [0.0, 1.0, 0.0]\n\
+                radius = inf\ncharge = 1e-6\n";
+    assert_eq!(error(ring), "Invalid scene file: distribution #1: must have a positive size");
+    let slab = "[[distributions]]\nkind = \"slab\"\npoint = [0.0, 0.0, 0.0]\nnormal = [0.0, 1.0, 0.0]\n\
+                thickness = nan\nrho = 1e-6\n";
+    assert_eq!(error(slab), "Invalid scene file: distribution #1: must have a positive size");
+}
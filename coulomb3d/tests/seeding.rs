@@ -0,0 +1,42 @@
+use coulomb3d::*;
+
+#[test]
+fn grid_and_circle_points_lie_in_their_plane() {
+    let grid = SeedShape::Grid { center: [1.0, 2.0, 3.0], normal: [0.0, 0.0, 2.0], size: [2.0, 1.0], count: [4, 3] };
+    let points = grid.points();
+    assert_eq!(points.len(), 12);
+    for p in &points {
+        let (x, y, z) = p.components();
+        assert!((z - 3.0).abs() < 1e-12 && (x - 1.0).abs() < 1.0 && (y - 2.0).abs() < 1.0);
+    }
+    let circle = SeedShape::Circle { center: [0.0, 0.0, 0.0], normal: [1.0, 1.0, 0.0], radius: 0.5, count: 7 };
+    for p in circle.points() {
+        let (x, y, _) = p.components();
+        assert!((p.magnitude() - 0.5).abs() < 1e-12 && (x + y).abs() < 1e-12);
+    }
+    // Every point is traced both ways
+    let seeds = user_seeds(&[grid, circle, SeedShape::Point { position: [0.0; 3] }]);
+    assert_eq!(seeds.len(), 2 * (12 + 7 + 1));
+    assert_eq!(seeds.iter().filter(|s| s.direction < 0.0).count(), 20);
+}
+
+#[test]
+fn seeds_between_capacitor_plates_give_straight_lines_across() {
+    let plates = vec![
+        Distribution::Plane { point: [0.0, 1.0, 0.0], normal: [0.0, 1.0, 0.0], sigma: 1e-9 },
+        Distribution::Plane { point: [0.0, -1.0, 0.0], normal: [0.0, 1.0, 0.0], sigma: -1e-9 },
+    ];
+    // Steps shorter than the plates' absorbing layer, so no line jumps across them
+    let settings = FieldLineSettings { step: 0.02, ..FieldLineSettings::default() };
+    let grid = SeedShape::Grid { center: [0.0, 0.0, 0.0], normal: [0.0, 1.0, 0.0], size: [2.0, 2.0], count: [2, 2] };
+    let seeds = user_seeds(&[grid]);
+    let (lines, stats, _) = generate_charge_field_lines(&plates, &[], &seeds, &settings);
+    assert_eq!(lines.len(), 8);
+    for (line, stats) in lines.iter().zip(&stats) {
+        assert_eq!(stats.end, LineEnd::Absorbed);
+        let (x0, _, z0) = line[0];
+        let (x, y, z) = *line.last().unwrap();
+        assert!((x - x0).abs() < 1e-4 && (z - z0).abs() < 1e-4);
+        assert!(y.abs() > 0.8, "{y}");
+    }
+}
@@ -0,0 +1,88 @@
+use coulomb3d::*;
+
+fn vector(p: (f32, f32, f32)) -> Vector3D {
+    Vector3D::new(p.0 as f64, p.1 as f64, p.2 as f64)
+}
+
+#[test]
+fn axes_are_orthonormal_and_right_handed() {
+    let xy = SlicePlane { center: [0.0; 3], normal: [0.0, 0.0, 2.0], half_size: 1.0 };
+    let (u, v) = xy.axes();
+    assert_eq!((u.components(), v.components()), ((1.0, 0.0, 0.0), (0.0, 1.0, 0.0)));
+    let yz = SlicePlane { normal: [-3.0, 0.0, 0.0], ..xy.clone() };
+    let (u, v) = yz.axes();
+    assert_eq!((u.components(), v.components()), ((0.0, 1.0, 0.0), (0.0, 0.0, -1.0)));
+
+    for normal in [[1.0, 2.0, 3.0], [0.95, -0.1, 0.2], [-1.0, 1.0, -1.0]] {
+        let (u, v) = SlicePlane { normal, ..xy.clone() }.axes();
+        let n = Vector3D::new(normal[0], normal[1], normal[2]).normalized();
+        assert!((u.magnitude() - 1.0).abs() < 1e-12 && (v.magnitude() - 1.0).abs() < 1e-12);
+        assert!(u.dot_product(&v).abs() < 1e-12 && u.dot_product(&n).abs() < 1e-12 && v.dot_product(&n).abs() < 1e-12);
+        assert!((u.cross_product(&v) - n).magnitude() < 1e-12, "{normal:?}");
+    }
+}
+
+#[test]
+fn corners_sit_half_size_from_the_centre_along_each_axis() {
+    let plane = SlicePlane { center: [1.0, 2.0, 3.0], normal: [0.0, 0.0, 1.0], half_size: 1.5 };
+    assert_eq!(plane.point(0.0, 0.0), (1.0, 2.0, 3.0));
+    assert_eq!(plane.point(1.0, 1.0), (2.5, 3.5, 3.0));
+    assert_eq!(plane.point(-1.0, 1.0), (-0.5, 3.5, 3.0));
+    assert_eq!(plane.point(-1.0, -1.0), (-0.5, 0.5, 3.0));
+
+    let oblique = SlicePlane { normal: [1.0, -2.0, 0.5], ..plane.clone() };
+    let (u, v) = oblique.axes();
+    let centre = vector(oblique.point(0.0, 0.0));
+    for (a, b) in [(1.0, 1.0), (1.0, -1.0), (-1.0, 1.0), (-1.0, -1.0)] {
+        let offset = vector(oblique.point(a, b)) - centre;
+        assert!((offset.dot_product(&u) - a * 1.5).abs() < 1e-5);
+        assert!((offset.dot_product(&v) - b * 1.5).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn grid_points_run_along_u_first() {
+    let plane = SlicePlane { center: [0.0; 3], normal: [0.0, 0.0, 1.0], half_size: 1.0 };
+    let points: Vec<_> = plane.grid_points(3).collect();
+    assert_eq!(points.len(), 9);
+    assert_eq!(points[0], (-1.0, -1.0, 0.0));
+    assert_eq!(points[1], (0.0, -1.0, 0.0));
+    assert_eq!(points[4], (0.0, 0.0, 0.0));
+    assert_eq!(points[8], (1.0, 1.0, 0.0));
+    // Fewer than two points per edge cannot span the plane
+    assert_eq!(plane.grid_points(1).count(), 4);
+}
+
+#[test]
+fn single_charge_slice_matches_coulombs_law() {
+    let q = 1e-9;
+    let charges = vec![Charge::new(q, (0.0, 0.0, 0.0))];
+    let plane = SlicePlane { center: [0.0, 0.0, 1.0], normal: [0.0, 0.0, 1.0], half_size: 2.0 };
+    let phi = sample_slice(charges.as_slice(), &plane, SliceQuantity::Potential, 5);
+    let e = sample_slice(charges.as_slice(), &plane, SliceQuantity::FieldMagnitude, 5);
+    assert_eq!((phi.quantity, e.quantity), (SliceQuantity::Potential, SliceQuantity::FieldMagnitude));
+    assert_eq!((phi.values.len(), e.values.len()), (25, 25));
+    for ((p, &v), &m) in plane.grid_points(5).zip(&phi.values).zip(&e.values) {
+        let r = vector(p).magnitude();
+        assert!((v - K * q / r).abs() < 1e-6 * v, "{p:?}");
+        assert!((m - K * q / (r * r)).abs() < 1e-6 * m, "{p:?}");
+    }
+    // The middle sample sits 1 m above the charge
+    assert!((e.values[12] - K * q).abs() < 1e-6 * K * q);
+}
+
+#[test]
+fn percentile_range_clips_the_sample_next_to_the_charge() {
+    let mut ramp: Vec<f64> = (0..=100).rev().map(f64::from).collect();
+    assert_eq!(percentile_range(&mut ramp), (2.0, 98.0));
+    assert_eq!(percentile_range(&mut []), (0.0, 0.0));
+
+    // The centre sample passes 1 cm from the charge and dwarfs the rest of the slice
+    let charges = vec![Charge::new(1e-9, (0.0, 0.0, 0.0))];
+    let plane = SlicePlane { center: [0.0, 0.0, 0.01], normal: [0.0, 0.0, 1.0], half_size: 2.0 };
+    let grid = sample_slice(charges.as_slice(), &plane, SliceQuantity::FieldMagnitude, 21);
+    let peak = grid.values[grid.values.len() / 2];
+    assert_eq!(grid.values.iter().copied().fold(0.0, f64::max), peak);
+    let (lo, hi) = grid.value_range();
+    assert!(0.0 < lo && lo < hi && hi < 1e-3 * peak, "{lo} {hi} {peak}");
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::cloud;
+use coulomb3d::*;
+
+const FOUR_PI: f64 = 4.0 * std::f64::consts::PI;
+
+fn close(a: f64, b: f64, tol: f64) -> bool {
+    (a - b).abs() <= tol * b.abs().max(1e-30)
+}
+
+#[test]
+fn low_orders_match_the_cartesian_moments() {
+    // Jackson (4.4)–(4.6) relate Q_ℓm to Q, p and the traceless quadrupole
+    let charges = cloud(40, 21);
+    let c = (0.1f32, 0.2f32, -0.3f32);
+    let sm = spherical_multipoles(&charges, &c, 2);
+    let mm = multipole_moments(&charges, &c);
+    let (px, py, pz) = mm.dipole.components();
+    let q = &mm.quadrupole;
+
+    assert!(close(sm.moment(0, 0).re, mm.monopole / FOUR_PI.sqrt(), 1e-6));
+    assert!(close(sm.moment(1, 0).re, (3.0 / FOUR_PI).sqrt() * pz, 1e-6));
+    let q11 = sm.moment(1, 1);
+    let s = -(3.0 / (2.0 * FOUR_PI)).sqrt();
+    assert!(close(q11.re, s * px, 1e-6) && close(q11.im, -s * py, 1e-6));
+    assert!(close(sm.moment(2, 0).re, 0.5 * (5.0 / FOUR_PI).sqrt() * q[2][2], 1e-6));
+    let q22 = sm.moment(2, 2);
+    let s = (30.0 / FOUR_PI).sqrt() / 12.0;
+    assert!(close(q22.re, s * (q[0][0] - q[1][1]), 1e-6) && close(q22.im, -2.0 * s * q[0][1], 1e-6));
+}
+
+#[test]
+fn negative_orders_are_conjugates() {
+    let sm = spherical_multipoles(&cloud(30, 23), &(0.0, 0.0, 0.0), 6);
+    for l in 0..=6 {
+        for m in 1..=l as i64 {
+            let (pos, neg) = (sm.moment(l, m), sm.moment(l, -m));
+            let sign = if m % 2 == 0 { 1.0 } else { -1.0 };
+            assert!((neg.re - sign * pos.re).abs() < 1e-18 && (neg.im + sign * pos.im).abs() < 1e-18);
+        }
+    }
+    assert_eq!(sm.moment(7, 0), harmonics::Complex::ZERO);
+}
+
+#[test]
+fn axial_charge_gives_the_legendre_series() {
+    // q at z = a: Q_ℓ0 = q aˡ √((2ℓ+1)/4π), and on the axis ϕ_ℓ = K q aˡ / zˡ⁺¹
+    let (q, a, z) = (2e-9, 0.5f32, 3.0f32);
+    let sm = spherical_multipoles(&[Charge::new(q, (0.0, 0.0, a))], &(0.0, 0.0, 0.0), 8);
+    for l in 0..=8 {
+        let expected = q * (a as f64).powi(l as i32) * ((2 * l + 1) as f64 / FOUR_PI).sqrt();
+        assert!(close(sm.moment(l, 0).re, expected, 1e-6), "Q_{l}0");
+        assert!(sm.moment(l, 1).norm() < 1e-20);
+        let term = K * q * (a as f64).powi(l as i32) / (z as f64).powi(l as i32 + 1);
+        assert!(close(sm.potential_term(l, &(0.0, 0.0, z)), term, 1e-6), "phi_{l}");
+    }
+}
+
+#[test]
+fn truncated_potential_converges_to_the_exact_one() {
+    let charges = cloud(60, 25);
+    let sm = spherical_multipoles(&charges, &(0.0, 0.0, 0.0), 12);
+    assert!(sm.radius < 3.5);
+    let p = (5.0, -6.0, 4.0);
+    let exact = electric_potential(&charges, &p);
+    let errors: Vec<f64> = [0, 2, 4, 8, 12].iter().map(|&l| (sm.potential_to(l, &p) - exact).abs()).collect();
+    for pair in errors.windows(2) {
+        assert!(pair[1] < pair[0], "{errors:?}");
+    }
+    assert!(errors[4] < 1e-6 * exact.abs(), "{errors:?} vs {exact}");
+    assert_eq!(sm.potential(&p), sm.potential_to(12, &p));
+}
@@ -0,0 +1,97 @@
+use coulomb3d::*;
+
+#[test]
+fn uniform_field_gives_a_parabola() {
+    // A charged sheet below: E = σ/2ε₀ upwards, chosen so a negative particle falls at 0.5 m/s²
+    let epsilon_0 = 1.0 / (4.0 * std::f64::consts::PI * K);
+    let sheet = vec![Distribution::Plane { point: [0.0, -3.0, 0.0], normal: [0.0, 1.0, 0.0], sigma: 2.0 * epsilon_0 }];
+    let particle = Particle { charge: -0.5, mass: 1.0, position: [-2.0, 0.0, 0.0], velocity: [1.0, 0.0, 0.0] };
+    let settings = TrajectorySettings { duration: 2.0, ..TrajectorySettings::default() };
+    let path = trace_particle(&particle, &sheet, &[], &settings);
+    assert_eq!(path.end, TrajectoryEnd::TimeLimit);
+    assert_eq!(*path.times.last().unwrap(), 2.0);
+    let (x, y, _) = *path.points.last().unwrap();
+    assert!(x.abs() < 1e-5 && (y + 1.0).abs() < 1e-5, "{x}, {y}");
+    // Parabola all along: y = −(x + 2)²/4
+    for (x, y, _) in &path.points {
+        assert!((y + (x + 2.0).powi(2) / 4.0).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn magnetic_field_bends_a_particle_into_a_circle() {
+    // Inside a long solenoid B = μ₀nI; the orbit has radius mv/qB and period 2πm/qB
+    let coil = [Current::Solenoid { center: [0.0; 3], axis: [0.0, 0.0, 1.0], radius: 1.0, length: 8.0, turns: 800, current: 1.5 }];
+    let b = magnetic_field(&coil, &(0.0, 0.0, 0.0)).magnitude();
+    let (q, m, v) = (1e-3, 1e-7, 0.3);
+    let (radius, period) = (m * v / (q * b), 2.0 * std::f64::consts::PI * m / (q * b));
+    let particle = Particle { charge: q, mass: m, position: [radius, 0.0, 0.0], velocity: [0.0, -v, 0.0] };
+    let settings = TrajectorySettings { duration: period, ..TrajectorySettings::default() };
+    let path = trace_particle(&particle, &Vec::<Charge>::new(), &coil, &settings);
+    assert_eq!(path.end, TrajectoryEnd::TimeLimit);
+    // Centred on the axis (the solenoid field points along +z, a positive charge turns clockwise)
+    for (x, y, _) in &path.points {
+        let r = ((x * x + y * y) as f64).sqrt();
+        assert!((r - radius).abs() < 2e-3 * radius, "{r} vs {radius}");
+    }
+    let (x, y, _) = *path.points.last().unwrap();
+    assert!((x as f64 - radius).abs() < 5e-3 * radius && (y as f64).abs() < 5e-3 * radius);
+    // The magnetic force does no work
+    let speed = path.velocities.last().unwrap().magnitude();
+    assert!((speed - v).abs() < 1e-6 * v);
+}
+
+#[test]
+fn particle_released_near_an_opposite_charge_hits_it() {
+    let charges = vec![Charge::new(-1e-6, (0.0, 0.0, 0.0))];
+    let particle = Particle { charge: 1e-6, mass: 1e-3, position: [1.5, 0.5, 0.0], velocity: [0.0; 3] };
+    let path = trace_particle(&particle, &charges, &[], &TrajectorySettings::default());
+    assert_eq!(path.end, TrajectoryEnd::Collided);
+    let (x, y, z) = *path.points.last().unwrap();
+    assert!((x * x + y * y + z * z).sqrt() < 0.2);
+}
+
+#[test]
+fn particles_round_trip_through_a_scene_file() {
+    let scene = scene::Scene {
+        particles: vec![Particle { charge: -1.6e-19, mass: 9.1e-31, position: [-3.0, 0.0, 0.0], velocity: [1e6, 0.0, 0.0] }],
+        trajectories: TrajectorySettings { duration: 1e-5, ..TrajectorySettings::default() },
+        ..scene::Scene::default()
+    };
+    let text = scene.to_toml().unwrap();
+    assert_eq!(scene::Scene::from_toml(&text).unwrap(), scene);
+}
+
+/// A uniform field that is undefined beyond x = 1, counting how often it is evaluated.
+struct Cliff {
+    evaluations: std::cell::Cell<usize>,
+}
+
+impl FieldSource for Cliff {
+    fn potential(&self, _s: &(f32, f32, f32)) -> f64 {
+        0.0
+    }
+    fn field(&self, s: &(f32, f32, f32)) -> Vector3D {
+        self.evaluations.set(self.evaluations.get() + 1);
+        let e = if s.0 > 1.0 { f64::NAN } else { 1.0 };
+        Vector3D::new(e, 0.0, 0.0)
+    }
+    fn absorbs(&self, _s: &(f32, f32, f32)) -> bool {
+        false
+    }
+    fn extent(&self) -> f32 {
+        1.0
+    }
+}
+
+#[test]
+fn a_singular_point_ends_the_trajectory_quickly() {
+    let cliff = Cliff { evaluations: std::cell::Cell::new(0) };
+    let particle = Particle { charge: 1.0, mass: 1.0, position: [0.0; 3], velocity: [1.0, 0.0, 0.0] };
+    let path = trace_particle(&particle, &cliff, &[], &TrajectorySettings::default());
+    assert_eq!(path.end, TrajectoryEnd::StepLimit);
+    let (x, _, _) = *path.points.last().unwrap();
+    assert!(x > 0.9 && x <= 1.0, "{x}");
+    // The step collapses after a few dozen tries instead of using up all 20 000 steps
+    assert!(cliff.evaluations.get() < 5_000, "{}", cliff.evaluations.get());
+}
@@ -0,0 +1,61 @@
+use coulomb3d::vtk;
+use coulomb3d::*;
+
+/// The lines following the first one that starts with `keyword`, and that line's fields.
+fn section<'a>(text: &'a str, keyword: &str) -> (Vec<&'a str>, Vec<&'a str>) {
+    let mut lines = text.lines().skip_while(|l| !l.starts_with(keyword));
+    let head = lines.next().unwrap_or_else(|| panic!("no {keyword} section")).split_whitespace().collect();
+    (head, lines.collect())
+}
+
+#[test]
+fn field_lines_file_lists_every_point_once() {
+    let charges = vec![Charge::new(1e-6, (0.0, 0.0, 0.0))];
+    let lines: Vec<Polyline> = vec![
+        vec![(0.1, 0.0, 0.0), (0.5, 0.0, 0.0), (1.0, 0.0, 0.0)],
+        vec![(0.0, 0.1, 0.0), (0.0, 1.0, 0.0)],
+        vec![(0.0, 0.0, 0.1)], // too short to draw, left out
+    ];
+    let scalars: Vec<_> = lines.iter().map(|l| LineScalars::sample(&charges, l)).collect();
+    let text = vtk::field_lines(&lines, &scalars);
+
+    assert!(text.starts_with("# vtk DataFile Version 3.0\nCoulomb3D field lines\nASCII\nDATASET POLYDATA\n"));
+    let (points, body) = section(&text, "POINTS");
+    assert_eq!(points, ["POINTS", "5", "double"]);
+    assert_eq!(body[0], "0.1 0 0");
+
+    // n cells, and n + (number of point ids) integers in the connectivity list
+    let (cells, body) = section(&text, "LINES");
+    assert_eq!(cells, ["LINES", "2", "7"]);
+    assert_eq!(body[..2], ["3 0 1 2", "2 3 4"]);
+
+    let (data, body) = section(&text, "POINT_DATA");
+    assert_eq!(data, ["POINT_DATA", "5"]);
+    assert_eq!(body.iter().filter(|l| l.starts_with("SCALARS")).count(), 3);
+    let (_, potential) = section(&text, "SCALARS potential");
+    let phi: f64 = potential[1].parse().unwrap();
+    assert!((phi - K * 1e-6 / 0.1).abs() < 1e-4 * phi);
+}
+
+#[test]
+fn sampled_fields_has_a_value_per_lattice_point() {
+    let charges = vec![Charge::new(1e-6, (0.0, 0.0, 0.0))];
+    let n = 5;
+    let text = vtk::sampled_fields(&charges, 2.0, n);
+
+    assert!(text.starts_with("# vtk DataFile Version 3.0\n"));
+    assert!(text.contains("DATASET STRUCTURED_POINTS\nDIMENSIONS 5 5 5\nORIGIN -2 -2 -2\nSPACING 1 1 1\n"));
+    let (data, _) = section(&text, "POINT_DATA");
+    assert_eq!(data, ["POINT_DATA", "125"]);
+
+    let (_, potential) = section(&text, "SCALARS potential");
+    let values: Vec<f64> = potential[1..].iter().take_while(|l| !l.starts_with("VECTORS")).map(|l| l.parse().unwrap()).collect();
+    assert_eq!(values.len(), n * n * n);
+    // The charge sits on the centre lattice point, written as 0 rather than infinity
+    assert_eq!(values[62], 0.0);
+    assert!((values[0] - K * 1e-6 / 12f64.sqrt()).abs() < 1e-9 * values[0]);
+
+    let (_, vectors) = section(&text, "VECTORS field");
+    assert_eq!(vectors.len(), n * n * n);
+    assert!(vectors.iter().all(|l| l.split_whitespace().count() == 3));
+}
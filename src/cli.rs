@@ -0,0 +1,434 @@
+/*
+Headless command-line mode. `Coulomb3D eval <scene.toml>` loads a scene, evaluates the same
+potential, field and multipole values the "Calculations & Properties" panel shows, and prints
+them as text or JSON without opening a window; `Coulomb3D export` writes a scene's field lines,
+charges and sampled fields as VTK files for ParaView. Running the binary without a subcommand starts
+the interactive app as usual, optionally with a scene already loaded (`--scene`).
+*/
+use coulomb3d::scene::{Scene, SceneSources};
+use coulomb3d::vtk;
+use coulomb3d::*;
+use serde_json::json;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage:
+  Coulomb3D [--scene <scene.toml>]          start the interactive app
+  Coulomb3D eval <scene.toml> [options]      evaluate a scene without a window
+  Coulomb3D export <scene.toml> [options]    write VTK files for ParaView
+
+Options for eval:
+  --at x,y,z          evaluation point r' (may be repeated, default: the scene's r')
+  --format text|json  output format (default text)
+
+Options for export:
+  --out <prefix>      output prefix (default: the scene path without `.toml`);
+                      writes <prefix>_field_lines.vtk, _charges.vtk and _fields.vtk
+  --resolution <n>    grid points per edge for the sampled fields (default 40)";
+
+#[derive(Debug, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug)]
+pub struct EvalArgs {
+    pub scene: PathBuf,
+    pub points: Vec<(f32, f32, f32)>,
+    pub format: Format,
+}
+
+#[derive(Debug)]
+pub struct ExportArgs {
+    pub scene: PathBuf,
+    pub prefix: Option<PathBuf>,
+    pub resolution: usize,
+}
+
+#[derive(Debug)]
+pub enum Command {
+    /// Start the interactive app, loading `scene` if given.
+    Gui { scene: Option<PathBuf> },
+    Eval(EvalArgs),
+    Export(ExportArgs),
+    Help,
+}
+
+/// Options that are followed by a value, in any subcommand.
+const VALUED_OPTIONS: [&str; 5] = ["--scene", "--at", "--format", "--out", "--resolution"];
+
+/// Parse the command line (without the program name). Options may come before or after
+/// the subcommand and the scene file.
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    // The subcommand is the first argument that is neither an option nor an option's value
+    let mut at = 0;
+    while at < args.len() && args[at].starts_with('-') {
+        at += if VALUED_OPTIONS.contains(&args[at].as_str()) { 2 } else { 1 };
+    }
+    let Some(cmd) = args.get(at) else {
+        return parse_gui(args);
+    };
+    let rest: Vec<String> = args[..at].iter().chain(&args[at + 1..]).cloned().collect();
+    match cmd.as_str() {
+        "eval" => parse_eval(&rest).map(Command::Eval),
+        "export" => parse_export(&rest).map(Command::Export),
+        "help" => Ok(Command::Help),
+        other => Err(format!("Unknown command `{other}`")),
+    }
+}
+
+fn parse_gui(args: &[String]) -> Result<Command, String> {
+    let mut scene = None;
+
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--scene" => {
+                let path = it.next().ok_or("--scene takes exactly one scene file")?;
+                if scene.replace(PathBuf::from(path)).is_some() {
+                    return Err(String::from("--scene takes exactly one scene file"));
+                }
+            }
+            other => return Err(format!("Unknown option `{other}`")),
+        }
+    }
+    Ok(Command::Gui { scene })
+}
+
+fn parse_eval(args: &[String]) -> Result<EvalArgs, String> {
+    let mut scene = None;
+    let mut points = Vec::new();
+    let mut format = Format::Text;
+
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--at" => {
+                let v = it.next().ok_or("--at needs a point, e.g. --at 0,0,0")?;
+                points.push(string_to_tuple(v).map_err(|e| format!("--at {v}: {e}"))?);
+            }
+            "--format" => {
+                format = match it.next().map(String::as_str) {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    _ => return Err(String::from("--format must be `text` or `json`")),
+                };
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown option `{flag}`")),
+            path => {
+                if scene.replace(PathBuf::from(path)).is_some() {
+                    return Err(String::from("eval takes exactly one scene file"));
+                }
+            }
+        }
+    }
+
+    let scene = scene.ok_or("eval needs a scene file")?;
+    Ok(EvalArgs { scene, points, format })
+}
+
+fn parse_export(args: &[String]) -> Result<ExportArgs, String> {
+    let mut scene = None;
+    let mut prefix = None;
+    let mut resolution = 40;
+
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--out" => prefix = Some(PathBuf::from(it.next().ok_or("--out needs an output prefix")?)),
+            "--resolution" => {
+                resolution = it
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .filter(|n| (2..=256).contains(n))
+                    .ok_or("--resolution must be a number between 2 and 256")?;
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown option `{flag}`")),
+            path => {
+                if scene.replace(PathBuf::from(path)).is_some() {
+                    return Err(String::from("export takes exactly one scene file"));
+                }
+            }
+        }
+    }
+
+    let scene = scene.ok_or("export needs a scene file")?;
+    Ok(ExportArgs { scene, prefix, resolution })
+}
+
+/// Run a headless command, returning the process exit code.
+pub fn run(cmd: Command) -> i32 {
+    match cmd {
+        Command::Gui { .. } => unreachable!("the GUI is started from main"),
+        Command::Help => {
+            println!("{USAGE}");
+            0
+        }
+        Command::Eval(args) => match eval(&args) {
+            Ok(out) => {
+                println!("{out}");
+                0
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                1
+            }
+        },
+        Command::Export(args) => match export(&args) {
+            Ok((paths, summary)) => {
+                println!("traced {summary}");
+                for p in paths {
+                    println!("wrote {}", p.display());
+                }
+                0
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                1
+            }
+        },
+    }
+}
+
+/// Field lines are traced with the scene's own settings; the sampled fields
+/// cover the same bounding cube the GUI draws.
+fn export(args: &ExportArgs) -> Result<(Vec<PathBuf>, String), String> {
+    let scene = Scene::load(&args.scene).map_err(|e| e.to_string())?;
+    let sources = scene.sources();
+    let charges = &sources.charges;
+    let settings = &scene.field_lines.settings;
+
+    let mut extra = distribution_seeds(&scene.distributions, settings.seeds);
+    extra.extend(user_seeds(&scene.seeds));
+    let (lines, stats, _) = generate_charge_field_lines(&sources, charges, &extra, settings);
+    let bounds = bounding_half_size(&sources) as f64;
+
+    let prefix = args.prefix.clone().unwrap_or_else(|| args.scene.with_extension(""));
+    let paths = vtk::export(prefix, charges, &sources, &lines, bounds, args.resolution).map_err(|e| e.to_string())?;
+    Ok((paths, line_stats_summary(&stats)))
+}
+
+fn eval(args: &EvalArgs) -> Result<String, String> {
+    let scene = Scene::load(&args.scene).map_err(|e| e.to_string())?;
+    let sources = scene.sources();
+    let charges = &sources.charges;
+    let points = if args.points.is_empty() { vec![scene.reference()] } else { args.points.clone() };
+    let empty = sources.is_empty();
+    let trajectories: Vec<_> = scene
+        .particles
+        .iter()
+        .map(|p| trace_particle(p, &sources, &scene.currents, &scene.trajectories))
+        .collect();
+
+    Ok(match args.format {
+        Format::Text => {
+            let mut blocks: Vec<_> = points
+                .iter()
+                .map(|at| {
+                    let report = if empty { format!("r' = {at:?}\nNot Calculated (no charges)") }
+                                 else { text_report(charges, &sources, &scene.dielectrics, at) };
+                    if scene.currents.is_empty() { report } else { format!("{report}\n{}", text_magnetic(&scene.currents, at)) }
+                })
+                .collect();
+            if !charges.is_empty() {
+                blocks.push(text_configuration(&sources));
+            }
+            if !scene.currents.is_empty() {
+                let (mx, my, mz) = magnetic_dipole_moment(&scene.currents).components();
+                blocks.push(format!("Currents\nm   = ({mx:.3e}, {my:.3e}, {mz:.3e}) A·m² (about the origin)"));
+            }
+            if !trajectories.is_empty() {
+                blocks.push(text_trajectories(&trajectories));
+            }
+            blocks.join("\n\n")
+        }
+        Format::Json => {
+            let reports: Vec<_> = points
+                .iter()
+                .map(|at| {
+                    let mut report = if empty { json!({ "at": [at.0, at.1, at.2] }) }
+                                     else { json_report(charges, &sources, &scene.dielectrics, at) };
+                    if !scene.currents.is_empty() {
+                        let b = magnetic_field(&scene.currents, at);
+                        let (bx, by, bz) = b.components();
+                        report["magnetic_field"] = json!([bx, by, bz]);
+                        report["magnetic_field_magnitude"] = json!(b.magnitude());
+                    }
+                    report
+                })
+                .collect();
+            let (mx, my, mz) = magnetic_dipole_moment(&scene.currents).components();
+            serde_json::to_string_pretty(&json!({
+                "scene": args.scene.display().to_string(),
+                "charges": charges.len(),
+                "distributions": scene.distributions.len(),
+                "conductors": scene.conductors.len(),
+                "dielectrics": scene.dielectrics.len(),
+                "currents": scene.currents.len(),
+                "magnetic_dipole_moment": [mx, my, mz],
+                "trajectories": trajectories.iter().map(json_trajectory).collect::<Vec<_>>(),
+                "configuration": json_configuration(&sources),
+                "results": reports,
+            }))
+            .map_err(|e| e.to_string())?
+        }
+    })
+}
+
+/// Properties of the configuration as a whole (forces, energy), independent of r'.
+fn text_configuration(sources: &SceneSources) -> String {
+    let forces = sources
+        .forces()
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let (fx, fy, fz) = f.components();
+            format!("F{}  = ({:.3e}, {:.3e}, {:.3e}) N  |F| = {:.4e} N", i + 1, fx, fy, fz, f.magnitude())
+        })
+        .collect::<Vec<_>>();
+    let pe = potential_energy(&sources.charges);
+    let pairs = pe
+        .pairs
+        .iter()
+        .map(|p| format!("U{},{} = {:.4e} J", p.i + 1, p.j + 1, p.energy))
+        .collect::<Vec<_>>();
+    format!(
+        "Forces on charges\n{}\n\nPotential energy\nU   = {:.4e} J\n{}",
+        forces.join("\n"),
+        pe.total,
+        pairs.join("\n")
+    )
+}
+
+fn json_configuration(sources: &SceneSources) -> serde_json::Value {
+    let forces: Vec<_> = sources
+        .forces()
+        .iter()
+        .map(|f| {
+            let (fx, fy, fz) = f.components();
+            json!({ "force": [fx, fy, fz], "magnitude": f.magnitude() })
+        })
+        .collect();
+    let pe = potential_energy(&sources.charges);
+    let pairs: Vec<_> = pe
+        .pairs
+        .iter()
+        .map(|p| json!({ "i": p.i, "j": p.j, "energy": p.energy }))
+        .collect();
+    json!({
+        "forces": forces,
+        "potential_energy": { "total": pe.total, "pairs": pairs },
+    })
+}
+
+/// Values at r'. Multipoles cover the point charges; φ and E include every source. With
+/// dielectrics in the scene D, P and εᵣ at r' follow.
+fn text_report(charges: &[Charge], sources: &dyn FieldSource, dielectrics: &[Dielectric], at: &(f32, f32, f32)) -> String {
+    let mm  = multipole_moments(charges, at);
+    let e   = sources.field(at);
+    let phi = sources.potential(at);
+    let (px, py, pz) = mm.dipole.components();
+    let (ex, ey, ez) = e.components();
+    let q = &mm.quadrupole;
+
+    let report = format!(
+        "r'  = {at:?}\n\
+         Q   = {:.4e} C\n\
+         p   = ({:.3e}, {:.3e}, {:.3e}) C·m\n\
+         |p| = {:.4e} C·m\n\
+         Qxx = {:.4e}  Qyy = {:.4e}  Qzz = {:.4e} C·m²\n\
+         Qxy = {:.4e}  Qxz = {:.4e}  Qyz = {:.4e} C·m²\n\
+         Phi = {:.4e} V\n\
+         E   = ({:.3e}, {:.3e}, {:.3e}) N/C\n\
+         |E| = {:.4e} N/C",
+        mm.monopole,
+        px, py, pz, mm.dipole.magnitude(),
+        q[0][0], q[1][1], q[2][2],
+        q[0][1], q[0][2], q[1][2],
+        phi,
+        ex, ey, ez, e.magnitude(),
+    );
+    if dielectrics.is_empty() {
+        return report;
+    }
+    let m = material_fields(sources, dielectrics, at);
+    let (dx, dy, dz) = m.d.components();
+    let (polx, poly, polz) = m.p.components();
+    format!(
+        "{report}\n\
+         eps = {:.4}\n\
+         D   = ({:.3e}, {:.3e}, {:.3e}) C/m²\n\
+         P   = ({:.3e}, {:.3e}, {:.3e}) C/m²",
+        m.epsilon_r,
+        dx, dy, dz,
+        polx, poly, polz,
+    )
+}
+
+/// Where and why each test particle stopped.
+fn text_trajectories(trajectories: &[Trajectory]) -> String {
+    let lines: Vec<_> = trajectories
+        .iter()
+        .enumerate()
+        .map(|(i, tr)| {
+            let (x, y, z) = *tr.points.last().unwrap();
+            format!(
+                "P{}  {} after t = {:.4e} s at ({x:.3}, {y:.3}, {z:.3}) m, |v| = {:.4e} m/s",
+                i + 1,
+                tr.end.describe(),
+                tr.times.last().unwrap(),
+                tr.velocities.last().unwrap().magnitude(),
+            )
+        })
+        .collect();
+    format!("Trajectories\n{}", lines.join("\n"))
+}
+
+fn json_trajectory(tr: &Trajectory) -> serde_json::Value {
+    let (x, y, z) = *tr.points.last().unwrap();
+    let (vx, vy, vz) = tr.velocities.last().unwrap().components();
+    json!({
+        "end": format!("{:?}", tr.end),
+        "time": tr.times.last().unwrap(),
+        "position": [x, y, z],
+        "velocity": [vx, vy, vz],
+        "steps": tr.points.len() - 1,
+    })
+}
+
+/// Magnetic field of the currents at r'.
+fn text_magnetic(currents: &[Current], at: &(f32, f32, f32)) -> String {
+    let b = magnetic_field(currents, at);
+    let (bx, by, bz) = b.components();
+    format!("B   = ({bx:.3e}, {by:.3e}, {bz:.3e}) T\n|B| = {:.4e} T", b.magnitude())
+}
+
+fn json_report(charges: &[Charge], sources: &dyn FieldSource, dielectrics: &[Dielectric], at: &(f32, f32, f32)) -> serde_json::Value {
+    let point = [at.0, at.1, at.2];
+    let mm = multipole_moments(charges, at);
+    let e  = sources.field(at);
+    let (px, py, pz) = mm.dipole.components();
+    let (ex, ey, ez) = e.components();
+
+    let mut report = json!({
+        "at": point,
+        "monopole": mm.monopole,
+        "dipole": [px, py, pz],
+        "dipole_magnitude": mm.dipole.magnitude(),
+        "quadrupole": mm.quadrupole,
+        "potential": sources.potential(at),
+        "field": [ex, ey, ez],
+        "field_magnitude": e.magnitude(),
+    });
+    if !dielectrics.is_empty() {
+        let m = material_fields(sources, dielectrics, at);
+        let (dx, dy, dz) = m.d.components();
+        let (polx, poly, polz) = m.p.components();
+        report["epsilon_r"] = json!(m.epsilon_r);
+        report["displacement"] = json!([dx, dy, dz]);
+        report["polarization"] = json!([polx, poly, polz]);
+    }
+    report
+}
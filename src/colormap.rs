@@ -0,0 +1,25 @@
+/*
+Colour bars for the colour maps of the `coulomb3d` library, drawn next to slice heatmaps and
+coloured field lines.
+*/
+use coulomb3d::ColorScale;
+use macroquad::prelude::*;
+
+/// Draw a vertical colour bar in screen space with `title` above it and
+/// the values at the bottom, middle and top written to its right.
+pub fn draw_color_bar(x: f32, y: f32, w: f32, h: f32, scale: &ColorScale, title: &str) {
+    const BANDS: usize = 64;
+    let band = h / BANDS as f32;
+    for k in 0..BANDS {
+        let t = (k as f32 + 0.5) / BANDS as f32;
+        // t = 0 at the bottom
+        draw_rectangle(x, y + h - (k + 1) as f32 * band, w, band + 0.5, Color::from(scale.colormap.color(t)));
+    }
+    draw_rectangle_lines(x, y, w, h, 1.0, DARKGRAY);
+    draw_text(title, x, y - 8.0, 16.0, BLACK);
+    for t in [0.0f32, 0.5, 1.0] {
+        let ty = y + h * (1.0 - t);
+        draw_line(x + w, ty, x + w + 4.0, ty, 1.0, DARKGRAY);
+        draw_text(&format!("{:.2e}", scale.value_at(t)), x + w + 6.0, ty + 4.0, 14.0, BLACK);
+    }
+}
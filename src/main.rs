@@ -1,23 +1,47 @@
 /*
-In this file, all front-ends and back-ends come together to form the App. For math and algebra
-you need to check the algebra.rs file and all physical calculations are in physics.rs. All those
-workers are placed as a mod to this file, and are indeed included in math/mod.rs, and therefore
-they are added here.
+In this file, all front-ends and back-ends come together to form the App. The physics itself
+lives in the `coulomb3d` library crate (see coulomb3d/src/math): algebra.rs holds the vector
+algebra and physics.rs all physical calculations. Render-only helpers, such as the colour
+wrapper around each charge, are in render.rs, and the headless command-line mode is in cli.rs.
 */
+use coulomb3d::scene::{widen, Scene, SceneSources, MAX_GRID_RESOLUTION};
+use coulomb3d::vtk;
+use coulomb3d::*;
 use macroquad::prelude::*;
 use macroquad::ui::{hash, root_ui, Skin};
-mod math;
-use math::*;
+mod cli;
+mod colormap;
+mod render;
+use colormap::*;
+use render::*;
 
 // Global Variables
 const WINDOW: (i32, i32) = (1280, 720);
-
-#[derive(Debug)]
-struct Charge {
-    charge: f64,
-    position: (f32, f32, f32),
-    color: (f32, f32, f32, f32),
-}
+// Kinds offered by the "add source" form with the meaning of its A / B / size / density inputs
+const DISTRIBUTION_KINDS: [&str; 6] = ["Line", "Ring", "Disk", "Sphere", "Plane", "Slab"];
+const DISTRIBUTION_HELP: [&str; 6] = [
+    "A = start, B = end, density = λ [C/m]",
+    "A = centre, B = normal, size = radius, density = Q [C]",
+    "A = centre, B = normal, size = radius, density = σ [C/m²]",
+    "A = centre, size = radius, density = Q [C]",
+    "A = point, B = normal, density = σ [C/m²]",
+    "A = point, B = normal, size = thickness, density = ρ [C/m³]",
+];
+// Electrodes offered by the grid solver form: A = centre / lower corner, B = radius / upper corner
+const ELECTRODE_KINDS: [&str; 2] = ["Sphere", "Box"];
+// Conductors offered by the "add conductor" form
+const CONDUCTOR_KINDS: [&str; 3] = ["Grounded plane", "Grounded sphere", "Isolated sphere"];
+// Dielectrics offered by the "add dielectric" form: A = centre / lower corner / point,
+// B = radius / upper corner / outward normal
+const DIELECTRIC_KINDS: [&str; 3] = ["Sphere", "Box", "Half-space"];
+// Currents offered by the "add current" form: A = start / centre, B = end / normal / axis
+const CURRENT_KINDS: [&str; 3] = ["Segment", "Loop", "Solenoid"];
+// User seeds offered by the "add seeds" form: A = position / centre, B = normal; size is
+// "w,h" (grid) or the radius (circle), count "rows,columns" (grid) or the number of points
+const SEED_KINDS: [&str; 3] = ["Point", "Grid", "Circle"];
+const MAX_TURNS:     usize = 2000; // solenoid turns accepted by the form
+const SIM_SUBSTEPS:  usize = 10;   // integrator steps per frame while playing
+const SIM_SOFTENING: f64   = 0.05; // softening length [m] keeping close encounters finite
 
 // ── Error dialog ─────────────────────────────────────────────────────────────
 
@@ -73,24 +97,329 @@ impl ErrorDialog {
     }
 }
 
-// ── Bounding cube wireframe ───────────────────────────────────────────────────
-
-/// Draw the 12 edges of an axis-aligned cube centred at the origin with half-size `b`.
-fn draw_bounds_cube(b: f32, col: Color) {
-    let c = [
-        vec3(-b, -b, -b), vec3( b, -b, -b), vec3( b,  b, -b), vec3(-b,  b, -b),
-        vec3(-b, -b,  b), vec3( b, -b,  b), vec3( b,  b,  b), vec3(-b,  b,  b),
-    ];
-    let edges: [(usize, usize); 12] = [
-        (0,1),(1,2),(2,3),(3,0), // bottom face
-        (4,5),(5,6),(6,7),(7,4), // top face
-        (0,4),(1,5),(2,6),(3,7), // verticals
-    ];
-    for (i, j) in edges {
-        draw_line_3d(c[i], c[j], col);
+// ── Continuous sources form ───────────────────────────────────────────────────
+
+/// Build a distribution from the "add source" form; the inputs are read as listed
+/// in DISTRIBUTION_HELP for the chosen kind.
+fn parse_distribution(
+    kind: usize,
+    a: &str,
+    b: &str,
+    size: &str,
+    density: &str,
+    solid: bool,
+) -> Result<Distribution, String> {
+    let point = |s: &str, name: &str| {
+        string_to_tuple(s)
+            .map(|(x, y, z)| [x as f64, y as f64, z as f64])
+            .map_err(|e| format!("{name}: {e}"))
+    };
+    let a = point(a, "A")?;
+    let b = point(b, "B")?;
+    let density = density
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|d| d.is_finite())
+        .ok_or("Density is not a number")?;
+    let size = size
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|s| s.is_finite() && *s > 0.0)
+        .ok_or("Size must be a positive number")?;
+    let needs_normal = !matches!(DISTRIBUTION_KINDS[kind], "Line" | "Sphere");
+    if needs_normal && b == [0.0; 3] {
+        return Err(String::from("B (normal) must not be the zero vector"));
+    }
+
+    Ok(match DISTRIBUTION_KINDS[kind] {
+        "Line" => {
+            if a == b {
+                return Err(String::from("Line start and end must differ"));
+            }
+            Distribution::Line { start: a, end: b, lambda: density }
+        }
+        "Ring"   => Distribution::Ring { center: a, normal: b, radius: size, charge: density },
+        "Disk"   => Distribution::Disk { center: a, normal: b, radius: size, sigma: density },
+        "Sphere" => Distribution::Sphere { center: a, radius: size, charge: density, solid },
+        "Plane"  => Distribution::Plane { point: a, normal: b, sigma: density },
+        _        => Distribution::Slab { point: a, normal: b, thickness: size, rho: density },
+    })
+}
+
+/// Build a conductor from the "add conductor" form: a plane through `point` with
+/// `normal`, or a sphere centred on `point` with `radius` (and `charge` when isolated).
+fn parse_conductor(kind: usize, point: &str, normal: &str, radius: &str, charge: &str) -> Result<Conductor, String> {
+    let vector = |s: &str, name: &str| {
+        string_to_tuple(s)
+            .map(|(x, y, z)| [x as f64, y as f64, z as f64])
+            .map_err(|e| format!("{name}: {e}"))
+    };
+    let point = vector(point, "Point")?;
+    if kind == 0 {
+        let normal = vector(normal, "Normal")?;
+        if normal == [0.0; 3] {
+            return Err(String::from("Normal must not be the zero vector"));
+        }
+        return Ok(Conductor::Plane { point, normal });
+    }
+    let radius = radius
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|r| r.is_finite() && *r > 0.0)
+        .ok_or("Radius must be a positive number")?;
+    let grounded = kind == 1;
+    let charge = if grounded {
+        0.0
+    } else {
+        charge.trim().parse::<f64>().ok().filter(|q| q.is_finite()).ok_or("Charge is not a number")?
+    };
+    Ok(Conductor::Sphere { center: point, radius, grounded, charge })
+}
+
+/// Build an electrode from the grid solver form; see ELECTRODE_KINDS.
+fn parse_electrode(kind: usize, a: &str, b: &str, potential: &str) -> Result<Electrode, String> {
+    let vector = |s: &str, name: &str| {
+        string_to_tuple(s)
+            .map(|(x, y, z)| [x as f64, y as f64, z as f64])
+            .map_err(|e| format!("{name}: {e}"))
+    };
+    let potential = potential
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or("Potential is not a number")?;
+    let a = vector(a, "A")?;
+    if kind == 0 {
+        let radius = b
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|r| r.is_finite() && *r > 0.0)
+            .ok_or("B (radius) must be a positive number")?;
+        return Ok(Electrode::Sphere { center: a, radius, potential });
+    }
+    let b = vector(b, "B")?;
+    if (0..3).any(|i| a[i] >= b[i]) {
+        return Err(String::from("A must be the lower and B the upper corner of the box"));
+    }
+    Ok(Electrode::Box { min: a, max: b, potential })
+}
+
+/// Build a dielectric from the "add dielectric" form; see DIELECTRIC_KINDS.
+fn parse_dielectric(kind: usize, a: &str, b: &str, epsilon_r: &str) -> Result<Dielectric, String> {
+    let vector = |s: &str, name: &str| {
+        string_to_tuple(s)
+            .map(|(x, y, z)| [x as f64, y as f64, z as f64])
+            .map_err(|e| format!("{name}: {e}"))
+    };
+    let epsilon_r = epsilon_r
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|e| e.is_finite() && *e >= 1.0)
+        .ok_or("Relative permittivity must be a number of at least 1")?;
+    let a = vector(a, "A")?;
+    match kind {
+        0 => {
+            let radius = b
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|r| r.is_finite() && *r > 0.0)
+                .ok_or("B (radius) must be a positive number")?;
+            Ok(Dielectric::Sphere { center: a, radius, epsilon_r })
+        }
+        1 => {
+            let b = vector(b, "B")?;
+            if (0..3).any(|i| a[i] >= b[i]) {
+                return Err(String::from("A must be the lower and B the upper corner of the box"));
+            }
+            Ok(Dielectric::Box { min: a, max: b, epsilon_r })
+        }
+        _ => {
+            let normal = vector(b, "B")?;
+            if normal == [0.0; 3] {
+                return Err(String::from("Normal must not be the zero vector"));
+            }
+            Ok(Dielectric::HalfSpace { point: a, normal, epsilon_r })
+        }
+    }
+}
+
+/// Build a current element from the "add current" form; see CURRENT_KINDS. `radius` is
+/// ignored for segments, `length` and `turns` are only read for solenoids.
+fn parse_current(kind: usize, a: &str, b: &str, radius: &str, length: &str, turns: &str, current: &str) -> Result<Current, String> {
+    let vector = |s: &str, name: &str| {
+        string_to_tuple(s)
+            .map(|(x, y, z)| [x as f64, y as f64, z as f64])
+            .map_err(|e| format!("{name}: {e}"))
+    };
+    let positive = |s: &str, name: &str| {
+        s.trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|x| x.is_finite() && *x > 0.0)
+            .ok_or(format!("{name} must be a positive number"))
+    };
+    let current = current
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|i| i.is_finite())
+        .ok_or("Current is not a number")?;
+    let a = vector(a, "A")?;
+    let b = vector(b, "B")?;
+    if kind == 0 {
+        if a == b {
+            return Err(String::from("A segment needs distinct start and end points"));
+        }
+        return Ok(Current::Segment { start: a, end: b, current });
+    }
+    if b == [0.0; 3] {
+        return Err(String::from("B (direction) must not be the zero vector"));
+    }
+    let radius = positive(radius, "Radius")?;
+    if kind == 1 {
+        return Ok(Current::Loop { center: a, normal: b, radius, current });
+    }
+    let length = positive(length, "Length")?;
+    let turns = turns
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|n| (1..=MAX_TURNS).contains(n))
+        .ok_or(format!("Turns must be a whole number between 1 and {MAX_TURNS}"))?;
+    Ok(Current::Solenoid { center: a, axis: b, radius, length, turns, current })
+}
+
+/// Build a test particle from the "launch particle" form.
+fn parse_particle(charge: &str, mass: &str, position: &str, velocity: &str) -> Result<Particle, String> {
+    let vector = |s: &str, name: &str| {
+        string_to_tuple(s)
+            .map(|(x, y, z)| [x as f64, y as f64, z as f64])
+            .map_err(|e| format!("{name}: {e}"))
+    };
+    let charge = charge.trim().parse::<f64>().ok().filter(|q| q.is_finite()).ok_or("Charge is not a number")?;
+    let mass = mass
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|m| m.is_finite() && *m > 0.0)
+        .ok_or("Mass must be a positive number")?;
+    Ok(Particle { charge, mass, position: vector(position, "Position")?, velocity: vector(velocity, "Velocity")? })
+}
+
+/// Build a set of field line seeds from the "add seeds" form; see SEED_KINDS.
+fn parse_seed_shape(kind: usize, a: &str, b: &str, size: &str, count: &str) -> Result<SeedShape, String> {
+    let vector = |s: &str, name: &str| {
+        string_to_tuple(s)
+            .map(|(x, y, z)| [x as f64, y as f64, z as f64])
+            .map_err(|e| format!("{name}: {e}"))
+    };
+    let sizes = size
+        .split(',')
+        .map(|x| x.trim().parse::<f64>().ok().filter(|x| x.is_finite() && *x > 0.0))
+        .collect::<Option<Vec<f64>>>()
+        .ok_or("Size must be positive numbers")?;
+    let counts = count
+        .split(',')
+        .map(|x| x.trim().parse::<usize>().ok().filter(|n| *n > 0))
+        .collect::<Option<Vec<usize>>>()
+        .ok_or("Count must be positive whole numbers")?;
+    let center = vector(a, "A")?;
+    if kind == 0 {
+        return Ok(SeedShape::Point { position: center });
+    }
+    let normal = vector(b, "B")?;
+    if normal == [0.0; 3] {
+        return Err(String::from("B (normal) must not be the zero vector"));
+    }
+    let shape = match (kind, sizes.as_slice(), counts.as_slice()) {
+        (1, &[w, h], &[rows, cols]) => SeedShape::Grid { center, normal, size: [w, h], count: [rows, cols] },
+        (1, ..) => return Err(String::from("A grid needs a size \"w,h\" and a count \"rows,columns\"")),
+        (_, &[radius], &[count]) => SeedShape::Circle { center, normal, radius, count },
+        _ => return Err(String::from("A circle needs a single radius and count")),
+    };
+    if shape.len() > MAX_SEED_POINTS {
+        return Err(format!("At most {MAX_SEED_POINTS} seed points per shape"));
+    }
+    Ok(shape)
+}
+
+/// One-line summary of a set of seeds for the seeds list.
+fn describe_seed_shape(s: &SeedShape) -> String {
+    match s {
+        SeedShape::Point { position } => format!("point at {position:?}"),
+        SeedShape::Grid { center, size, count, .. } => {
+            format!("{}x{} grid at {center:?}, {}x{} m", count[0], count[1], size[0], size[1])
+        }
+        SeedShape::Circle { center, radius, count, .. } => format!("{count} on a circle at {center:?}, R: {radius}"),
     }
 }
 
+/// One-line summary of a current element for the currents list.
+fn describe_current(c: &Current) -> String {
+    match c {
+        Current::Segment { start, end, current } => format!("segment {start:?}->{end:?}, I: {current}"),
+        Current::Loop { center, radius, current, .. } => format!("loop at {center:?}, R: {radius}, I: {current}"),
+        Current::Solenoid { center, radius, turns, current, .. } => {
+            format!("solenoid at {center:?}, R: {radius}, N: {turns}, I: {current}")
+        }
+    }
+}
+
+/// One-line summary of a dielectric for the dielectrics list.
+fn describe_dielectric(d: &Dielectric) -> String {
+    match d {
+        Dielectric::Sphere { center, radius, epsilon_r } => format!("sphere at {center:?}, R: {radius}, εr: {epsilon_r}"),
+        Dielectric::Box { min, max, epsilon_r } => format!("box {min:?}->{max:?}, εr: {epsilon_r}"),
+        Dielectric::HalfSpace { point, normal, epsilon_r } => format!("half-space at {point:?}, n: {normal:?}, εr: {epsilon_r}"),
+    }
+}
+
+/// One-line summary of a conductor for the conductors list.
+fn describe_conductor(k: &Conductor) -> String {
+    match k {
+        Conductor::Plane { point, normal } => format!("grounded plane through {point:?}, n: {normal:?}"),
+        Conductor::Sphere { center, radius, grounded: true, .. } => format!("grounded sphere at {center:?}, R: {radius}"),
+        Conductor::Sphere { center, radius, charge, .. } => format!("isolated sphere at {center:?}, R: {radius}, Q: {charge:e}"),
+    }
+}
+
+/// One-line summary of a distribution for the sources list.
+fn describe_distribution(d: &Distribution) -> String {
+    match d {
+        Distribution::Line { start, end, lambda } => format!("line {start:?}->{end:?}, λ: {lambda:e}"),
+        Distribution::Ring { center, radius, charge, .. } => format!("ring at {center:?}, R: {radius}, Q: {charge:e}"),
+        Distribution::Disk { center, radius, sigma, .. } => format!("disk at {center:?}, R: {radius}, σ: {sigma:e}"),
+        Distribution::Sphere { center, radius, charge, solid } => format!(
+            "{} at {center:?}, R: {radius}, Q: {charge:e}",
+            if *solid { "ball" } else { "shell" }
+        ),
+        Distribution::Plane { point, sigma, .. } => format!("plane through {point:?}, σ: {sigma:e}"),
+        Distribution::Slab { point, thickness, rho, .. } => format!("slab at {point:?}, t: {thickness}, ρ: {rho:e}"),
+    }
+}
+
+// ── Slice plane ───────────────────────────────────────────────────────────────
+
+/// The plane picked in the slice section (orientation from SLICE_ORIENTATIONS, `custom` the
+/// normal of the last one): through `offset` · n̂, spanning a square of `half_size`.
+fn slice_plane(orientation: usize, custom: [f64; 3], offset: f64, half_size: f64) -> SlicePlane {
+    let normal = match orientation {
+        0 => [0.0, 0.0, 1.0],
+        1 => [1.0, 0.0, 0.0],
+        2 => [0.0, 1.0, 0.0],
+        _ => custom,
+    };
+    let len = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+    SlicePlane { center: normal.map(|c| c / len * offset), normal, half_size }
+}
+
 // ── Window config ─────────────────────────────────────────────────────────────
 
 fn window_conf() -> Conf {
@@ -107,8 +436,19 @@ fn window_conf() -> Conf {
 
 // ── Main ──────────────────────────────────────────────────────────────────────
 
-#[macroquad::main(window_conf)]
-async fn main() {
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match cli::parse(&args) {
+        Ok(cli::Command::Gui { scene }) => macroquad::Window::from_config(window_conf(), app(scene)),
+        Ok(cmd) => std::process::exit(cli::run(cmd)),
+        Err(e) => {
+            eprintln!("error: {e}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    }
+}
+
+async fn app(initial_scene: Option<std::path::PathBuf>) {
     const FONT_BYTES: &[u8] = include_bytes!("static/Lato-Light.ttf");
 
     // Camera parameters
@@ -121,16 +461,98 @@ async fn main() {
     let mut last_mouse_pos = Vec2::ZERO;
 
     // Charge configuration
-    let mut charges: Vec<Charge> = Vec::new();
+    let mut charges: Vec<ChargeSprite> = Vec::new();
     let mut reference_string = String::from("(0,0,0)");
     let mut reference: (f32, f32, f32) = (0.0, 0.0, 0.0);
     let mut charge_value    = String::from("0.00001");
     let mut charge_position = String::from("(1,1,1)");
+    let mut charge_mass     = String::from("1");
+    let mut charge_velocity = String::from("(0,0,0)");
+    let mut charge_pinned   = false;
+
+    // Continuous charge distributions and the "add source" form. The meaning of the
+    // inputs depends on the kind, see DISTRIBUTION_KINDS.
+    let mut distributions: Vec<Distribution> = Vec::new();
+    let mut dist_kind:    usize  = 0;
+    let mut dist_a        = String::from("(0,0,0)");
+    let mut dist_b        = String::from("(0,1,0)");
+    let mut dist_size     = String::from("1");
+    let mut dist_density  = String::from("0.00001");
+    let mut dist_solid    = false;
+
+    // Conductors, solved by the method of images. Their images are rebuilt with `sources`
+    // whenever the configuration changes; `surface_charges` holds σ sampled on every conductor.
+    let mut conductors: Vec<Conductor> = Vec::new();
+    let mut cond_kind:   usize = 0;
+    let mut cond_point   = String::from("(0,-2,0)");
+    let mut cond_normal  = String::from("(0,1,0)");
+    let mut cond_radius  = String::from("1");
+    let mut cond_charge  = String::from("0");
+    let mut show_images: bool = false;
+    let mut color_conductors: bool = false;
+    let mut surface_charges_dirty: bool = false;
+    let mut surface_charges: Vec<SurfaceCharge> = Vec::new();
+    let mut surface_scale = ColorScale::new(Colormap::Diverging, Scale::Linear, -1.0, 1.0);
+
+    // Dielectrics. With any present `sources.screened` replaces the point charges' field: exact for
+    // a single sphere or half-space, otherwise solved on the grid. `bound_charges` holds the
+    // bound surface charge sampled on every dielectric, sharing `surface_scale`.
+    let mut dielectrics: Vec<Dielectric> = Vec::new();
+    let mut diel_kind: usize = 0;
+    let mut diel_a   = String::from("(0,0,0)");
+    let mut diel_b   = String::from("1");
+    let mut diel_eps = String::from("4");
+    let mut color_dielectrics: bool = false;
+    let mut bound_charges: Vec<SurfaceCharge> = Vec::new();
+
+    // Steady currents and their magnetic field. B-field lines are traced like the E lines,
+    // with the same settings, whenever the currents change.
+    let mut currents: Vec<Current> = Vec::new();
+    let mut cur_kind: usize = 1;
+    let mut cur_a       = String::from("(0,0,0)");
+    let mut cur_b       = String::from("(0,1,0)");
+    let mut cur_radius  = String::from("1");
+    let mut cur_length  = String::from("3");
+    let mut cur_turns   = String::from("20");
+    let mut cur_current = String::from("10");
+    let mut show_b_lines: bool = false;
+    let mut b_lines: Vec<Polyline> = Vec::new();
+    let mut b_lines_dirty: bool = false;
+
+    // Test particles launched into the static fields; their trajectories are traced again
+    // whenever the configuration changes, once playback pauses.
+    let mut particles: Vec<Particle> = Vec::new();
+    let mut part_charge   = String::from("-0.000001");
+    let mut part_mass     = String::from("0.001");
+    let mut part_position = String::from("(-3,0,0)");
+    let mut part_velocity = String::from("(1,0,0)");
+    let mut trajectory_settings = TrajectorySettings::default();
+    let mut duration_string = String::from("10");
+    let mut trajectories: Vec<Trajectory> = Vec::new();
+    let mut show_trajectories: bool = true;
+    let mut trajectories_dirty: bool = false;
+
+    // Scene file; `pending_scene` is loaded at the start of the next frame
+    let mut scene_path = initial_scene
+        .as_ref()
+        .map_or(String::from("scene.toml"), |p| p.display().to_string());
+    let mut pending_scene = initial_scene;
 
     // Calculation results
     let mut phi: String;
     let mut p:   String;
     let mut e:   String;
+    let mut b:   String;
+    let mut f:   String;
+    let mut u:   String;
+    let mut sph: String;
+    let mut forces: Vec<Vector3D> = Vec::new();
+
+    // Spherical multipole expansion shown next to the exact potential at r'
+    let mut sph_l_max_string  = String::from("4");
+    let mut sph_l_max: usize  = 4;
+    let mut sph_center_string = String::from("(0,0,0)");
+    let mut sph_center        = (0.0f32, 0.0f32, 0.0f32);
 
     // Error dialog
     let mut error_dialog = ErrorDialog::new();
@@ -141,9 +563,136 @@ async fn main() {
     // `show_field_lines` toggles the visualisation.
     // `field_lines_dirty` signals that lines must be recomputed next frame.
     let mut show_field_lines: bool = false;
-    let mut field_lines: Vec<Vec<(f32, f32, f32)>> = Vec::new();
+    let mut field_lines: Vec<Polyline> = Vec::new();
     let mut field_line_bounds: f32 = 0.0;
     let mut field_lines_dirty: bool = false;
+    let mut field_line_settings = FieldLineSettings::default();
+    // Integrator choice (index into FIELD_LINE_INTEGRATORS) and how the last lines were traced
+    const FIELD_LINE_INTEGRATORS: [&str; 2] = ["RK4 (fixed step)", "Dormand-Prince (adaptive)"];
+    let mut integrator_index: usize = 0;
+    // Seeding choice (index into FIELD_LINE_SEEDINGS)
+    const FIELD_LINE_SEEDINGS: [&str; 2] = ["Proportional to |q|", "Fixed per charge"];
+    let mut seeding_index: usize = 0;
+    let mut tolerance_string = String::from("0.00001");
+    let mut field_line_stats: Vec<LineStats> = Vec::new();
+    // User-placed seeds, traced both ways; `seed_by_click` adds a point seed on right click
+    let mut seed_shapes: Vec<SeedShape> = Vec::new();
+    let mut seed_kind: usize = 1;
+    let mut seed_a      = String::from("(0,0,0)");
+    let mut seed_b      = String::from("(1,0,0)");
+    let mut seed_size   = String::from("2,2");
+    let mut seed_count  = String::from("4,4");
+    let mut seed_by_click: bool = false;
+    // Multipole error along each field line (one value per vertex), shown instead of
+    // the plain line colour when `color_lines_by_error` is set
+    let mut color_lines_by_error: bool = false;
+    let mut line_errors_dirty: bool = false;
+    let mut field_line_errors: Vec<Vec<f64>> = Vec::new();
+    // |E|, ϕ and arc length along each field line, for colouring by LINE_COLORINGS[line_color_index]
+    // (0 = plain); the multipole error above takes precedence when it is switched on
+    const LINE_COLORINGS: [&str; 4] = ["Plain", "|E|", "Potential", "Arc length"];
+    const LINE_QUANTITIES: [LineQuantity; 3] = [LineQuantity::FieldMagnitude, LineQuantity::Potential, LineQuantity::ArcLength];
+    const COLORMAPS: [&str; 3] = ["Viridis", "Plasma", "Diverging"];
+    let mut line_color_index: usize = 0;
+    let mut line_colormap_index: usize = 0;
+    let mut line_color_log: bool = true;
+    let mut line_scalars: Vec<LineScalars> = Vec::new();
+    let mut line_scale: Option<ColorScale> = None;
+    let mut line_scalars_dirty: bool = false;
+    // Arrowheads along the field lines pointing along E, `arrow_spacing` apart (arc length)
+    // and `arrow_size` long, in metres
+    let mut show_line_arrows: bool = false;
+    let mut arrow_spacing: f64 = 1.0;
+    let mut arrow_size: f32 = 0.15;
+    let mut arrow_spacing_string = String::from("1");
+    let mut arrow_size_string    = String::from("0.15");
+    let mut field_line_arrows: Vec<Vec<LineArrow>> = Vec::new();
+    let mut line_arrows_dirty: bool = false;
+    // Set whenever charges or sources change; turned into the per-view dirty flags
+    let mut config_changed: bool = false;
+
+    // ── Equipotential surfaces ────────────────────────────────────────────────
+    // One triangle soup per requested level, extracted from a potential grid
+    // sampled inside the bounding cube.
+    let mut show_equipotentials: bool = false;
+    let mut equipotentials_dirty: bool = false;
+    let mut equipotential_levels_string = String::from("1e5, 5e4, -5e4, -1e5");
+    let mut equipotential_levels: Vec<f64> = vec![1e5, 5e4, -5e4, -1e5];
+    let mut equipotential_resolution_string = String::from("40");
+    let mut equipotential_resolution: usize = 40;
+    let mut equipotentials: Vec<(f64, Vec<Triangle>)> = Vec::new();
+
+    // ── Slice plane ───────────────────────────────────────────────────────────
+    // A heatmap of ϕ or |E| on a movable plane through the scene. The plane passes
+    // through `slice_offset` · n̂ and spans the bounding cube.
+    const SLICE_ORIENTATIONS: [&str; 4] = ["XY", "YZ", "XZ", "Custom normal"];
+    const SLICE_QUANTITIES: [&str; 3] = ["Potential", "Field strength |E|", "Multipole error"];
+    let mut show_slice: bool = false;
+    let mut slice_dirty: bool = false;
+    let mut slice_orientation: usize = 0;
+    let mut slice_quantity: usize = 0;
+    let mut slice_log: bool = false;
+    let mut slice_normal_string = String::from("(1, 1, 0)");
+    let mut slice_normal: [f64; 3] = [1.0, 1.0, 0.0];
+    let mut slice_offset: f64 = 0.0;
+    let mut slice_offset_string = String::from("0");
+    let mut slice_resolution_string = String::from("128");
+    let mut slice_resolution: usize = 128;
+    let mut slice: Option<(SliceGrid, ColorScale, Texture2D)> = None;
+
+    // ── Vector glyphs ─────────────────────────────────────────────────────────
+    // A quiver plot of E on a lattice over the bounding cube or over the slice plane,
+    // coloured by |E|. Glyphs on the slice follow it whenever the plane moves.
+    const GLYPH_DOMAINS: [&str; 2] = ["3-D lattice", "Slice plane"];
+    let mut show_glyphs: bool = false;
+    let mut glyphs_dirty: bool = false;
+    let mut glyph_domain: usize = 0;
+    let mut glyph_log: bool = false;
+    let mut glyph_resolution_string = String::from("9");
+    let mut glyph_resolution: usize = 9;
+    let mut glyph_length_string = String::from("0.5");
+    let mut glyph_length: f64 = 0.5;
+    let mut glyphs: Vec<Glyph> = Vec::new();
+    let mut glyph_sizes: Vec<f64> = Vec::new();
+    let mut glyph_scale: Option<ColorScale> = None;
+    let mut glyph_plane: Option<SlicePlane> = None;
+
+    // ── Field evaluation backend ──────────────────────────────────────────────
+    // Direct summation, a Barnes–Hut octree or the fast multipole method for large
+    // charge clouds, used by `sources` for the point charges.
+    const FIELD_BACKENDS: [&str; 4] = ["Direct sum", "Barnes-Hut", "Fast multipole", "Grid (Poisson)"];
+    let mut field_backend_index: usize = 0;
+    let mut field_backend = FieldBackend::Direct;
+    let mut theta_string = String::from("0.5");
+    let mut theta: f64 = 0.5;
+    let mut fmm_order_string = String::from("6");
+    let mut fmm_order: usize = 6;
+    // The grid (Poisson) method replaces the point charges by `sources.grid`, solved for the
+    // charges and the electrodes whenever the configuration changes
+    const GRID_BOUNDARIES: [&str; 2] = ["Grounded faces", "Insulating faces"];
+    let mut grid_solver = GridSolver::default();
+    let mut grid_resolution_string = String::from("48");
+    let mut grid_boundary_index: usize = 0;
+    let mut electrode_kind: usize = 0;
+    let mut electrode_a = String::from("(0,-3,0)");
+    let mut electrode_b = String::from("1");
+    let mut electrode_potential = String::from("100");
+    // Everything the electric field comes from, rebuilt whenever the configuration changes
+    let mut sources = SceneSources::new(&configuration(&charges), field_backend, &distributions, &conductors, &dielectrics, None);
+
+    // Force arrows drawn at each charge
+    let mut show_forces: bool = false;
+
+    // ── Dynamics state ────────────────────────────────────────────────────────
+    // `simulation` is created lazily on Play/Step and dropped whenever the user
+    // edits the configuration, so the drift is always measured from the last edit.
+    let mut simulation: Option<Simulation> = None;
+    let mut sim_running: bool = false;
+    let mut sim_step_once: bool = false;
+    let mut time_step_string = String::from("0.001");
+    let mut time_step: f64 = 0.001;
+    let mut pin_index = String::from("1");
+    let mut d: String;
 
     // ── UI skin ───────────────────────────────────────────────────────────────
     let window_style = root_ui()
@@ -153,7 +702,7 @@ async fn main() {
 
     let label_style = root_ui()
         .style_builder()
-        .font(&FONT_BYTES).unwrap()
+        .font(FONT_BYTES).unwrap()
         .font_size(13)
         .build();
 
@@ -162,7 +711,7 @@ async fn main() {
         .background_margin(RectOffset::new(16.0, 16.0, 16.0, 16.0))
         .margin(RectOffset::new(16.0, 16.0, -8.0, -8.0))
         .color(LIGHTGRAY)
-        .font(&FONT_BYTES).unwrap()
+        .font(FONT_BYTES).unwrap()
         .text_color(BLACK)
         .font_size(12)
         .build();
@@ -174,25 +723,235 @@ async fn main() {
     loop {
         clear_background(LIGHTGRAY);
 
+        // ── Load a scene requested via --scene or the Load button ─────────────
+        if let Some(path) = pending_scene.take() {
+            match Scene::load(&path) {
+                Err(e) => { err_text = e.to_string(); show_error = true; }
+                Ok(scene) => {
+                    charges             = sprites_from_scene(&scene);
+                    distributions       = scene.distributions.clone();
+                    conductors          = scene.conductors.clone();
+                    dielectrics         = scene.dielectrics.clone();
+                    currents            = scene.currents.clone();
+                    b_lines_dirty       = true;
+                    particles           = scene.particles.clone();
+                    seed_shapes         = scene.seeds.clone();
+                    trajectory_settings = scene.trajectories.clone();
+                    duration_string     = trajectory_settings.duration.to_string();
+                    if let Some(g) = &scene.grid_solver {
+                        grid_solver            = g.clone();
+                        grid_resolution_string = g.resolution.to_string();
+                        grid_boundary_index    = usize::from(g.boundary == Boundary::Neumann);
+                        field_backend_index    = 3;
+                    } else if field_backend_index == 3 {
+                        field_backend_index    = 0;
+                    }
+                    reference           = scene.reference();
+                    reference_string    = format!("({},{},{})", reference.0, reference.1, reference.2);
+                    show_field_lines    = scene.field_lines.show;
+                    field_line_settings = scene.field_lines.settings;
+                    integrator_index    = usize::from(field_line_settings.integrator == Integrator::Adaptive);
+                    seeding_index       = usize::from(field_line_settings.seeding == Seeding::PerCharge);
+                    tolerance_string    = field_line_settings.tolerance.to_string();
+                    field_lines.clear();
+                    config_changed      = true;
+                    simulation          = None;
+                    sim_running         = false;
+                }
+            }
+        }
+
+        // ── Advance the dynamics simulation ───────────────────────────────────
+        // The simulation only knows Coulomb's law, and every step would re-solve the grid
+        let dynamics_allowed = field_backend_index != 3 && dielectrics.is_empty();
+        if !dynamics_allowed {
+            sim_running   = false;
+            sim_step_once = false;
+        }
+        if (sim_running || sim_step_once) && !charges.is_empty() {
+            let sim = simulation.get_or_insert_with(|| {
+                Simulation::new(&configuration(&charges), &kinematics(&charges), SIM_SOFTENING)
+            });
+            let steps = if sim_running { SIM_SUBSTEPS } else { 1 };
+            for _ in 0..steps {
+                sim.step(time_step);
+            }
+            for ((sprite, charge), kin) in charges.iter_mut().zip(sim.charges()).zip(sim.kinematics()) {
+                sprite.charge     = charge;
+                sprite.kinematics = kin;
+            }
+            // Field lines and surfaces follow the charges
+            config_changed = true;
+        }
+        sim_step_once = false;
+
+        // Everything derived from the configuration is rebuilt lazily
+        if config_changed {
+            let grid             = (field_backend_index == 3).then_some(&grid_solver);
+            sources              = SceneSources::new(
+                &configuration(&charges), field_backend, &distributions, &conductors, &dielectrics, grid,
+            );
+            field_lines_dirty    = true;
+            trajectories_dirty   = true;
+            surface_charges_dirty = true;
+            equipotentials_dirty = true;
+            slice_dirty          = true;
+            glyphs_dirty         = true;
+            config_changed       = false;
+        }
+
+        let config = configuration(&charges);
+        let has_sources = !sources.is_empty();
+        let electrodes = if field_backend_index == 3 { grid_solver.electrodes.as_slice() } else { &[] };
+
         // ── Recompute field lines if the configuration changed ────────────────
         // done once per dirty frame.
         if field_lines_dirty && show_field_lines {
-            if charges.is_empty() {
+            if !has_sources {
                 field_lines.clear();
                 field_line_bounds = 0.0;
             } else {
-                let (lines, bounds) = generate_field_lines(&charges);
+                let mut extra = distribution_seeds(&distributions, field_line_settings.seeds);
+                extra.extend(user_seeds(&seed_shapes));
+                let (lines, stats, bounds) = generate_charge_field_lines(&sources, &config, &extra, &field_line_settings);
                 field_lines      = lines;
+                field_line_stats = stats;
                 field_line_bounds = bounds;
             }
             field_lines_dirty = false;
+            line_errors_dirty = true;
+            line_scalars_dirty = true;
+            line_arrows_dirty = true;
+        }
+
+        // ── Recompute B-field lines if the currents changed ───────────────────
+        if b_lines_dirty && show_b_lines {
+            let seeds = current_seeds(&currents, &field_line_settings);
+            b_lines = generate_field_lines_in(&MagneticField::new(&currents), &seeds, &field_line_settings).0;
+            b_lines_dirty = false;
+        }
+
+        // ── Trace the test particles ──────────────────────────────────────────
+        // Every playback frame moves the charges, so retrace only once it pauses
+        if trajectories_dirty && show_trajectories && !sim_running {
+            trajectories = particles
+                .iter()
+                .map(|p| trace_particle(p, &sources, &currents, &trajectory_settings))
+                .collect();
+            trajectories_dirty = false;
+        }
+
+        // ── Multipole error along the field lines ─────────────────────────────
+        if line_errors_dirty && color_lines_by_error {
+            let approx = MultipoleApproximation::new(&config, &reference);
+            field_line_errors = field_lines
+                .iter()
+                .map(|line| line.iter().map(|p| multipole_relative_error(&config, &approx, p)).collect())
+                .collect();
+            line_errors_dirty = false;
+        }
+
+        // ── Scalars along the field lines and their colour scale ──────────────
+        if line_scalars_dirty && line_color_index > 0 {
+            line_scalars = field_lines.iter().map(|line| LineScalars::sample(&sources, line)).collect();
+            let quantity = LINE_QUANTITIES[line_color_index - 1];
+            let colormap = [Colormap::Viridis, Colormap::Plasma, Colormap::Diverging][line_colormap_index];
+            let scale = if line_color_log { Scale::Log } else { Scale::Linear };
+            line_scale = line_scalar_range(&line_scalars, quantity).map(|(lo, hi)| ColorScale::new(colormap, scale, lo, hi));
+            line_scalars_dirty = false;
+        }
+
+        // ── Direction arrows along the field lines ────────────────────────────
+        if line_arrows_dirty && show_line_arrows {
+            field_line_arrows = field_lines.iter().map(|line| line_arrows(&sources, line, arrow_spacing)).collect();
+            line_arrows_dirty = false;
+        }
+
+        // ── Induced surface charge on the conductors, bound charge on dielectrics ─
+        // Planes and half-spaces are sampled across the bounding cube they are drawn in
+        if surface_charges_dirty && (color_conductors || color_dielectrics) {
+            let half = bounding_half_size(&sources).max(6.0) as f64;
+            surface_charges = if color_conductors {
+                conductors.iter().map(|k| sample_surface_charge(&sources, k, 48, half)).collect()
+            } else {
+                Vec::new()
+            };
+            bound_charges = if color_dielectrics {
+                dielectrics.iter().flat_map(|d| sample_bound_charge(&sources, d, 48, half)).collect()
+            } else {
+                Vec::new()
+            };
+            let s_max = surface_charges
+                .iter()
+                .chain(&bound_charges)
+                .map(|s| s.sigma_range())
+                .fold(0.0f64, |acc, (lo, hi)| acc.max(lo.abs()).max(hi.abs()));
+            surface_scale = ColorScale::new(Colormap::Diverging, Scale::Linear, -s_max, s_max);
+            surface_charges_dirty = false;
+        }
+
+        // ── Re-extract equipotential surfaces ─────────────────────────────────
+        if equipotentials_dirty && show_equipotentials {
+            equipotentials.clear();
+            if has_sources {
+                let bounds = bounding_half_size(&sources) as f64;
+                let grid = ScalarGrid::sample_cube(bounds, equipotential_resolution, |p| sources.potential(p));
+                for &level in &equipotential_levels {
+                    equipotentials.push((level, marching_cubes(&grid, level)));
+                }
+            }
+            equipotentials_dirty = false;
+        }
+
+        // ── Resample the slice plane ──────────────────────────────────────────
+        if slice_dirty && show_slice {
+            slice = None;
+            if has_sources {
+                let plane = slice_plane(slice_orientation, slice_normal, slice_offset, bounding_half_size(&sources) as f64);
+                let (grid, scale) = if slice_quantity == 2 {
+                    // Error of the multipole expansion about r', on a fixed scale
+                    (sample_multipole_error(&config, &reference, &plane, slice_resolution), multipole_error_scale())
+                } else {
+                    let quantity = if slice_quantity == 0 { SliceQuantity::Potential } else { SliceQuantity::FieldMagnitude };
+                    let grid = sample_slice(&sources, &plane, quantity, slice_resolution);
+                    let (lo, hi) = grid.value_range();
+                    let colormap = if quantity == SliceQuantity::Potential { Colormap::Diverging } else { Colormap::Viridis };
+                    let scale = ColorScale::new(colormap, if slice_log { Scale::Log } else { Scale::Linear }, lo, hi);
+                    (grid, scale)
+                };
+                let texture = slice_texture(&grid, &scale);
+                slice = Some((grid, scale, texture));
+            }
+            slice_dirty = false;
+        }
+
+        // ── Resample the vector glyphs ────────────────────────────────────────
+        if show_glyphs {
+            let half_size = bounding_half_size(&sources) as f64;
+            let plane = (glyph_domain == 1).then(|| slice_plane(slice_orientation, slice_normal, slice_offset, half_size));
+            if glyphs_dirty || plane != glyph_plane {
+                glyphs = match (&plane, has_sources) {
+                    (_, false) => Vec::new(),
+                    (Some(plane), true) => sample_glyphs_slice(&sources, plane, glyph_resolution),
+                    (None, true) => sample_glyphs_cube(&sources, half_size, glyph_resolution),
+                };
+                let scaling = if glyph_log { GlyphScaling::Log } else { GlyphScaling::Linear };
+                glyph_sizes = glyph_lengths(&glyphs, scaling, glyph_length);
+                let (lo, hi) = glyph_magnitude_range(&glyphs);
+                glyph_scale = (hi > 0.0)
+                    .then(|| ColorScale::new(Colormap::Viridis, if glyph_log { Scale::Log } else { Scale::Linear }, lo, hi));
+                glyph_plane = plane;
+                glyphs_dirty = false;
+            }
         }
 
         // ── Electrostatic calculations ────────────────────────────────────────
-        if !charges.is_empty() {
-            let mm   = multipole_moments(&charges, &reference);
-            let _e   = electric_field(&charges, &reference);
-            let _phi = electric_potential(&charges, &reference);
+        if has_sources {
+            // Multipole moments and the interaction energy cover the point charges;
+            // the potential, field and forces include the distributions too.
+            let mm   = multipole_moments(&config, &reference);
+            let _e   = sources.field(&reference);
+            let _phi = sources.potential(&reference);
 
             let (px, py, pz) = mm.dipole.components();
             let (ex, ey, ez) = _e.components();
@@ -225,12 +984,109 @@ async fn main() {
                  |E| = {:.4e} N/C",
                 ex, ey, ez, _e.magnitude()
             );
+            if !dielectrics.is_empty() {
+                let m = material_fields(&sources, &dielectrics, &reference);
+                let (dx, dy, dz) = m.d.components();
+                let (polx, poly, polz) = m.p.components();
+                e += &format!(
+                    "\n\nεr = {:.4}\n\
+                     D = ({:.3e},\n     {:.3e},\n     {:.3e}) C/m²\n\
+                     P = ({:.3e},\n     {:.3e},\n     {:.3e}) C/m²",
+                    m.epsilon_r, dx, dy, dz, polx, poly, polz
+                );
+            }
+
+            // Net Coulomb force on each charge (own field excluded) plus the push from the
+            // continuous distributions, the induced charge and the grid's electrodes
+            forces = sources.forces();
+            f = forces
+                .iter()
+                .enumerate()
+                .map(|(i, force)| {
+                    let (fx, fy, fz) = force.components();
+                    format!(
+                        "{}- F = ({:.3e},\n       {:.3e},\n       {:.3e}) N\n   |F| = {:.4e} N",
+                        i + 1, fx, fy, fz, force.magnitude()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            // Truncated spherical multipole series against the exact ϕ of the point charges
+            sph = if config.is_empty() {
+                String::from("Not Calculated (no point charges)")
+            } else {
+                let sm    = spherical_multipoles(&config, &sph_center, sph_l_max);
+                let exact = electric_potential(&config, &reference);
+                let (rx, ry, rz) = (reference.0 - sph_center.0, reference.1 - sph_center.1, reference.2 - sph_center.2);
+                let dist  = ((rx * rx + ry * ry + rz * rz) as f64).sqrt();
+                let mut text = format!(
+                    "|r' - c| = {:.3} m\ncharges within R = {:.3} m\n",
+                    dist, sm.radius
+                );
+                if dist <= sm.radius {
+                    text += "r' lies inside R: the series\ndoes not converge there\n";
+                }
+                text += "\nl   Phi up to l (V)  rel. error\n";
+                for l in 0..=sph_l_max {
+                    let phi_l = sm.potential_to(l, &reference);
+                    text += &format!("{:<3} {:.5e}  {:.2e}\n", l, phi_l, ((phi_l - exact) / exact).abs());
+                }
+                text += &format!("exact: {:.5e} V\n\nQ_lm (m >= 0) [C·m^l]", exact);
+                for l in 0..=sph_l_max {
+                    for m in 0..=l as i64 {
+                        let q = sm.moment(l, m);
+                        text += &format!("\nQ{},{} = ({:.3e}, {:.3e})", l, m, q.re, q.im);
+                    }
+                }
+                text
+            };
+
+            // Interaction energy with per-pair breakdown
+            let pe = potential_energy(&config);
+            u = format!("U = {:.4e} J", pe.total);
+            for pair in &pe.pairs {
+                u += &format!("\n  U{},{} = {:.4e} J", pair.i + 1, pair.j + 1, pair.energy);
+            }
         } else {
             phi = String::from("Not Calculated");
             p   = String::from("Not Calculated");
             e   = String::from("Not Calculated");
+            f   = String::from("Not Calculated");
+            u   = String::from("Not Calculated");
+            sph = String::from("Not Calculated");
+            forces.clear();
         }
 
+        // The magnetic field only depends on the currents
+        let _b = magnetic_field(&currents, &reference);
+        let (bx, by, bz) = _b.components();
+        b = format!("B = ({:.3e},\n     {:.3e},\n     {:.3e}) T\n|B| = {:.4e} T", bx, by, bz, _b.magnitude());
+
+        d = match &simulation {
+            None => String::from("Not Running"),
+            Some(sim) => {
+                let (px, py, pz) = sim.momentum().components();
+                let drift = sim.drift();
+                format!(
+                    "t = {:.4} s\n\
+                     T = {:.4e} J\n\
+                     U = {:.4e} J\n\
+                     E = {:.4e} J\n\
+                     dE/|E0| = {:.3e}\n\
+                     P = ({:.3e},\n     {:.3e},\n     {:.3e}) kg·m/s\n\
+                     |dP| = {:.3e} kg·m/s",
+                    sim.time,
+                    sim.kinetic_energy(),
+                    sim.potential_energy(),
+                    sim.total_energy(),
+                    drift.energy,
+                    px, py, pz,
+                    drift.momentum,
+                )
+            }
+        };
+
         // ── Camera drag ───────────────────────────────────────────────────────
         if is_mouse_button_down(MouseButton::Left) {
             let mouse_pos: Vec2 = mouse_position().into();
@@ -258,53 +1114,190 @@ async fn main() {
             camera_distance * camera_yaw.sin() * camera_pitch.cos(),
         ) + target;
 
-        set_camera(&Camera3D {
+        let camera = Camera3D {
             position: camera_position,
             target,
             up: Vec3::new(0.0, 1.0, 0.0),
             ..Default::default()
-        });
+        };
+        set_camera(&camera);
+
+        // ── Seed a field line by right-clicking the view ──────────────────────
+        // The seed lands where the mouse ray crosses the plane through the target facing the camera
+        let mouse: Vec2 = mouse_position().into();
+        if seed_by_click && is_mouse_button_pressed(MouseButton::Right) && !root_ui().is_mouse_over(mouse) {
+            if let Some(p) = pick_point(&camera, mouse, target) {
+                seed_shapes.push(SeedShape::Point { position: [p.x as f64, p.y as f64, p.z as f64] });
+                field_lines_dirty = true;
+            }
+        }
 
         // ── 3-D objects ───────────────────────────────────────────────────────
         draw_grid(20, 1.0, WHITE, WHITE);
         draw_sphere(vec3(reference.0, reference.1, reference.2), 0.1, None, BLACK);
 
-        for charge in &charges {
-            let (px, py, pz) = charge.position;
-            let (cr, cg, cb, ca) = charge.color;
+        for dist in &distributions {
+            draw_distribution(dist, field_line_bounds.max(6.0));
+        }
+        for current in &currents {
+            draw_current(current);
+        }
+
+        if color_conductors && surface_charges.len() == conductors.len() {
+            for surface in &surface_charges {
+                draw_surface_charge(surface, &surface_scale);
+            }
+        } else {
+            for k in &conductors {
+                draw_conductor(k, field_line_bounds.max(6.0));
+            }
+        }
+        if show_images {
+            draw_image_charges(sources.images.images());
+        }
+        if color_dielectrics && !bound_charges.is_empty() {
+            for surface in &bound_charges {
+                draw_surface_charge(surface, &surface_scale);
+            }
+        } else {
+            for d in &dielectrics {
+                draw_dielectric(d, field_line_bounds.max(6.0));
+            }
+        }
+
+        let electrode_v_max = electrodes.iter().fold(0.0f64, |acc, e| acc.max(e.potential().abs()));
+        for electrode in electrodes {
+            draw_electrode(electrode, electrode_v_max);
+        }
+
+        for sprite in &charges {
+            let (px, py, pz) = sprite.charge.position;
             draw_sphere(
                 vec3(px, py, pz),
-                charge_to_radius(charge.charge) as f32,
+                charge_to_radius(sprite.charge.charge) as f32,
                 None,
-                Color::new(cr, cg, cb, ca),
+                sprite.color,
             );
         }
 
         // Lines between charges
-        for i in 0..charges.len() {
-            for j in (i + 1)..charges.len() {
-                let (ax, ay, az) = charges[i].position;
-                let (bx, by, bz) = charges[j].position;
+        for i in 0..config.len() {
+            for j in (i + 1)..config.len() {
+                let (ax, ay, az) = config[i].position;
+                let (bx, by, bz) = config[j].position;
                 draw_line_3d(vec3(ax, ay, az), vec3(bx, by, bz), Color::new(1.0, 0.3, 0.5, 0.9));
             }
         }
 
+        // Force arrows: start on the sphere surface, length linear in |F|
+        // relative to the strongest force in the configuration
+        if show_forces {
+            let f_max = forces.iter().fold(0.0f64, |acc, f| acc.max(f.magnitude()));
+            if f_max > 0.0 {
+                for (ch, force) in config.iter().zip(&forces) {
+                    let m = force.magnitude();
+                    if m == 0.0 {
+                        continue;
+                    }
+                    let (fx, fy, fz) = force.components();
+                    let dir = vec3(fx as f32, fy as f32, fz as f32) / m as f32;
+                    let centre = vec3(ch.position.0, ch.position.1, ch.position.2);
+                    let from = centre + dir * charge_to_radius(ch.charge) as f32;
+                    let len = 0.3 + 1.2 * (m / f_max) as f32;
+                    draw_arrow_3d(from, from + dir * len, 0.2, Color::new(0.85, 0.4, 0.0, 1.0));
+                }
+            }
+        }
+
         // ── Field lines ───────────────────────────────────────────────────────
         if show_field_lines && !field_lines.is_empty() {
             // Faint bounding cube so the user sees the computation volume
             draw_bounds_cube(field_line_bounds, Color::new(0.35, 0.35, 1.0, 0.18));
 
-            // Draw each polyline segment by segment
-            for line in &field_lines {
+            // Draw each polyline segment by segment, optionally coloured by the
+            // multipole error or the chosen scalar at the segment's start
+            let error_scale = multipole_error_scale();
+            let scalar_scale = line_scale.filter(|_| line_color_index > 0);
+            for (k, line) in field_lines.iter().enumerate() {
+                let errors = field_line_errors.get(k).filter(|_| color_lines_by_error);
+                let scalars = scalar_scale
+                    .and_then(|scale| Some((scale, line_scalars.get(k)?.get(LINE_QUANTITIES[line_color_index - 1]))));
+                let color = |i: usize| match (errors, scalars) {
+                    (Some(err), _)             => Color::from(error_scale.color(err[i])),
+                    (None, Some((scale, v)))   => Color::from(scale.color(v[i])),
+                    (None, None)               => Color::new(0.05, 0.88, 0.3, 0.82),
+                };
+                for (i, seg) in line.windows(2).enumerate() {
+                    let (ax, ay, az) = seg[0];
+                    let (bx, by, bz) = seg[1];
+                    draw_line_3d(vec3(ax, ay, az), vec3(bx, by, bz), color(i));
+                }
+                // Arrowheads take the colour of the segment they sit on
+                for arrow in field_line_arrows.get(k).filter(|_| show_line_arrows).into_iter().flatten() {
+                    let (x, y, z) = arrow.position;
+                    let (dx, dy, dz) = arrow.direction.components();
+                    let dir = vec3(dx as f32, dy as f32, dz as f32);
+                    draw_cone(vec3(x, y, z), dir, arrow_size, 0.35 * arrow_size, color(arrow.segment));
+                }
+            }
+        }
+
+        if show_field_lines {
+            for p in seed_shapes.iter().flat_map(SeedShape::points) {
+                let (x, y, z) = p.components();
+                draw_sphere(vec3(x as f32, y as f32, z as f32), 0.04, None, Color::new(0.05, 0.6, 0.2, 1.0));
+            }
+        }
+
+        if show_b_lines {
+            for line in &b_lines {
                 for seg in line.windows(2) {
                     let (ax, ay, az) = seg[0];
                     let (bx, by, bz) = seg[1];
-                    draw_line_3d(
-                        vec3(ax, ay, az),
-                        vec3(bx, by, bz),
-                        Color::new(0.05, 0.88, 0.3, 0.82),
-                    );
+                    draw_line_3d(vec3(ax, ay, az), vec3(bx, by, bz), Color::new(0.75, 0.1, 0.75, 0.82));
+                }
+            }
+        }
+
+        // ── Vector glyphs ─────────────────────────────────────────────────────
+        if let Some(scale) = glyph_scale.filter(|_| show_glyphs) {
+            for (g, &len) in glyphs.iter().zip(&glyph_sizes) {
+                let m = g.field.magnitude();
+                if len <= 0.0 || m == 0.0 {
+                    continue;
                 }
+                let (ex, ey, ez) = g.field.components();
+                let dir = vec3(ex as f32, ey as f32, ez as f32) / m as f32;
+                let half = dir * (0.5 * len as f32);
+                let centre = vec3(g.position.0, g.position.1, g.position.2);
+                draw_arrow_3d(centre - half, centre + half, 0.3 * len as f32, Color::from(scale.color(m)));
+            }
+        }
+
+        // ── Particle trajectories ─────────────────────────────────────────────
+        for trajectory in &trajectories {
+            let (x, y, z) = trajectory.points[0];
+            draw_sphere(vec3(x, y, z), 0.06, None, Color::new(0.95, 0.6, 0.0, 1.0));
+            for seg in trajectory.points.windows(2) {
+                let (ax, ay, az) = seg[0];
+                let (bx, by, bz) = seg[1];
+                draw_line_3d(vec3(ax, ay, az), vec3(bx, by, bz), Color::new(0.95, 0.6, 0.0, 1.0));
+            }
+        }
+
+        // ── Slice heatmap ─────────────────────────────────────────────────────
+        if show_slice {
+            if let Some((grid, _, texture)) = &slice {
+                draw_slice(grid, texture);
+            }
+        }
+
+        // ── Equipotentials ────────────────────────────────────────────────────
+        // Drawn last so the translucent surfaces blend over everything else
+        if show_equipotentials {
+            let v_max = equipotential_levels.iter().fold(0.0f64, |acc, v| acc.max(v.abs()));
+            for (level, tris) in &equipotentials {
+                draw_shaded_triangles(tris, level_color(*level, v_max));
             }
         }
 
@@ -320,7 +1313,12 @@ async fn main() {
                 if ui.button(None, "Set") {
                     match string_to_tuple(&reference_string) {
                         Err(e) => { err_text = String::from(e); show_error = true; }
-                        Ok(v)  => reference = v,
+                        Ok(v)  => {
+                            reference = v;
+                            // the multipole expansion is taken about r'
+                            line_errors_dirty = true;
+                            if slice_quantity == 2 { slice_dirty = true; }
+                        }
                     }
                 }
 
@@ -328,37 +1326,57 @@ async fn main() {
                 ui.label(None, "** Add Charges to Configuration:");
                 ui.input_text(hash!(), "Charge value (in C)", &mut charge_value);
                 ui.input_text(hash!(), "Charge position",     &mut charge_position);
+                ui.input_text(hash!(), "Mass (in kg)",        &mut charge_mass);
+                ui.input_text(hash!(), "Velocity (in m/s)",   &mut charge_velocity);
+                ui.checkbox(hash!(), "Pinned", &mut charge_pinned);
 
                 if ui.button(None, "add charge") {
                     let p = string_to_tuple(&charge_position);
+                    let v = string_to_tuple(&charge_velocity);
+                    let mass = charge_mass.parse::<f64>().ok().filter(|m| m.is_finite() && *m > 0.0);
                     if charge_value.parse::<f32>().is_err() {
                         err_text = String::from("Charge value is not a number");
                         show_error = true;
                     } else if let Err(e) = p {
                         err_text = String::from(e);
                         show_error = true;
-                    } else if !is_position_unique(&charge_position, &charges) {
+                    } else if !is_position_unique(&charge_position, &config) {
                         err_text = String::from("Position is not unique");
                         show_error = true;
+                    } else if mass.is_none() {
+                        err_text = String::from("Mass must be a positive number");
+                        show_error = true;
+                    } else if let Err(e) = v {
+                        err_text = format!("Velocity: {e}");
+                        show_error = true;
                     } else {
                         let position = p.unwrap();
-                        charges.push(Charge {
-                            charge:   charge_value.parse::<f64>().unwrap(),
-                            position,
-                            color:    generate_random_rgba(),
-                        });
+                        let (vx, vy, vz) = v.unwrap();
+                        let mut sprite = ChargeSprite::new(
+                            Charge::new(charge_value.parse::<f64>().unwrap(), position),
+                            generate_random_rgba(),
+                        );
+                        sprite.kinematics = Kinematics {
+                            mass:     mass.unwrap(),
+                            velocity: (vx as f64, vy as f64, vz as f64),
+                            pinned:   charge_pinned,
+                        };
+                        charges.push(sprite);
                         // Mark field lines for recomputation
-                        field_lines_dirty = true;
+                        config_changed = true;
+                        simulation = None;
                     }
                 }
 
                 ui.separator(); ui.separator();
                 ui.label(None, "** Charges:");
 
-                let mut idx = 1;
-                for charge in &charges {
-                    ui.label(None, &format!("{idx}- c: {:.?}, p: {:?}", charge.charge, charge.position));
-                    idx += 1;
+                for (idx, sprite) in charges.iter().enumerate() {
+                    let pin = if sprite.kinematics.pinned { " [pinned]" } else { "" };
+                    ui.label(None, &format!(
+                        "{}- c: {:.?}, p: {:?}{pin}",
+                        idx + 1, sprite.charge.charge, sprite.charge.position
+                    ));
                 }
 
                 if charges.is_empty() {
@@ -367,9 +1385,48 @@ async fn main() {
                     ui.separator();
                 } else if ui.button(None, "Reset") {
                     charges.clear();
-                    field_lines.clear();
-                    field_line_bounds = 0.0;
-                    field_lines_dirty = false;
+                    config_changed = true;
+                    simulation  = None;
+                    sim_running = false;
+                }
+
+                // ── Dynamics controls ─────────────────────────────────────────
+                ui.separator(); ui.separator();
+                ui.label(None, "** Dynamics (velocity Verlet):");
+                ui.input_text(hash!(), "Time step (in s)", &mut time_step_string);
+                if ui.button(None, "Set dt") {
+                    match time_step_string.parse::<f64>() {
+                        Ok(dt) if dt.is_finite() && dt > 0.0 => time_step = dt,
+                        _ => { err_text = String::from("Time step must be a positive number"); show_error = true; }
+                    }
+                }
+                if dynamics_allowed {
+                    let play_label = if sim_running { "Pause" } else { "Play" };
+                    if ui.button(None, play_label) {
+                        sim_running = !sim_running;
+                    }
+                    ui.same_line(0.0);
+                    if ui.button(None, "Step") {
+                        sim_running   = false;
+                        sim_step_once = true;
+                    }
+                    ui.same_line(0.0);
+                } else {
+                    ui.label(None, "Not available with the grid solver or dielectrics");
+                }
+                if ui.button(None, "Restart Drift") {
+                    simulation = None;
+                }
+                ui.input_text(hash!(), "Charge #", &mut pin_index);
+                if ui.button(None, "Toggle Pin") {
+                    match pin_index.trim().parse::<usize>() {
+                        Ok(i) if (1..=charges.len()).contains(&i) => {
+                            let kin = &mut charges[i - 1].kinematics;
+                            kin.pinned = !kin.pinned;
+                            simulation = None;
+                        }
+                        _ => { err_text = String::from("No charge with that number"); show_error = true; }
+                    }
                 }
 
                 // ── Field line toggle ─────────────────────────────────────────
@@ -382,16 +1439,548 @@ async fn main() {
                     field_lines_dirty = true; // recompute (or clear) next frame
                     if !show_field_lines {
                         field_lines.clear();
+                        field_line_stats.clear();
+                    }
+                }
+                let seeding_before = seeding_index;
+                ui.combo_box(hash!(), "Lines per charge", &FIELD_LINE_SEEDINGS, &mut seeding_index);
+                if seeding_index != seeding_before {
+                    field_line_settings.seeding = if seeding_index == 1 { Seeding::PerCharge } else { Seeding::Flux };
+                    field_lines_dirty = true;
+                }
+                let integrator_before = integrator_index;
+                ui.combo_box(hash!(), "Integrator", &FIELD_LINE_INTEGRATORS, &mut integrator_index);
+                if integrator_index != integrator_before {
+                    field_line_settings.integrator = if integrator_index == 1 { Integrator::Adaptive } else { Integrator::Rk4 };
+                    field_lines_dirty = true;
+                }
+                if integrator_index == 1 {
+                    ui.input_text(hash!(), "Tolerance (in m)", &mut tolerance_string);
+                    if ui.button(None, "apply tolerance") {
+                        match tolerance_string.trim().parse::<f64>() {
+                            Ok(t) if t.is_finite() && t > 0.0 => {
+                                field_line_settings.tolerance = t;
+                                field_lines_dirty = true;
+                            }
+                            _ => { err_text = String::from("Tolerance must be a positive number"); show_error = true; }
+                        }
+                    }
+                }
+                if show_field_lines && !field_line_stats.is_empty() {
+                    ui.label(None, &line_stats_summary(&field_line_stats));
+                }
+                let arrows_before = show_line_arrows;
+                ui.checkbox(hash!(), "Direction arrows", &mut show_line_arrows);
+                if show_line_arrows && !arrows_before {
+                    line_arrows_dirty = true;
+                }
+                if show_line_arrows {
+                    ui.input_text(hash!(), "Arrow spacing (in m)", &mut arrow_spacing_string);
+                    ui.input_text(hash!(), "Arrow size (in m)", &mut arrow_size_string);
+                    if ui.button(None, "apply arrows") {
+                        let positive = |s: &str| s.trim().parse::<f64>().ok().filter(|x| x.is_finite() && *x > 0.0);
+                        match (positive(&arrow_spacing_string), positive(&arrow_size_string)) {
+                            (Some(spacing), Some(size)) => {
+                                arrow_spacing     = spacing;
+                                arrow_size        = size as f32;
+                                line_arrows_dirty = true;
+                            }
+                            _ => { err_text = String::from("Arrow spacing and size must be positive numbers"); show_error = true; }
+                        }
+                    }
+                }
+                let before = (line_color_index, line_colormap_index, line_color_log);
+                ui.combo_box(hash!(), "Colour by", &LINE_COLORINGS, &mut line_color_index);
+                if line_color_index > 0 {
+                    ui.combo_box(hash!(), "Colour map", &COLORMAPS, &mut line_colormap_index);
+                    ui.checkbox(hash!(), "Log colour scale", &mut line_color_log);
+                }
+                if before != (line_color_index, line_colormap_index, line_color_log) {
+                    line_scalars_dirty = true;
+                }
+                ui.label(None, "Extra seeds (traced both ways):");
+                ui.combo_box(hash!(), "Seeds", &SEED_KINDS, &mut seed_kind);
+                ui.input_text(hash!(), if seed_kind == 0 { "A (position)" } else { "A (centre)" }, &mut seed_a);
+                if seed_kind > 0 {
+                    ui.input_text(hash!(), "B (normal)", &mut seed_b);
+                    ui.input_text(hash!(), ["", "Size (w,h in m)", "Radius (in m)"][seed_kind], &mut seed_size);
+                    ui.input_text(hash!(), ["", "Count (rows,columns)", "Count"][seed_kind], &mut seed_count);
+                }
+                if ui.button(None, "add seeds") {
+                    // A point needs no size or count
+                    let (size, count) = if seed_kind == 0 { ("1", "1") } else { (seed_size.as_str(), seed_count.as_str()) };
+                    match parse_seed_shape(seed_kind, &seed_a, &seed_b, size, count) {
+                        Err(e) => { err_text = e; show_error = true; }
+                        Ok(shape) => {
+                            seed_shapes.push(shape);
+                            field_lines_dirty = true;
+                        }
+                    }
+                }
+                ui.checkbox(hash!(), "Right-click the view to seed", &mut seed_by_click);
+                for (idx, shape) in seed_shapes.iter().enumerate() {
+                    ui.label(None, &format!("{}- {}", idx + 1, describe_seed_shape(shape)));
+                }
+                if !seed_shapes.is_empty() && ui.button(None, "Clear Seeds") {
+                    seed_shapes.clear();
+                    field_lines_dirty = true;
+                }
+
+                ui.label(None, "** Field Lines (B):");
+                if currents.is_empty() {
+                    ui.label(None, "No current has been defined yet.");
+                } else {
+                    let bl_label = if show_b_lines { "[ ON] Hide B Lines" } else { "[OFF] Show B Lines" };
+                    if ui.button(None, bl_label) {
+                        show_b_lines  = !show_b_lines;
+                        b_lines_dirty = true;
+                        if !show_b_lines {
+                            b_lines.clear();
+                        }
+                    }
+                }
+
+                // ── Force arrow toggle ────────────────────────────────────────
+                ui.separator(); ui.separator();
+                ui.label(None, "** Coulomb Forces (F):");
+                let f_label = if show_forces { "[ ON] Hide Force Arrows" }
+                else          { "[OFF] Show Force Arrows" };
+                if ui.button(None, f_label) {
+                    show_forces = !show_forces;
+                }
+
+                // ── Scene file ────────────────────────────────────────────────
+                ui.separator(); ui.separator();
+                ui.label(None, "** Scene File:");
+                ui.input_text(hash!(), "Path", &mut scene_path);
+                if ui.button(None, "Save") {
+                    let scene = Scene {
+                        distributions: distributions.clone(),
+                        conductors: conductors.clone(),
+                        grid_solver: (field_backend_index == 3).then(|| grid_solver.clone()),
+                        dielectrics: dielectrics.clone(),
+                        currents: currents.clone(),
+                        particles: particles.clone(),
+                        trajectories: trajectory_settings.clone(),
+                        seeds: seed_shapes.clone(),
+                        ..scene_from_sprites(&charges, reference, show_field_lines, &field_line_settings)
+                    };
+                    if let Err(e) = scene.save(&scene_path) {
+                        err_text = e.to_string();
+                        show_error = true;
+                    }
+                }
+                ui.same_line(0.0);
+                if ui.button(None, "Load") {
+                    pending_scene = Some(std::path::PathBuf::from(&scene_path));
+                }
+                ui.same_line(0.0);
+                // Written next to the scene file: <scene>_field_lines.vtk, _charges.vtk, _fields.vtk
+                if ui.button(None, "Export VTK") {
+                    let prefix = std::path::Path::new(&scene_path).with_extension("");
+                    let bounds = bounding_half_size(&sources) as f64;
+                    if let Err(e) = vtk::export(prefix, &config, &sources, &field_lines, bounds, equipotential_resolution) {
+                        err_text = format!("VTK export failed: {e}");
+                        show_error = true;
+                    }
+                }
+            },
+        );
+
+        root_ui().label(vec2(1065., 392.), "Continuous Sources");
+        root_ui().window(
+            hash!(),
+            vec2(screen_width() / 2.0 + WINDOW.0 as f32 / 3.5 - 50.0, 412.0),
+            vec2(WINDOW.0 as f32 / 5.0 + 50.0, WINDOW.1 as f32 - 432.0),
+            |ui| {
+                ui.combo_box(hash!(), "Kind", &DISTRIBUTION_KINDS, &mut dist_kind);
+                ui.label(None, DISTRIBUTION_HELP[dist_kind]);
+                ui.input_text(hash!(), "A",       &mut dist_a);
+                ui.input_text(hash!(), "B",       &mut dist_b);
+                ui.input_text(hash!(), "Size (in m)", &mut dist_size);
+                ui.input_text(hash!(), "Density", &mut dist_density);
+                if DISTRIBUTION_KINDS[dist_kind] == "Sphere" {
+                    ui.checkbox(hash!(), "Solid (ball)", &mut dist_solid);
+                }
+
+                if ui.button(None, "add source") {
+                    match parse_distribution(dist_kind, &dist_a, &dist_b, &dist_size, &dist_density, dist_solid) {
+                        Err(e) => { err_text = e; show_error = true; }
+                        Ok(dist) => {
+                            distributions.push(dist);
+                            config_changed = true;
+                        }
+                    }
+                }
+
+                ui.separator(); ui.separator();
+                ui.label(None, "** Sources:");
+                for (idx, dist) in distributions.iter().enumerate() {
+                    ui.label(None, &format!("{}- {}", idx + 1, describe_distribution(dist)));
+                }
+                if distributions.is_empty() {
+                    ui.label(None, "No source has been defined yet.");
+                } else if ui.button(None, "Clear Sources") {
+                    distributions.clear();
+                    config_changed = true;
+                }
+
+                // ── Conductors ────────────────────────────────────────────────
+                ui.separator(); ui.separator();
+                ui.label(None, "** Conductors (method of images):");
+                ui.combo_box(hash!(), "Conductor", &CONDUCTOR_KINDS, &mut cond_kind);
+                ui.input_text(hash!(), if cond_kind == 0 { "Point" } else { "Centre" }, &mut cond_point);
+                if cond_kind == 0 {
+                    ui.input_text(hash!(), "Normal (towards charges)", &mut cond_normal);
+                } else {
+                    ui.input_text(hash!(), "Radius (in m)", &mut cond_radius);
+                }
+                if cond_kind == 2 {
+                    ui.input_text(hash!(), "Charge (in C)", &mut cond_charge);
+                }
+                if ui.button(None, "add conductor") {
+                    match parse_conductor(cond_kind, &cond_point, &cond_normal, &cond_radius, &cond_charge) {
+                        Err(e) => { err_text = e; show_error = true; }
+                        Ok(k) => {
+                            conductors.push(k);
+                            config_changed = true;
+                        }
+                    }
+                }
+                for (idx, k) in conductors.iter().enumerate() {
+                    ui.label(None, &format!("{}- {}", idx + 1, describe_conductor(k)));
+                }
+                if conductors.is_empty() {
+                    ui.label(None, "No conductor has been defined yet.");
+                } else {
+                    ui.label(None, &format!("{} image charges", sources.images.images().len()));
+                    ui.checkbox(hash!(), "Show image charges", &mut show_images);
+                    let was_colored = color_conductors;
+                    ui.checkbox(hash!(), "Colour by surface charge", &mut color_conductors);
+                    if color_conductors && !was_colored {
+                        surface_charges_dirty = true;
+                    }
+                    if ui.button(None, "Clear Conductors") {
+                        conductors.clear();
+                        config_changed = true;
+                    }
+                }
+
+                // ── Currents ──────────────────────────────────────────────────
+                ui.separator(); ui.separator();
+                ui.label(None, "** Currents (magnetostatics):");
+                ui.combo_box(hash!(), "Current", &CURRENT_KINDS, &mut cur_kind);
+                ui.input_text(hash!(), if cur_kind == 0 { "A (start)" } else { "A (centre)" }, &mut cur_a);
+                ui.input_text(hash!(), ["B (end)", "B (normal)", "B (axis)"][cur_kind], &mut cur_b);
+                if cur_kind > 0 {
+                    ui.input_text(hash!(), "Radius (in m)", &mut cur_radius);
+                }
+                if cur_kind == 2 {
+                    ui.input_text(hash!(), "Length (in m)", &mut cur_length);
+                    ui.input_text(hash!(), "Turns", &mut cur_turns);
+                }
+                ui.input_text(hash!(), "Current (in A)", &mut cur_current);
+                if ui.button(None, "add current") {
+                    match parse_current(cur_kind, &cur_a, &cur_b, &cur_radius, &cur_length, &cur_turns, &cur_current) {
+                        Err(e) => { err_text = e; show_error = true; }
+                        Ok(c) => {
+                            currents.push(c);
+                            b_lines_dirty  = true;
+                            config_changed = true;
+                        }
+                    }
+                }
+                for (idx, c) in currents.iter().enumerate() {
+                    ui.label(None, &format!("{}- {}", idx + 1, describe_current(c)));
+                }
+                if !currents.is_empty() {
+                    let (mx, my, mz) = magnetic_dipole_moment(&currents).components();
+                    ui.label(None, &format!("m = ({mx:.2e}, {my:.2e}, {mz:.2e}) A·m²"));
+                    if ui.button(None, "Clear Currents") {
+                        currents.clear();
+                        b_lines.clear();
+                        b_lines_dirty  = true;
+                        config_changed = true;
+                    }
+                }
+
+                // ── Test particles ────────────────────────────────────────────
+                ui.separator(); ui.separator();
+                ui.label(None, "** Test Particles (Lorentz force):");
+                ui.input_text(hash!(), "Charge (in C)", &mut part_charge);
+                ui.input_text(hash!(), "Mass (in kg)", &mut part_mass);
+                ui.input_text(hash!(), "Position", &mut part_position);
+                ui.input_text(hash!(), "Velocity (in m/s)", &mut part_velocity);
+                if ui.button(None, "launch particle") {
+                    match parse_particle(&part_charge, &part_mass, &part_position, &part_velocity) {
+                        Err(e) => { err_text = e; show_error = true; }
+                        Ok(p) => {
+                            particles.push(p);
+                            trajectories_dirty = true;
+                        }
+                    }
+                }
+                ui.input_text(hash!(), "Duration (in s)", &mut duration_string);
+                if ui.button(None, "apply duration") {
+                    match duration_string.trim().parse::<f64>() {
+                        Ok(t) if t.is_finite() && t > 0.0 => {
+                            trajectory_settings.duration = t;
+                            trajectories_dirty = true;
+                        }
+                        _ => { err_text = String::from("Duration must be a positive number"); show_error = true; }
+                    }
+                }
+                for (idx, tr) in trajectories.iter().enumerate() {
+                    ui.label(None, &format!("{}- {} after {:.3e} s", idx + 1, tr.end.describe(), tr.times.last().unwrap()));
+                }
+                if !particles.is_empty() {
+                    let tr_label = if show_trajectories { "[ ON] Hide Trajectories" } else { "[OFF] Show Trajectories" };
+                    if ui.button(None, tr_label) {
+                        show_trajectories  = !show_trajectories;
+                        trajectories_dirty = true;
+                        if !show_trajectories {
+                            trajectories.clear();
+                        }
+                    }
+                    if ui.button(None, "Clear Particles") {
+                        particles.clear();
+                        trajectories.clear();
+                    }
+                }
+
+                // ── Dielectrics ───────────────────────────────────────────────
+                ui.separator(); ui.separator();
+                ui.label(None, "** Dielectrics:");
+                ui.combo_box(hash!(), "Dielectric", &DIELECTRIC_KINDS, &mut diel_kind);
+                ui.input_text(hash!(), ["A (centre)", "A (lower corner)", "A (point)"][diel_kind], &mut diel_a);
+                ui.input_text(hash!(), ["B (radius)", "B (upper corner)", "B (normal, out)"][diel_kind], &mut diel_b);
+                ui.input_text(hash!(), "Relative permittivity", &mut diel_eps);
+                if ui.button(None, "add dielectric") {
+                    match parse_dielectric(diel_kind, &diel_a, &diel_b, &diel_eps) {
+                        Err(e) => { err_text = e; show_error = true; }
+                        Ok(d) => {
+                            dielectrics.push(d);
+                            config_changed = true;
+                        }
+                    }
+                }
+                for (idx, d) in dielectrics.iter().enumerate() {
+                    ui.label(None, &format!("{}- {}", idx + 1, describe_dielectric(d)));
+                }
+                if dielectrics.is_empty() {
+                    ui.label(None, "No dielectric has been defined yet.");
+                } else {
+                    if let Some(screened) = &sources.screened {
+                        ui.label(None, if screened.is_exact() { "Solved exactly" } else { "Solved on the grid" });
+                    }
+                    let was_colored = color_dielectrics;
+                    ui.checkbox(hash!(), "Colour by bound charge", &mut color_dielectrics);
+                    if color_dielectrics && !was_colored {
+                        surface_charges_dirty = true;
+                    }
+                    if ui.button(None, "Clear Dielectrics") {
+                        dielectrics.clear();
+                        config_changed = true;
                     }
                 }
             },
         );
 
+        root_ui().label(vec2(95., 392.), "Visualisation");
+        root_ui().window(
+            hash!(),
+            vec2(20., 412.),
+            vec2(WINDOW.0 as f32 / 5.0, WINDOW.1 as f32 - 432.0),
+            |ui| {
+                // ── Field evaluation ──────────────────────────────────────────
+                ui.label(None, "** Field Evaluation:");
+                let backend_before = field_backend_index;
+                ui.combo_box(hash!(), "Method", &FIELD_BACKENDS, &mut field_backend_index);
+                if field_backend_index == 1 {
+                    ui.input_text(hash!(), "Opening angle (0-1)", &mut theta_string);
+                    if ui.button(None, "Set theta") {
+                        match theta_string.trim().parse::<f64>() {
+                            Ok(t) if (0.0..=1.0).contains(&t) => { theta = t; config_changed = true; }
+                            _ => { err_text = String::from("The opening angle must be between 0 and 1"); show_error = true; }
+                        }
+                    }
+                }
+                if field_backend_index == 2 {
+                    ui.input_text(hash!(), "Expansion order", &mut fmm_order_string);
+                    if ui.button(None, "Set order") {
+                        match fmm_order_string.trim().parse::<usize>() {
+                            Ok(n) if (1..=MAX_FMM_ORDER).contains(&n) => { fmm_order = n; config_changed = true; }
+                            _ => { err_text = format!("The expansion order must be between 1 and {MAX_FMM_ORDER}"); show_error = true; }
+                        }
+                    }
+                }
+                if field_backend_index == 3 {
+                    ui.input_text(hash!(), "Grid points", &mut grid_resolution_string);
+                    ui.combo_box(hash!(), "Boundary", &GRID_BOUNDARIES, &mut grid_boundary_index);
+                    if ui.button(None, "Solve") {
+                        match grid_resolution_string.trim().parse::<usize>() {
+                            Ok(n) if (3..=MAX_GRID_RESOLUTION).contains(&n) => {
+                                grid_solver.resolution = n;
+                                grid_solver.boundary = if grid_boundary_index == 1 {
+                                    Boundary::Neumann
+                                } else {
+                                    Boundary::Dirichlet { potential: 0.0 }
+                                };
+                                config_changed = true;
+                            }
+                            _ => { err_text = format!("Grid points must be between 3 and {MAX_GRID_RESOLUTION}"); show_error = true; }
+                        }
+                    }
+                    if let Some(solution) = &sources.grid {
+                        ui.label(None, &format!("{} SOR sweeps, residual {:.1e}", solution.iterations, solution.residual));
+                    }
+                    ui.combo_box(hash!(), "Electrode", &ELECTRODE_KINDS, &mut electrode_kind);
+                    ui.input_text(hash!(), if electrode_kind == 0 { "A = centre" } else { "A = min corner" }, &mut electrode_a);
+                    ui.input_text(hash!(), if electrode_kind == 0 { "B = radius" } else { "B = max corner" }, &mut electrode_b);
+                    ui.input_text(hash!(), "Potential (V)", &mut electrode_potential);
+                    if ui.button(None, "add electrode") {
+                        match parse_electrode(electrode_kind, &electrode_a, &electrode_b, &electrode_potential) {
+                            Err(e) => { err_text = e; show_error = true; }
+                            Ok(e) => {
+                                grid_solver.electrodes.push(e);
+                                config_changed = true;
+                            }
+                        }
+                    }
+                    if !grid_solver.electrodes.is_empty() {
+                        ui.same_line(0.0);
+                        if ui.button(None, "Clear Electrodes") {
+                            grid_solver.electrodes.clear();
+                            config_changed = true;
+                        }
+                    }
+                }
+                if backend_before != field_backend_index {
+                    config_changed = true;
+                }
+                field_backend = match field_backend_index {
+                    1 => FieldBackend::BarnesHut { theta },
+                    2 => FieldBackend::Fmm { order: fmm_order },
+                    _ => FieldBackend::Direct,
+                };
+
+                ui.separator(); ui.separator();
+                ui.label(None, "** Equipotential Surfaces (marching cubes):");
+                let eq_label = if show_equipotentials { "[ ON] Hide Equipotentials" }
+                else                 { "[OFF] Show Equipotentials" };
+                if ui.button(None, eq_label) {
+                    show_equipotentials  = !show_equipotentials;
+                    equipotentials_dirty = true;
+                }
+                ui.input_text(hash!(), "Levels (V)", &mut equipotential_levels_string);
+                ui.input_text(hash!(), "Grid points", &mut equipotential_resolution_string);
+                if ui.button(None, "Apply") {
+                    let levels: Result<Vec<f64>, _> = equipotential_levels_string
+                        .split(',')
+                        .filter(|s| !s.trim().is_empty())
+                        .map(|s| s.trim().parse::<f64>())
+                        .collect();
+                    match (levels, equipotential_resolution_string.trim().parse::<usize>()) {
+                        (Ok(l), Ok(n)) if (2..=128).contains(&n) && l.iter().all(|v| v.is_finite()) => {
+                            equipotential_levels     = l;
+                            equipotential_resolution = n;
+                            equipotentials_dirty     = true;
+                        }
+                        (Err(_), _) => { err_text = String::from("Levels must be comma-separated numbers"); show_error = true; }
+                        _ => { err_text = String::from("Grid points must be between 2 and 128"); show_error = true; }
+                    }
+                }
+
+                // ── Multipole validity ────────────────────────────────────────
+                ui.separator(); ui.separator();
+                ui.label(None, "** Multipole Approximation (about r'):");
+                let before = color_lines_by_error;
+                ui.checkbox(hash!(), "Colour field lines by error", &mut color_lines_by_error);
+                if before != color_lines_by_error {
+                    line_errors_dirty = true;
+                }
+
+                // ── Vector glyphs ─────────────────────────────────────────────
+                ui.separator(); ui.separator();
+                ui.label(None, "** Vector Glyphs (quiver plot of E):");
+                let glyph_label = if show_glyphs { "[ ON] Hide Glyphs" } else { "[OFF] Show Glyphs" };
+                if ui.button(None, glyph_label) {
+                    show_glyphs  = !show_glyphs;
+                    glyphs_dirty = true;
+                }
+                let before = (glyph_domain, glyph_log);
+                ui.combo_box(hash!(), "Sample on", &GLYPH_DOMAINS, &mut glyph_domain);
+                ui.checkbox(hash!(), "Log arrow length", &mut glyph_log);
+                if before != (glyph_domain, glyph_log) {
+                    glyphs_dirty = true;
+                }
+                ui.input_text(hash!(), "Points per edge", &mut glyph_resolution_string);
+                ui.input_text(hash!(), "Longest arrow (in m)", &mut glyph_length_string);
+                if ui.button(None, "apply glyphs") {
+                    match (glyph_resolution_string.trim().parse::<usize>(), glyph_length_string.trim().parse::<f64>()) {
+                        (Ok(n), Ok(l)) if (2..=32).contains(&n) && l.is_finite() && l > 0.0 => {
+                            glyph_resolution = n;
+                            glyph_length     = l;
+                            glyphs_dirty     = true;
+                        }
+                        _ => { err_text = String::from("Points per edge must be between 2 and 32, the length positive"); show_error = true; }
+                    }
+                }
+
+                // ── Slice plane ───────────────────────────────────────────────
+                ui.separator(); ui.separator();
+                ui.label(None, "** Slice Plane (heatmap):");
+                let slice_label = if show_slice { "[ ON] Hide Slice" } else { "[OFF] Show Slice" };
+                if ui.button(None, slice_label) {
+                    show_slice  = !show_slice;
+                    slice_dirty = true;
+                }
+                let before = (slice_orientation, slice_quantity, slice_log);
+                ui.combo_box(hash!(), "Plane", &SLICE_ORIENTATIONS, &mut slice_orientation);
+                ui.combo_box(hash!(), "Show", &SLICE_QUANTITIES, &mut slice_quantity);
+                ui.checkbox(hash!(), "Log scale", &mut slice_log);
+                if before != (slice_orientation, slice_quantity, slice_log) {
+                    slice_dirty = true;
+                }
+                if slice_orientation == 3 {
+                    ui.input_text(hash!(), "Normal", &mut slice_normal_string);
+                }
+                ui.input_text(hash!(), "Offset (m)", &mut slice_offset_string);
+                ui.input_text(hash!(), "Samples per side", &mut slice_resolution_string);
+                if ui.button(None, "Apply ") {
+                    let normal = string_to_tuple(&slice_normal_string);
+                    match (normal, slice_offset_string.trim().parse::<f64>(), slice_resolution_string.trim().parse::<usize>()) {
+                        (Ok((x, y, z)), Ok(o), Ok(n)) if (2..=512).contains(&n) && o.is_finite() && (x, y, z) != (0.0, 0.0, 0.0) => {
+                            slice_normal     = widen((x, y, z));
+                            slice_offset     = o;
+                            slice_resolution = n;
+                            slice_dirty      = true;
+                        }
+                        (Err(e), _, _) => { err_text = String::from(e); show_error = true; }
+                        (_, Ok(_), Ok(_)) => { err_text = String::from("The slice normal must not be zero"); show_error = true; }
+                        _ => { err_text = String::from("Offset must be a number and samples between 2 and 512"); show_error = true; }
+                    }
+                }
+                ui.same_line(0.0);
+                if ui.button(None, "-") {
+                    slice_offset       -= 0.25;
+                    slice_offset_string = format!("{slice_offset}");
+                    slice_dirty         = true;
+                }
+                ui.same_line(0.0);
+                if ui.button(None, "+") {
+                    slice_offset       += 0.25;
+                    slice_offset_string = format!("{slice_offset}");
+                    slice_dirty         = true;
+                }
+            },
+        );
+
         root_ui().label(vec2(75., 20.), "Calculations & Properties");
         root_ui().window(
             hash!(),
             vec2(20., 40.),
-            vec2(WINDOW.0 as f32 / 5.0, WINDOW.1 as f32 / 1.35),
+            vec2(WINDOW.0 as f32 / 5.0, WINDOW.1 as f32 / 2.0 - 20.0),
             |ui| {
                 ui.label(None, "** Multipole Moments:");
                 ui.editbox(hash!(), vec2(WINDOW.0 as f32 / 5.0 - 6., 220.), &mut p);
@@ -400,7 +1989,33 @@ async fn main() {
                 ui.editbox(hash!(), vec2(WINDOW.0 as f32 / 5.0 - 6., 50.), &mut phi);
                 ui.separator(); ui.separator();
                 ui.label(None, "** Electric Field (N/C):");
-                ui.editbox(hash!(), vec2(WINDOW.0 as f32 / 5.0 - 6., 90.), &mut e);
+                ui.editbox(hash!(), vec2(WINDOW.0 as f32 / 5.0 - 6., if dielectrics.is_empty() { 90. } else { 200. }), &mut e);
+                if !currents.is_empty() {
+                    ui.separator(); ui.separator();
+                    ui.label(None, "** Magnetic Field (T):");
+                    ui.editbox(hash!(), vec2(WINDOW.0 as f32 / 5.0 - 6., 90.), &mut b);
+                }
+                ui.separator(); ui.separator();
+                ui.label(None, "** Forces on Charges (N):");
+                ui.editbox(hash!(), vec2(WINDOW.0 as f32 / 5.0 - 6., 120.), &mut f);
+                ui.separator(); ui.separator();
+                ui.label(None, "** Potential Energy (J):");
+                ui.editbox(hash!(), vec2(WINDOW.0 as f32 / 5.0 - 6., 90.), &mut u);
+                ui.separator(); ui.separator();
+                ui.label(None, "** Dynamics & Drift:");
+                ui.editbox(hash!(), vec2(WINDOW.0 as f32 / 5.0 - 6., 150.), &mut d);
+                ui.separator(); ui.separator();
+                ui.label(None, "** Spherical Multipoles vs exact Phi at r':");
+                ui.editbox(hash!(), vec2(WINDOW.0 as f32 / 5.0 - 6., 220.), &mut sph);
+                ui.input_text(hash!(), "l_max", &mut sph_l_max_string);
+                ui.input_text(hash!(), "Centre c", &mut sph_center_string);
+                if ui.button(None, "Expand") {
+                    match (sph_l_max_string.trim().parse::<usize>(), string_to_tuple(&sph_center_string)) {
+                        (Ok(l), Ok(c)) if l <= 30 => { sph_l_max = l; sph_center = c; }
+                        (_, Err(e)) => { err_text = String::from(e); show_error = true; }
+                        _ => { err_text = String::from("l_max must be between 0 and 30"); show_error = true; }
+                    }
+                }
                 ui.separator(); ui.separator();
             },
         );
@@ -408,12 +2023,29 @@ async fn main() {
         // ── Overlays ──────────────────────────────────────────────────────────
         set_default_camera();
         root_ui().label(vec2((WINDOW.0 / 2) as f32 - 60.0, 20.0), "Coulomb3D (v0.1.1)");
+        if show_slice {
+            if let Some((grid, scale, _)) = &slice {
+                draw_color_bar(WINDOW.0 as f32 / 5.0 + 40.0, 440.0, 18.0, 220.0, scale, grid.quantity.label());
+            }
+        }
+        if (color_conductors && !surface_charges.is_empty()) || (color_dielectrics && !bound_charges.is_empty()) {
+            draw_color_bar(WINDOW.0 as f32 / 5.0 + 40.0, 60.0, 18.0, 220.0, &surface_scale, "sigma [C/m^2]");
+        }
+        if show_field_lines && color_lines_by_error {
+            draw_color_bar(WINDOW.0 as f32 / 5.0 + 150.0, 440.0, 18.0, 220.0, &multipole_error_scale(), "lines |dE|/|E|");
+        } else if let Some(scale) = line_scale.filter(|_| show_field_lines && line_color_index > 0) {
+            let title = format!("lines {}", LINE_QUANTITIES[line_color_index - 1].label());
+            draw_color_bar(WINDOW.0 as f32 / 5.0 + 150.0, 440.0, 18.0, 220.0, &scale, &title);
+        }
+        if let Some(scale) = glyph_scale.filter(|_| show_glyphs) {
+            draw_color_bar(WINDOW.0 as f32 / 5.0 + 150.0, 60.0, 18.0, 220.0, &scale, "glyphs |E| (N/C)");
+        }
 
         if show_error {
             error_dialog.show(&format!("{}:\nplease check your input.", err_text));
             show_error = false;
         }
-        if error_dialog.update() {}
+        error_dialog.update();
         error_dialog.draw();
 
         next_frame().await
@@ -0,0 +1,470 @@
+/*
+Render-side helpers. The physics core (the `coulomb3d` crate) knows nothing about colours or
+spheres; everything that only matters for drawing the scene lives here.
+*/
+use coulomb3d::scene::{widen, Scene, SceneCharge, SceneFieldLines, SCENE_VERSION};
+use coulomb3d::{
+    Charge, ColorScale, Conductor, Current, Dielectric, Distribution, Electrode, FieldLineSettings, Kinematics, SliceGrid,
+    SurfaceCharge, Triangle,
+};
+use macroquad::prelude::*;
+use ::rand::Rng;
+
+/// A physical charge together with the colour it is drawn in
+/// and the mass/velocity it has when the dynamics simulation runs.
+#[derive(Debug, Clone)]
+pub struct ChargeSprite {
+    pub charge: Charge,
+    pub color: Color,
+    pub kinematics: Kinematics,
+}
+
+impl ChargeSprite {
+    pub fn new(charge: Charge, color: Color) -> Self {
+        Self { charge, color, kinematics: Kinematics::default() }
+    }
+}
+
+/// Strip the render data off a list of sprites, leaving the bare configuration for the solver.
+pub fn configuration(sprites: &[ChargeSprite]) -> Vec<Charge> {
+    sprites.iter().map(|s| s.charge.clone()).collect()
+}
+
+/// The mass, velocity and pin state of every sprite, in configuration order.
+pub fn kinematics(sprites: &[ChargeSprite]) -> Vec<Kinematics> {
+    sprites.iter().map(|s| s.kinematics.clone()).collect()
+}
+
+/// Build sprites from a loaded scene; charges saved without a colour get a random one.
+pub fn sprites_from_scene(scene: &Scene) -> Vec<ChargeSprite> {
+    scene
+        .configuration()
+        .into_iter()
+        .zip(&scene.charges)
+        .zip(scene.kinematics())
+        .map(|((charge, sc), kinematics)| {
+            let color = sc.color.map_or_else(generate_random_rgba, |[r, g, b, a]| Color::new(r, g, b, a));
+            ChargeSprite { charge, color, kinematics }
+        })
+        .collect()
+}
+
+/// Snapshot the charges and view settings as a scene that can be written to disk; the caller
+/// adds the other sources (distributions, conductors, …).
+pub fn scene_from_sprites(
+    sprites: &[ChargeSprite],
+    reference: (f32, f32, f32),
+    show_field_lines: bool,
+    settings: &FieldLineSettings,
+) -> Scene {
+    Scene {
+        version: SCENE_VERSION,
+        reference: widen(reference),
+        field_lines: SceneFieldLines { show: show_field_lines, settings: settings.clone() },
+        charges: sprites
+            .iter()
+            .map(|s| SceneCharge {
+                charge: s.charge.charge,
+                position: widen(s.charge.position),
+                color: Some([s.color.r, s.color.g, s.color.b, s.color.a]),
+                mass: Some(s.kinematics.mass),
+                velocity: Some(<[f64; 3]>::from(s.kinematics.velocity)),
+                pinned: s.kinematics.pinned,
+            })
+            .collect(),
+        ..Scene::default()
+    }
+}
+
+pub fn generate_random_rgba() -> Color {
+    let mut rng = ::rand::rng();
+    let r = rng.random_range(0.0..=1.0);
+    let g = rng.random_range(0.0..=1.0);
+    let b = rng.random_range(0.0..=1.0);
+    let a = rng.random_range(0.5..=1.0);
+    Color::new(r, g, b, a)
+}
+
+pub fn charge_to_radius(charge: f64) -> f64 {
+    let min_old = 1e-9;
+    let max_old = 1.0;
+    let min_new = 0.1;
+    let max_new = 1f64;
+
+    let charge = (charge).abs().clamp(min_old, max_old);
+    let log_charge = (charge / min_old).ln();
+    let log_max   = (max_old / min_old).ln();
+
+    min_new + (log_charge / log_max) * (max_new - min_new)
+}
+
+// ── Bounding cube wireframe ───────────────────────────────────────────────────
+
+/// Draw the 12 edges of an axis-aligned cube centred at the origin with half-size `b`.
+pub fn draw_bounds_cube(b: f32, col: Color) {
+    let c = [
+        vec3(-b, -b, -b), vec3( b, -b, -b), vec3( b,  b, -b), vec3(-b,  b, -b),
+        vec3(-b, -b,  b), vec3( b, -b,  b), vec3( b,  b,  b), vec3(-b,  b,  b),
+    ];
+    let edges: [(usize, usize); 12] = [
+        (0,1),(1,2),(2,3),(3,0), // bottom face
+        (4,5),(5,6),(6,7),(7,4), // top face
+        (0,4),(1,5),(2,6),(3,7), // verticals
+    ];
+    for (i, j) in edges {
+        draw_line_3d(c[i], c[j], col);
+    }
+}
+
+// ── Arrows ────────────────────────────────────────────────────────────────────
+
+/// Draw a 3-D arrow from `from` to `to`; the head is four barbs `head` long.
+pub fn draw_arrow_3d(from: Vec3, to: Vec3, head: f32, col: Color) {
+    let d = to - from;
+    let len = d.length();
+    if len < 1e-6 {
+        return;
+    }
+    draw_line_3d(from, to, col);
+
+    // Two unit vectors perpendicular to the shaft span the barbs
+    let dir = d / len;
+    let (u, v) = perpendiculars(dir);
+    let head = head.min(len * 0.5);
+    let base = to - dir * head;
+    for side in [u, -u, v, -v] {
+        draw_line_3d(to, base + side * head * 0.4, col);
+    }
+}
+
+/// Draw a solid cone `length` long centred on `at`, its tip pointing along the unit vector `dir`.
+pub fn draw_cone(at: Vec3, dir: Vec3, length: f32, radius: f32, col: Color) {
+    const SIDES: usize = 8;
+    let (u, v) = perpendiculars(dir);
+    let (base, tip) = (at - dir * (0.5 * length), at + dir * (0.5 * length));
+    let rim = |k: usize| {
+        let t = std::f32::consts::TAU * k as f32 / SIDES as f32;
+        base + (u * t.cos() + v * t.sin()) * radius
+    };
+    let triangles: Vec<[Vec3; 3]> = (0..SIDES)
+        .flat_map(|k| [[tip, rim(k), rim(k + 1)], [base, rim(k + 1), rim(k)]])
+        .collect();
+    draw_triangles(&triangles, col);
+}
+
+// ── Meshes ────────────────────────────────────────────────────────────────────
+
+/// Triangles per `draw_mesh` call, keeping each call under macroquad's per-draw index limit.
+const TRIANGLES_PER_MESH: usize = 1500;
+
+/// Draw a triangle soup in a single colour, split into as many meshes as needed.
+pub fn draw_triangles(triangles: &[[Vec3; 3]], col: Color) {
+    for chunk in triangles.chunks(TRIANGLES_PER_MESH) {
+        let vertices = chunk
+            .iter()
+            .flatten()
+            .map(|p| Vertex::new(p.x, p.y, p.z, 0.0, 0.0, col))
+            .collect();
+        let indices = (0..chunk.len() as u16 * 3).collect();
+        draw_mesh(&Mesh { vertices, indices, texture: None });
+    }
+}
+
+/// Draw a triangle soup with simple Lambert shading: each triangle is
+/// darkened by the angle between its normal and a fixed light direction, which is
+/// enough to read the shape of an isosurface.
+pub fn draw_shaded_triangles(triangles: &[Triangle], col: Color) {
+    let light = vec3(0.4, 0.8, 0.45).normalize();
+    for chunk in triangles.chunks(TRIANGLES_PER_MESH) {
+        let mut vertices = Vec::with_capacity(chunk.len() * 3);
+        for [a, b, c] in chunk {
+            let (a, b, c) = (vec3(a.0, a.1, a.2), vec3(b.0, b.1, b.2), vec3(c.0, c.1, c.2));
+            let shade = 0.45 + 0.55 * (b - a).cross(c - a).normalize_or_zero().dot(light).abs();
+            let tint = Color::new(col.r * shade, col.g * shade, col.b * shade, col.a);
+            for p in [a, b, c] {
+                vertices.push(Vertex::new(p.x, p.y, p.z, 0.0, 0.0, tint));
+            }
+        }
+        let indices = (0..chunk.len() as u16 * 3).collect();
+        draw_mesh(&Mesh { vertices, indices, texture: None });
+    }
+}
+
+/// Colour for a potential level: red for positive, blue for negative,
+/// paler for levels closer to zero relative to `v_max`.
+pub fn level_color(level: f64, v_max: f64) -> Color {
+    let t = if v_max > 0.0 { (level.abs() / v_max).clamp(0.0, 1.0) as f32 } else { 1.0 };
+    let fade = 0.75 * (1.0 - t);
+    if level >= 0.0 {
+        Color::new(0.95, 0.2 + fade, 0.15 + fade, 0.35)
+    } else {
+        Color::new(0.15 + fade, 0.35 + fade, 0.95, 0.35)
+    }
+}
+
+/// Upload a slice heatmap as an RGBA texture, one texel per sample. Texel row
+/// j holds the samples at in-plane coordinate b = −1 + 2j/(n − 1).
+pub fn slice_texture(grid: &SliceGrid, scale: &ColorScale) -> Texture2D {
+    let bytes: Vec<u8> = grid
+        .values
+        .iter()
+        .flat_map(|&v| {
+            let [r, g, b, _] = scale.color(v);
+            [r, g, b, 0.85].map(|x| (x * 255.0) as u8)
+        })
+        .collect();
+    let n = grid.resolution as u16;
+    let texture = Texture2D::from_rgba8(n, n, &bytes);
+    texture.set_filter(FilterMode::Linear);
+    texture
+}
+
+/// Draw a slice heatmap as a textured square in the slice plane.
+pub fn draw_slice(grid: &SliceGrid, texture: &Texture2D) {
+    let corner = |a: f64, b: f64| {
+        let (x, y, z) = grid.plane.point(a, b);
+        let (u, v) = ((a as f32 + 1.0) / 2.0, (b as f32 + 1.0) / 2.0);
+        Vertex::new(x, y, z, u, v, WHITE)
+    };
+    let vertices = vec![corner(-1.0, -1.0), corner(1.0, -1.0), corner(1.0, 1.0), corner(-1.0, 1.0)];
+    draw_mesh(&Mesh { vertices, indices: vec![0, 1, 2, 0, 2, 3], texture: Some(texture.clone()) });
+}
+
+/// Two unit vectors perpendicular to `n` (and to each other).
+fn perpendiculars(n: Vec3) -> (Vec3, Vec3) {
+    let helper = if n.x.abs() < 0.9 { Vec3::X } else { Vec3::Y };
+    let u = n.cross(helper).normalize();
+    (u, n.cross(u))
+}
+
+/// Triangles of a tube of `radius` following `path`; `closed` joins the last point to the first.
+fn tube_triangles(path: &[Vec3], radius: f32, closed: bool) -> Vec<[Vec3; 3]> {
+    const SIDES: usize = 8;
+    let n = path.len();
+    let segments = if closed { n } else { n.saturating_sub(1) };
+    let mut tris = Vec::with_capacity(segments * SIDES * 2);
+    for s in 0..segments {
+        let (a, b) = (path[s], path[(s + 1) % n]);
+        let (u, v) = perpendiculars((b - a).normalize_or_zero());
+        for k in 0..SIDES {
+            let t0 = std::f32::consts::TAU * k as f32 / SIDES as f32;
+            let t1 = std::f32::consts::TAU * (k + 1) as f32 / SIDES as f32;
+            let o0 = (u * t0.cos() + v * t0.sin()) * radius;
+            let o1 = (u * t1.cos() + v * t1.sin()) * radius;
+            tris.push([a + o0, b + o0, b + o1]);
+            tris.push([a + o0, b + o1, a + o1]);
+        }
+    }
+    tris
+}
+
+/// Triangles of a square of half-size `half` centred on `c`, spanned by `u` and `v`.
+fn square_triangles(c: Vec3, u: Vec3, v: Vec3, half: f32) -> [[Vec3; 3]; 2] {
+    let (u, v) = (u * half, v * half);
+    [[c - u - v, c + u - v, c + u + v], [c - u - v, c + u + v, c - u + v]]
+}
+
+fn to_vec3(a: [f64; 3]) -> Vec3 {
+    vec3(a[0] as f32, a[1] as f32, a[2] as f32)
+}
+
+/// Draw a continuous charge distribution as a translucent mesh: red for positive
+/// densities, blue for negative. Infinite planes and slabs are cut off at `bounds`.
+pub fn draw_distribution(d: &Distribution, bounds: f32) {
+    const TUBE: f32 = 0.03;
+    let positive = d.total_charge().map_or_else(
+        || matches!(*d, Distribution::Plane { sigma, .. } if sigma >= 0.0)
+            || matches!(*d, Distribution::Slab { rho, .. } if rho >= 0.0),
+        |q| q >= 0.0,
+    );
+    let col = if positive { Color::new(0.9, 0.25, 0.2, 0.45) } else { Color::new(0.2, 0.4, 0.95, 0.45) };
+
+    match *d {
+        Distribution::Line { start, end, .. } => {
+            draw_triangles(&tube_triangles(&[to_vec3(start), to_vec3(end)], TUBE, false), col);
+        }
+        Distribution::Ring { center, normal, radius, .. } => {
+            let (c, r) = (to_vec3(center), radius as f32);
+            let (u, v) = perpendiculars(to_vec3(normal).normalize());
+            let path: Vec<Vec3> = (0..48)
+                .map(|k| {
+                    let t = std::f32::consts::TAU * k as f32 / 48.0;
+                    c + (u * t.cos() + v * t.sin()) * r
+                })
+                .collect();
+            draw_triangles(&tube_triangles(&path, TUBE, true), col);
+        }
+        Distribution::Disk { center, normal, radius, .. } => {
+            let (c, r) = (to_vec3(center), radius as f32);
+            let (u, v) = perpendiculars(to_vec3(normal).normalize());
+            let rim = |k: usize| {
+                let t = std::f32::consts::TAU * k as f32 / 48.0;
+                c + (u * t.cos() + v * t.sin()) * r
+            };
+            let fan: Vec<[Vec3; 3]> = (0..48).map(|k| [c, rim(k), rim(k + 1)]).collect();
+            draw_triangles(&fan, col);
+        }
+        Distribution::Sphere { center, radius, solid, .. } => {
+            let c = to_vec3(center);
+            draw_sphere(c, radius as f32, None, col);
+            if !solid {
+                draw_sphere_wires(c, radius as f32, None, Color { a: 0.6, ..col });
+            }
+        }
+        Distribution::Plane { point, normal, .. } | Distribution::Slab { point, normal, .. } => {
+            let n = to_vec3(normal).normalize();
+            let foot = n * to_vec3(point).dot(n);
+            let (u, v) = perpendiculars(n);
+            let offsets = match *d {
+                Distribution::Slab { thickness, .. } => vec![-0.5 * thickness as f32, 0.5 * thickness as f32],
+                _ => vec![0.0],
+            };
+            for off in offsets {
+                draw_triangles(&square_triangles(foot + n * off, u, v, bounds), col);
+            }
+        }
+    }
+}
+
+/// Draw a conductor as a translucent grey surface; planes are cut off at `bounds`.
+pub fn draw_conductor(k: &Conductor, bounds: f32) {
+    let col = Color::new(0.55, 0.57, 0.6, 0.45);
+    match *k {
+        Conductor::Plane { point, normal } => {
+            let n = to_vec3(normal).normalize();
+            let (u, v) = perpendiculars(n);
+            draw_triangles(&square_triangles(n * to_vec3(point).dot(n), u, v, bounds), col);
+        }
+        Conductor::Sphere { center, radius, .. } => {
+            draw_sphere(to_vec3(center), radius as f32, None, col);
+            draw_sphere_wires(to_vec3(center), radius as f32, None, Color { a: 0.6, ..col });
+        }
+    }
+}
+
+/// Draw a conductor's surface coloured by its induced charge density, interpolated
+/// across each cell of the sample grid.
+pub fn draw_surface_charge(surface: &SurfaceCharge, scale: &ColorScale) {
+    let n = surface.resolution;
+    let vertex = |i: usize, j: usize| {
+        let (x, y, z) = surface.points[i * n + j];
+        let c = scale.color(surface.sigma[i * n + j]);
+        Vertex::new(x, y, z, 0.0, 0.0, Color { a: 0.8, ..Color::from(c) })
+    };
+    let cells: Vec<(usize, usize)> = (0..n - 1).flat_map(|i| (0..n - 1).map(move |j| (i, j))).collect();
+    for chunk in cells.chunks(TRIANGLES_PER_MESH / 2) {
+        let mut vertices = Vec::with_capacity(chunk.len() * 4);
+        let mut indices = Vec::with_capacity(chunk.len() * 6);
+        for (k, &(i, j)) in chunk.iter().enumerate() {
+            vertices.extend([vertex(i, j), vertex(i + 1, j), vertex(i + 1, j + 1), vertex(i, j + 1)]);
+            let b = 4 * k as u16;
+            indices.extend([b, b + 1, b + 2, b, b + 2, b + 3]);
+        }
+        draw_mesh(&Mesh { vertices, indices, texture: None });
+    }
+}
+
+/// Draw image charges as ghost spheres: wireframes in pale red or blue, sized like real charges.
+pub fn draw_image_charges(images: &[Charge]) {
+    for q in images {
+        let col = if q.charge >= 0.0 { Color::new(0.9, 0.3, 0.25, 0.35) } else { Color::new(0.25, 0.4, 0.9, 0.35) };
+        let (x, y, z) = q.position;
+        draw_sphere_wires(vec3(x, y, z), charge_to_radius(q.charge) as f32 * 0.5, None, col);
+    }
+}
+
+/// Draw a grid solver electrode, tinted like an equipotential of its potential
+/// relative to `v_max`.
+pub fn draw_electrode(e: &Electrode, v_max: f64) {
+    let col = level_color(e.potential(), v_max);
+    match *e {
+        Electrode::Sphere { center, radius, .. } => draw_sphere(to_vec3(center), radius as f32, None, col),
+        Electrode::Box { min, max, .. } => {
+            let (min, max) = (to_vec3(min), to_vec3(max));
+            draw_cube((min + max) / 2.0, max - min, None, col);
+            draw_cube_wires((min + max) / 2.0, max - min, Color { a: 0.7, ..col });
+        }
+    }
+}
+
+/// Draw a dielectric as pale glass, more opaque for a larger εᵣ; a half-space is drawn as its
+/// surface, a `bounds`-sized square.
+pub fn draw_dielectric(d: &Dielectric, bounds: f32) {
+    let a = (0.12 + 0.04 * d.epsilon_r().ln()).min(0.4) as f32;
+    let col = Color::new(0.55, 0.8, 0.85, a);
+    match *d {
+        Dielectric::Sphere { center, radius, .. } => {
+            draw_sphere(to_vec3(center), radius as f32, None, col);
+            draw_sphere_wires(to_vec3(center), radius as f32, None, Color { a: 0.25, ..col });
+        }
+        Dielectric::Box { min, max, .. } => {
+            let (min, max) = (to_vec3(min), to_vec3(max));
+            draw_cube((min + max) / 2.0, max - min, None, col);
+            draw_cube_wires((min + max) / 2.0, max - min, Color { a: 0.5, ..col });
+        }
+        Dielectric::HalfSpace { point, normal, .. } => {
+            let n = to_vec3(normal).normalize();
+            let (u, v) = perpendiculars(n);
+            draw_triangles(&square_triangles(n * to_vec3(point).dot(n), u, v, bounds), col);
+        }
+    }
+}
+
+/// Where the ray through the screen point `mouse` meets the plane through `through` that faces
+/// the camera; None if the plane lies behind it.
+pub fn pick_point(camera: &Camera3D, mouse: Vec2, through: Vec3) -> Option<Vec3> {
+    let ndc = vec2(2.0 * mouse.x / screen_width() - 1.0, 1.0 - 2.0 * mouse.y / screen_height());
+    let inverse = camera.matrix().inverse();
+    let near = inverse.project_point3(vec3(ndc.x, ndc.y, -1.0));
+    let far = inverse.project_point3(vec3(ndc.x, ndc.y, 1.0));
+    let (dir, normal) = ((far - near).normalize(), (camera.target - camera.position).normalize());
+    let t = (through - near).dot(normal) / dir.dot(normal);
+    (t.is_finite() && t > 0.0).then(|| near + dir * t)
+}
+
+/// Points around a circle of `radius` about `center` in the plane perpendicular to `n`.
+fn circle_points(center: Vec3, n: Vec3, radius: f32, segments: usize) -> Vec<Vec3> {
+    let (u, v) = perpendiculars(n);
+    (0..=segments)
+        .map(|i| {
+            let t = std::f32::consts::TAU * i as f32 / segments as f32;
+            center + (u * t.cos() + v * t.sin()) * radius
+        })
+        .collect()
+}
+
+/// Draw a current element as copper wire, with an arrow along the direction of the current.
+/// Solenoids with many turns are drawn with at most 40 of them.
+pub fn draw_current(c: &Current) {
+    let col = Color::new(0.72, 0.45, 0.2, 1.0);
+    let path = |points: &[Vec3]| {
+        for seg in points.windows(2) {
+            draw_line_3d(seg[0], seg[1], col);
+        }
+    };
+    match *c {
+        Current::Segment { start, end, current } => {
+            let (a, b) = (to_vec3(start), to_vec3(end));
+            path(&[a, b]);
+            let mid = (a + b) / 2.0;
+            let dir = (b - a).normalize() * current.signum() as f32;
+            draw_arrow_3d(mid, mid + dir * 0.6, 0.2, col);
+        }
+        Current::Loop { center, normal: axis, radius, current } | Current::Solenoid { center, axis, radius, current, .. } => {
+            let (n, c0) = (to_vec3(axis).normalize(), to_vec3(center));
+            let (turns, length) = match *c {
+                Current::Solenoid { turns, length, .. } => (turns, length as f32),
+                _ => (1, 0.0),
+            };
+            let drawn = turns.min(40);
+            for i in 0..drawn {
+                let t = if drawn > 1 { i as f32 / (drawn - 1) as f32 - 0.5 } else { 0.0 };
+                path(&circle_points(c0 + n * (t * length), n, radius as f32, 48));
+            }
+            // Anticlockwise about n for a positive current
+            let (u, v) = perpendiculars(n);
+            let at = c0 + u * radius as f32;
+            draw_arrow_3d(at, at + v * (0.6 * current.signum() as f32), 0.2, col);
+        }
+    }
+}
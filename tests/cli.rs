@@ -0,0 +1,55 @@
+use coulomb3d::K;
+use std::path::PathBuf;
+use std::process::Command;
+
+const Q: f64 = 1e-6;
+
+/// A dipole along x: +Q at (1,0,0) and −Q at (−1,0,0), written to a fresh scene file.
+fn dipole_scene(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("coulomb3d_cli_{name}_{}.toml", std::process::id()));
+    let text = format!(
+        "version = 1\n\
+         [[charges]]\ncharge = {Q:e}\nposition = [1.0, 0.0, 0.0]\n\
+         [[charges]]\ncharge = {:e}\nposition = [-1.0, 0.0, 0.0]\n",
+        -Q
+    );
+    std::fs::write(&path, text).unwrap();
+    path
+}
+
+fn run(args: &[&str]) -> String {
+    let out = Command::new(env!("CARGO_BIN_EXE_Coulomb3D")).args(args).output().unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    String::from_utf8(out.stdout).unwrap()
+}
+
+#[test]
+fn eval_prints_the_potential_and_field() {
+    let scene = dipole_scene("text");
+    let out = run(&["eval", scene.to_str().unwrap(), "--at", "2,0,0"]);
+    std::fs::remove_file(&scene).unwrap();
+
+    // At (2,0,0): ϕ = KQ(1 − 1/3), E = KQ(1 − 1/9) along x
+    let phi = K * Q * 2.0 / 3.0;
+    let e = K * Q * 8.0 / 9.0;
+    assert!(out.starts_with("r'  = (2.0, 0.0, 0.0)\n"), "{out}");
+    assert!(out.contains(&format!("Phi = {phi:.4e} V\n")), "{out}");
+    assert!(out.contains(&format!("E   = ({e:.3e}, 0.000e0, 0.000e0) N/C\n|E| = {e:.4e} N/C")), "{out}");
+    assert!(out.contains("Forces on charges\nF1  = "), "{out}");
+}
+
+#[test]
+fn eval_accepts_options_before_the_command() {
+    let scene = dipole_scene("json");
+    let out = run(&["--format", "json", "--at", "2,0,0", "eval", scene.to_str().unwrap()]);
+    std::fs::remove_file(&scene).unwrap();
+
+    let report: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(report["charges"], 2);
+    let result = &report["results"][0];
+    let phi = result["potential"].as_f64().unwrap();
+    assert!((phi - K * Q * 2.0 / 3.0).abs() < 1e-9 * phi);
+    let e: Vec<f64> = result["field"].as_array().unwrap().iter().map(|v| v.as_f64().unwrap()).collect();
+    assert!((e[0] - K * Q * 8.0 / 9.0).abs() < 1e-9 * e[0]);
+    assert_eq!(e[1..], [0.0, 0.0]);
+}